@@ -1,7 +1,7 @@
 use clap::{Parser, Subcommand};
 use std::fs;
 
-use bdir_io::{core::Document, editpacket, patch};
+use bdir_io::{core::{migrate_legacy_hash_algorithm, Document, IdStrategy, MIN_HASH_PREFIX_LEN}, editpacket, patch};
 use bdir_io::document_json::parse_document_json_str;
 use jsonschema::Validator;
 use once_cell::sync::Lazy;
@@ -26,18 +26,31 @@ static PATCH_V1_SCHEMA: Lazy<Validator> = Lazy::new(|| {
     Validator::new(&schema).expect("failed to compile patch schema")
 });
 
+static PATCH_V2_SCHEMA: Lazy<Validator> = Lazy::new(|| {
+    let schema: Value = serde_json::from_str(include_str!("../../../spec/schemas/patch.v2.schema.json"))
+        .expect("invalid embedded patch.v2.schema.json");
+    Validator::new(&schema).expect("failed to compile patch v2 schema")
+});
+
 #[derive(Debug, Parser)]
 #[command(name = "bdir", version, about = "BDIR Patch Protocol MVP CLI")]
 struct Cli {
     #[command(subcommand)]
     cmd: Command,
+
+    /// Read `xxh64` inputs as written by engine versions that labeled xxh3 digests `xxh64`.
+    ///
+    /// Documents, edit packets and patches declaring `xxh64` are relabeled `xxh3`, so
+    /// their existing hashes and page-hash bindings remain valid.
+    #[arg(long = "legacy-xxh64", global = true)]
+    legacy_xxh64: bool,
 }
 
 #[derive(Debug, Subcommand)]
 enum Command {
     /// Inspect a Document JSON and print blocks in a deterministic tabular format.
     Inspect {
-        /// Input Document JSON path (bdir-core::Document)
+        /// Input Document JSON, Markdown (`.md`) or HTML (`.html`) path (bdir-core::Document)
         input: String,
 
         /// Filter by kindCode (repeatable). Supports single values and ranges like `2-5`.
@@ -51,11 +64,16 @@ enum Command {
         /// Filter by substring match on block text.
         #[arg(long)]
         grep: Option<String>,
+
+        /// Only show the section rooted at this block id (the block and every block
+        /// nested under it via `parent`).
+        #[arg(long)]
+        section: Option<String>,
     },
 
     /// Convert a Document JSON into an Edit Packet JSON.
     EditPacket {
-        /// Input Document JSON path (bdir-core::Document)
+        /// Input Document JSON, Markdown (`.md`) or HTML (`.html`) path (bdir-core::Document)
         input: String,
         /// Optional trace id to include in the packet
         #[arg(long)]
@@ -63,6 +81,9 @@ enum Command {
         /// Output minified JSON
         #[arg(long)]
         min: bool,
+        /// Emit truncated hash prefixes of N hex chars (RFC-0001 §5.1.4, N >= 8)
+        #[arg(long, value_name = "N")]
+        hash_prefix_len: Option<usize>,
     },
 
     ValidatePatch {
@@ -93,6 +114,11 @@ enum Command {
         #[arg(long = "kindcode-allow")]
         kindcode_allow: Vec<String>,
 
+        /// Allow `reclassify` ops to promote boilerplate (20-39) into core (0-19)
+        /// under --strict-kindcode.
+        #[arg(long = "allow-boilerplate-promotion")]
+        allow_boilerplate_promotion: bool,
+
         /// Expected page-level hash binding when the patch does not include `h`.
         ///
         /// When provided, validation/application will require the target edit packet
@@ -107,12 +133,35 @@ enum Command {
         #[arg(long = "strict-page-hash-binding")]
         strict_page_hash_binding: bool,
 
+        /// Reject ops whose target block `text_hash` does not match the block text.
+        #[arg(long = "verify-block-hashes")]
+        verify_block_hashes: bool,
+
+        /// Non-standard: when `before` does not occur verbatim, retry with whitespace
+        /// runs, smart quotes, dashes and NBSP normalized. Ops that needed it are
+        /// reported on stderr.
+        #[arg(long = "tolerant-matching")]
+        tolerant_matching: bool,
+
+        /// Restrict the patch to the section rooted at this block id; ops that reference
+        /// blocks outside it are rejected.
+        #[arg(long)]
+        section: Option<String>,
+
         /// Emit PatchTelemetry JSON to stderr (deterministic, machine-readable).
         #[arg(long = "telemetry-json")]
         telemetry_json: bool,
         /// Print machine-readable JSON diagnostics to stderr on validation failure.
         #[arg(long = "diagnostics-json")]
         diagnostics_json: bool,
+
+        /// Keep validating past the first failure and report every diagnostic.
+        ///
+        /// Diagnostics are ordered deterministically: page-level binding first,
+        /// then one per failing op in `ops` order. With `--telemetry-json` the report
+        /// lists every message under `errors`.
+        #[arg(long = "all-diagnostics")]
+        all_diagnostics: bool,
     },
 
     /// Apply a Patch.
@@ -122,13 +171,17 @@ enum Command {
     ///
     /// Document JSON in/out:
     ///   bdir apply-patch --doc <input.document.json> --patch <patch.json> --out <updated.document.json> [--min]
+    ///
+    /// Markdown in/out (edits are written back into the original file):
+    ///   bdir apply <input.md> <patch.json> --out <updated.md>
+    #[command(alias = "apply")]
     ApplyPatch {
         /// Input Edit Packet JSON path (bdir-patch::EditPacketV1)
         edit_packet: Option<String>,
         /// Patch JSON path (bdir-patch::PatchV1)
         patch_pos: Option<String>,
 
-        /// Input Document JSON path (bdir-core::Document)
+        /// Input Document JSON, Markdown (`.md`) or HTML (`.html`) path (bdir-core::Document)
         #[arg(long)]
         doc: Option<String>,
 
@@ -137,6 +190,8 @@ enum Command {
         patch_flag: Option<String>,
 
         /// Output file path (required for --doc mode). If omitted, prints to stdout.
+        ///
+        /// For Markdown input the output is the patched Markdown source.
         #[arg(long)]
         out: Option<String>,
 
@@ -152,6 +207,10 @@ enum Command {
         #[arg(long = "kindcode-allow")]
         kindcode_allow: Vec<String>,
 
+        /// Allow `reclassify` ops to promote boilerplate into core under --strict-kindcode.
+        #[arg(long = "allow-boilerplate-promotion")]
+        allow_boilerplate_promotion: bool,
+
         /// Expected page-level hash binding when the patch does not include `h`.
         #[arg(long = "expected-page-hash")]
         expected_page_hash: Option<String>,
@@ -163,14 +222,190 @@ enum Command {
         #[arg(long = "strict-page-hash-binding")]
         strict_page_hash_binding: bool,
 
+        /// Reject ops whose target block `text_hash` does not match the block text.
+        #[arg(long = "verify-block-hashes")]
+        verify_block_hashes: bool,
+
+        /// Non-standard: when `before` does not occur verbatim, retry with whitespace
+        /// runs, smart quotes, dashes and NBSP normalized. Ops that needed it are
+        /// reported on stderr.
+        #[arg(long = "tolerant-matching")]
+        tolerant_matching: bool,
+
+        /// Restrict the patch to the section rooted at this block id; ops that reference
+        /// blocks outside it are rejected.
+        #[arg(long)]
+        section: Option<String>,
+
+        /// Non-standard: let the engine assign ids to inserted and split-off blocks whose
+        /// op omits `new_block_id`: `positional` (`p3`), `content` (`p-1a2b3c4d`) or
+        /// `hierarchical` (`h2.p3`).
+        #[arg(long = "assign-block-ids")]
+        assign_block_ids: Option<IdStrategy>,
+
         /// Emit PatchTelemetry JSON to stderr (deterministic, machine-readable).
         #[arg(long = "telemetry-json")]
         telemetry_json: bool,
+        /// Print machine-readable JSON diagnostics to stderr on failure.
+        #[arg(long = "diagnostics-json")]
+        diagnostics_json: bool,
+    },
+
+    /// Produce a patch that undoes <patch> once it has been applied to <doc>.
+    ///
+    /// The inverse patch is bound to the updated document's page hash.
+    InvertPatch {
+        /// Original Document JSON path (bdir-core::Document)
+        #[arg(long)]
+        doc: String,
+
+        /// Forward Patch JSON path (bdir-patch::PatchV1)
+        #[arg(long)]
+        patch: String,
+
+        /// Output file path. If omitted, prints to stdout.
+        #[arg(long)]
+        out: Option<String>,
+
+        /// Output minified JSON
+        #[arg(long)]
+        min: bool,
+
+        /// Minimum length for `before` substrings (applies to the forward and inverse patch).
+        #[arg(long = "min-before-len")]
+        min_before_len: Option<usize>,
+    },
+
+    /// Upgrade a patch to the latest wire version (v2).
+    ///
+    /// The upgrade is lossless: the page-hash binding and every op are kept, and the
+    /// v1-only `occurrence: "first"` becomes `1`.
+    UpgradePatch {
+        /// Patch JSON path (any supported version)
+        #[arg(long)]
+        patch: String,
+
+        /// Output file path. If omitted, prints to stdout.
+        #[arg(long)]
+        out: Option<String>,
+
+        /// Output minified JSON
+        #[arg(long)]
+        min: bool,
+    },
+
+    /// Rebase a patch bound to <base> onto the <new> revision of the document.
+    ///
+    /// Writes the rebased patch (bound to the new page hash). Ops that no longer apply
+    /// are dropped and reported on stderr; the exit code is 2 when any op conflicted.
+    RebasePatch {
+        /// Base Document JSON path the patch was authored against
+        #[arg(long)]
+        base: String,
+
+        /// New Document JSON path to rebase onto
+        #[arg(long)]
+        new: String,
+
+        /// Patch JSON path (bound to the base document)
+        #[arg(long)]
+        patch: String,
+
+        /// Output file path for the rebased patch. If omitted, prints to stdout.
+        #[arg(long)]
+        out: Option<String>,
+
+        /// Write the full rebase report (patch + conflicts) as JSON to this path.
+        #[arg(long)]
+        report: Option<String>,
+
+        /// Output minified JSON
+        #[arg(long)]
+        min: bool,
+
+        /// Minimum length for `before` substrings.
+        #[arg(long = "min-before-len")]
+        min_before_len: Option<usize>,
+    },
+
+    /// Merge several patches proposed against the same Edit Packet.
+    ///
+    /// Writes the merged, canonicalized patch. Conflicting ops are left out and reported
+    /// on stderr; the exit code is 2 when any conflict was found.
+    MergePatches {
+        /// Input Edit Packet JSON path (bdir-patch::EditPacketV1)
+        edit_packet: String,
+
+        /// Patch JSON paths to merge (bdir-patch::PatchV1)
+        #[arg(required = true)]
+        patches: Vec<String>,
+
+        /// Output file path for the merged patch. If omitted, prints to stdout.
+        #[arg(long)]
+        out: Option<String>,
+
+        /// Write the full merge report (patch + conflicts) as JSON to this path.
+        #[arg(long)]
+        report: Option<String>,
+
+        /// Output minified JSON
+        #[arg(long)]
+        min: bool,
+
+        /// Minimum length for `before` substrings.
+        #[arg(long = "min-before-len")]
+        min_before_len: Option<usize>,
+    },
+
+    /// Express the difference between two Documents as a patch.
+    ///
+    /// Writes a patch bound to <original> that turns it into <updated>; the exit code is
+    /// 2 when the change cannot be expressed with patch v1 ops.
+    Diff {
+        /// Original Document JSON path (bdir-core::Document)
+        original: String,
+
+        /// Updated Document JSON path (bdir-core::Document)
+        updated: String,
+
+        /// Output file path. If omitted, prints to stdout.
+        #[arg(long)]
+        out: Option<String>,
+
+        /// Output minified JSON
+        #[arg(long)]
+        min: bool,
+
+        /// Minimum length for `before` substrings.
+        #[arg(long = "min-before-len")]
+        min_before_len: Option<usize>,
+    },
+
+    /// Render a Document as Markdown, HTML or plain text.
+    ///
+    /// Blocks are laid out by kind_code (headings, lists, tables, code); heading levels
+    /// follow the `parent` nesting. Output is deterministic.
+    Render {
+        /// Input Document JSON, Markdown (`.md`) or HTML (`.html`) path (bdir-core::Document)
+        input: String,
+
+        /// Output format: `md`, `html` or `txt`.
+        #[arg(long, default_value = "md")]
+        format: bdir_render::RenderFormat,
+
+        /// Output file path. If omitted, prints to stdout.
+        #[arg(long)]
+        out: Option<String>,
+
+        /// Only render core content (kindCodes 0-19), dropping boilerplate and UI chrome.
+        #[arg(long = "core-only")]
+        core_only: bool,
     },
 }
 
 fn main() -> anyhow::Result<()> {
     let cli = Cli::parse();
+    let legacy_xxh64 = cli.legacy_xxh64;
 
     match cli.cmd {
         Command::Inspect {
@@ -178,14 +413,28 @@ fn main() -> anyhow::Result<()> {
             kind_filters,
             id,
             grep,
+            section,
         } => {
             let s = fs::read_to_string(&input)?;
-            let mut doc: Document = parse_document_json_str(&s)?;
+            let mut doc: Document = parse_input_document(&input, &s)?;
+            if legacy_xxh64 {
+                doc.hash_algorithm = migrate_legacy_hash_algorithm(&doc.hash_algorithm);
+            }
 
             // Keep output stable and useful for patch targeting/debugging.
             doc.recompute_hashes();
 
             let kind_ranges = parse_kind_filters(&kind_filters)?;
+            let section_ids: Option<Vec<String>> = match section.as_deref() {
+                Some(root) => Some(
+                    doc.section_ids(root)
+                        .ok_or_else(|| anyhow::anyhow!("unknown section block id '{root}'"))?
+                        .into_iter()
+                        .map(str::to_string)
+                        .collect(),
+                ),
+                None => None,
+            };
 
             // TSV when non-interactive (tests/pipes), aligned table when interactive (terminal).
             let stdout = io::stdout();
@@ -202,15 +451,20 @@ fn main() -> anyhow::Result<()> {
                     {
                         continue;
                     }
-                    if let Some(ref want) = id {
-                        if &b.id != want {
-                            continue;
-                        }
+                    if let Some(ref want) = id
+                        && &b.id != want
+                    {
+                        continue;
                     }
-                    if let Some(ref needle) = grep {
-                        if !b.text.contains(needle) {
-                            continue;
-                        }
+                    if let Some(ref needle) = grep
+                        && !b.text.contains(needle)
+                    {
+                        continue;
+                    }
+                    if let Some(ref ids) = section_ids
+                        && !ids.contains(&b.id)
+                    {
+                        continue;
                     }
 
                     let preview = make_preview(&b.text, INSPECT_PREVIEW_MAX_CHARS);
@@ -239,11 +493,24 @@ fn main() -> anyhow::Result<()> {
             }
         }
 
-        Command::EditPacket { input, tid, min } => {
+        Command::EditPacket { input, tid, min, hash_prefix_len } => {
+            if let Some(n) = hash_prefix_len
+                && n < MIN_HASH_PREFIX_LEN
+            {
+                eprintln!("--hash-prefix-len must be at least {MIN_HASH_PREFIX_LEN} (got {n})");
+                std::process::exit(1);
+            }
             let s = fs::read_to_string(&input)?;
-            let mut doc: Document = parse_document_json_str(&s)?;
+            let mut doc: Document = parse_input_document(&input, &s)?;
+            if legacy_xxh64 {
+                doc.hash_algorithm = migrate_legacy_hash_algorithm(&doc.hash_algorithm);
+            }
             doc.recompute_hashes();
-            let packet = editpacket::from_document(&doc, tid);
+            let packet = editpacket::from_document_with_options(
+                &doc,
+                tid,
+                editpacket::EditPacketOptions { hash_prefix_len },
+            );
 
             let out = if min {
                 editpacket::to_minified_json(&packet)?
@@ -260,10 +527,15 @@ fn main() -> anyhow::Result<()> {
             min_before_len,
             strict_kindcode,
             kindcode_allow,
+            allow_boilerplate_promotion,
             expected_page_hash,
             diagnostics_json,
+            all_diagnostics,
             telemetry_json,
-            strict_page_hash_binding
+            strict_page_hash_binding,
+            verify_block_hashes,
+            tolerant_matching,
+            section,
         } => {
             use std::process;
 
@@ -284,13 +556,16 @@ fn main() -> anyhow::Result<()> {
             };
             validate_json_or_exit(&EDIT_PACKET_V1_SCHEMA, &packet_val);
 
-            let packet: editpacket::EditPacketV1 = match serde_json::from_value(packet_val) {
+            let mut packet: editpacket::EditPacketV1 = match serde_json::from_value(packet_val) {
                 Ok(p) => p,
                 Err(e) => {
                     eprintln!("{e}");
                     process::exit(1);
                 }
             };
+            if legacy_xxh64 {
+                packet.ha = migrate_legacy_hash_algorithm(&packet.ha);
+            }
 
             let patch_s = match fs::read_to_string(&patch) {
                 Ok(s) => s,
@@ -307,15 +582,10 @@ fn main() -> anyhow::Result<()> {
                     process::exit(1);
                 }
             };
-            validate_json_or_exit(&PATCH_V1_SCHEMA, &patch_val);
-
-            let patch: patch::PatchV1 = match serde_json::from_value(patch_val) {
-                Ok(p) => p,
-                Err(e) => {
-                    eprintln!("{e}");
-                    process::exit(1);
-                }
-            };
+            let mut patch = parse_patch_value_or_exit(patch_val).into_patch();
+            if legacy_xxh64 {
+                patch.ha = patch.ha.as_deref().map(migrate_legacy_hash_algorithm);
+            }
 
             let mut opts = patch::ValidateOptions::default();
             if let Some(n) = min_before_len {
@@ -328,6 +598,12 @@ fn main() -> anyhow::Result<()> {
             if strict_page_hash_binding {
                 opts.strict_page_hash_binding = true;
             }
+            opts.verify_block_hashes = verify_block_hashes;
+            opts.collect_all_diagnostics = all_diagnostics;
+            if tolerant_matching {
+                opts.match_mode = patch::MatchMode::Tolerant;
+            }
+            opts.section = section;
 
 
             if strict_kindcode {
@@ -340,10 +616,13 @@ fn main() -> anyhow::Result<()> {
                     opts.kind_code_policy = patch::KindCodePolicy {
                         allow_ranges: ranges,
                         allow_suggest_any: true,
+                        allow_boilerplate_promotion: false,
                     };
                 }
+                opts.kind_code_policy.allow_boilerplate_promotion = allow_boilerplate_promotion;
             }
 
+            let notes = patch::tolerant_match_diagnostics_against_edit_packet(&packet, &patch, &opts);
             let (res, tel) = patch::validate_patch_against_edit_packet_with_telemetry(&packet, &patch, opts);
 
             match res {
@@ -352,6 +631,7 @@ fn main() -> anyhow::Result<()> {
                         // Deterministic telemetry for monitoring / CI.
                         eprintln!("{}", serde_json::to_string(&tel).unwrap());
                     }
+                    report_tolerant_matches(&notes, telemetry_json, diagnostics_json);
                     println!("OK");
                     process::exit(0);
                 }
@@ -364,8 +644,16 @@ fn main() -> anyhow::Result<()> {
                         eprintln!("{}", serde_json::to_string(&diag).unwrap());
                     } else if telemetry_json {
                         // Keep stderr parseable: emit a single JSON object.
-                        let report = serde_json::json!({"telemetry": tel, "error": diag.legacy_message()});
+                        let mut report = serde_json::json!({"telemetry": tel, "error": diag.legacy_message()});
+                        if all_diagnostics {
+                            let errors: Vec<&str> = diag.diagnostics.iter().map(|d| d.message.as_str()).collect();
+                            report["errors"] = serde_json::json!(errors);
+                        }
                         eprintln!("{}", serde_json::to_string(&report).unwrap());
+                    } else if all_diagnostics {
+                        for d in &diag.diagnostics {
+                            eprintln!("{}", d.message);
+                        }
                     } else {
                         eprintln!("{}", diag.legacy_message());
                     }
@@ -383,9 +671,15 @@ fn main() -> anyhow::Result<()> {
             min,
             strict_kindcode,
             kindcode_allow,
+            allow_boilerplate_promotion,
             expected_page_hash,
             telemetry_json,
-            strict_page_hash_binding
+            diagnostics_json,
+            strict_page_hash_binding,
+            verify_block_hashes,
+            tolerant_matching,
+            section,
+            assign_block_ids,
         } => {
             use std::process;
 
@@ -397,6 +691,12 @@ fn main() -> anyhow::Result<()> {
             if strict_page_hash_binding {
                 opts.strict_page_hash_binding = true;
             }
+            opts.verify_block_hashes = verify_block_hashes;
+            if tolerant_matching {
+                opts.match_mode = patch::MatchMode::Tolerant;
+            }
+            opts.section = section;
+            opts.assign_new_block_ids = assign_block_ids;
 
             if strict_kindcode {
                 opts.strict_kind_code = true;
@@ -408,10 +708,18 @@ fn main() -> anyhow::Result<()> {
                     opts.kind_code_policy = patch::KindCodePolicy {
                         allow_ranges: ranges,
                         allow_suggest_any: true,
+                        allow_boilerplate_promotion: false,
                     };
                 }
+                opts.kind_code_policy.allow_boilerplate_promotion = allow_boilerplate_promotion;
             }
 
+            // `bdir apply input.md patch.json`: a Markdown first positional is the document.
+            let (doc, edit_packet) = match edit_packet {
+                Some(path) if doc.is_none() && is_markdown_path(&path) => (Some(path), None),
+                edit_packet => (doc, edit_packet),
+            };
+
             if let Some(doc_path) = doc {
                 // Document JSON pathway
                 if edit_packet.is_some() {
@@ -432,13 +740,28 @@ fn main() -> anyhow::Result<()> {
                     }
                 };
 
-                let mut doc: Document = match parse_document_json_str(&doc_s) {
+                // Markdown input is written back into its own source, so keep the spans.
+                let md_import = is_markdown_path(&doc_path).then(|| {
+                    bdir_markdown::import_markdown_with_spans(&doc_s, &bdir_markdown::MarkdownOptions::default())
+                        .unwrap_or_else(|e| {
+                            eprintln!("{e}");
+                            process::exit(1);
+                        })
+                });
+                let parsed = match &md_import {
+                    Some(import) => Ok(import.document.clone()),
+                    None => parse_input_document(&doc_path, &doc_s),
+                };
+                let mut doc: Document = match parsed {
                     Ok(d) => d,
                     Err(e) => {
                         eprintln!("{e}");
                         process::exit(1);
                     }
                 };
+                if legacy_xxh64 {
+                    doc.hash_algorithm = migrate_legacy_hash_algorithm(&doc.hash_algorithm);
+                }
 
                 // Ensure hashes are deterministic + consistent with the patch's expectations.
                 doc.recompute_hashes();
@@ -451,22 +774,20 @@ fn main() -> anyhow::Result<()> {
                     }
                 };
 
-                let patch_val: Value = match serde_json::from_str(&patch_s) {
+                let mut patch_val: Value = match serde_json::from_str(&patch_s) {
                     Ok(v) => v,
                     Err(e) => {
                         eprintln!("{e}");
                         process::exit(1);
                     }
                 };
-                validate_json_or_exit(&PATCH_V1_SCHEMA, &patch_val);
-
-                let patch: patch::PatchV1 = match serde_json::from_value(patch_val) {
-                    Ok(p) => p,
-                    Err(e) => {
-                        eprintln!("{e}");
-                        process::exit(1);
-                    }
-                };
+                if let Some(strategy) = assign_block_ids {
+                    fill_new_block_ids(&mut patch_val, |p| patch::assign_new_block_ids(&doc, p, strategy));
+                }
+                let mut patch = parse_patch_value_or_exit(patch_val).into_patch();
+                if legacy_xxh64 {
+                    patch.ha = patch.ha.as_deref().map(migrate_legacy_hash_algorithm);
+                }
 
                 let (res, tel) = patch::apply_patch_against_document_with_telemetry(&doc, &patch, opts.clone());
 
@@ -475,19 +796,33 @@ fn main() -> anyhow::Result<()> {
                         if telemetry_json {
                             eprintln!("{}", serde_json::to_string(&tel).unwrap());
                         }
+                        let notes = patch::tolerant_match_diagnostics(&doc, &patch, &opts);
+                        report_tolerant_matches(&notes, telemetry_json, diagnostics_json);
                         d
                     }
-                    Err(msg) => {
-                        if telemetry_json {
-                            let report = serde_json::json!({"telemetry": tel, "error": msg});
-                            eprintln!("{}", serde_json::to_string(&report).unwrap());
-                        } else {
-                            eprintln!("{msg}");
-                        }
+                    Err(err) => {
+                        report_apply_error(&err, &tel, telemetry_json, diagnostics_json);
                         process::exit(2);
                     }
                 };
 
+                if let Some(import) = &md_import {
+                    let markdown = bdir_markdown::write_back(&doc_s, import, &updated).unwrap_or_else(|e| {
+                        eprintln!("{e}");
+                        process::exit(2);
+                    });
+                    match out {
+                        Some(out_path) => {
+                            if let Err(e) = fs::write(&out_path, markdown) {
+                                eprintln!("{e}");
+                                process::exit(1);
+                            }
+                        }
+                        None => print!("{markdown}"),
+                    }
+                    process::exit(0);
+                }
+
                 let out_json = if min {
                     serde_json::to_string(&updated).unwrap()
                 } else {
@@ -534,13 +869,16 @@ fn main() -> anyhow::Result<()> {
             };
             validate_json_or_exit(&EDIT_PACKET_V1_SCHEMA, &packet_val);
 
-            let packet: editpacket::EditPacketV1 = match serde_json::from_value(packet_val) {
+            let mut packet: editpacket::EditPacketV1 = match serde_json::from_value(packet_val) {
                 Ok(p) => p,
                 Err(e) => {
                     eprintln!("{e}");
                     process::exit(1);
                 }
             };
+            if legacy_xxh64 {
+                packet.ha = migrate_legacy_hash_algorithm(&packet.ha);
+            }
 
             let patch_s = match fs::read_to_string(&patch_path) {
                 Ok(s) => s,
@@ -550,39 +888,36 @@ fn main() -> anyhow::Result<()> {
                 }
             };
 
-            let patch_val: Value = match serde_json::from_str(&patch_s) {
+            let mut patch_val: Value = match serde_json::from_str(&patch_s) {
                 Ok(v) => v,
                 Err(e) => {
                     eprintln!("{e}");
                     process::exit(1);
                 }
             };
-            validate_json_or_exit(&PATCH_V1_SCHEMA, &patch_val);
-
-            let patch: patch::PatchV1 = match serde_json::from_value(patch_val) {
-                Ok(p) => p,
-                Err(e) => {
-                    eprintln!("{e}");
-                    process::exit(1);
-                }
-            };
+            if let Some(strategy) = assign_block_ids {
+                fill_new_block_ids(&mut patch_val, |p| {
+                    patch::assign_new_block_ids_against_edit_packet(&packet, p, strategy)
+                });
+            }
+            let mut patch = parse_patch_value_or_exit(patch_val).into_patch();
+            if legacy_xxh64 {
+                patch.ha = patch.ha.as_deref().map(migrate_legacy_hash_algorithm);
+            }
 
-            let (res, tel) = patch::apply_patch_against_edit_packet_with_telemetry(&packet, &patch, opts);
+            let (res, tel) = patch::apply_patch_against_edit_packet_with_telemetry(&packet, &patch, opts.clone());
 
             let updated = match res {
                 Ok(p) => {
                     if telemetry_json {
                         eprintln!("{}", serde_json::to_string(&tel).unwrap());
                     }
+                    let notes = patch::tolerant_match_diagnostics_against_edit_packet(&packet, &patch, &opts);
+                    report_tolerant_matches(&notes, telemetry_json, diagnostics_json);
                     p
                 }
-                Err(msg) => {
-                    if telemetry_json {
-                        let report = serde_json::json!({"telemetry": tel, "error": msg});
-                        eprintln!("{}", serde_json::to_string(&report).unwrap());
-                    } else {
-                        eprintln!("{msg}");
-                    }
+                Err(err) => {
+                    report_apply_error(&err, &tel, telemetry_json, diagnostics_json);
                     process::exit(2);
                 }
             };
@@ -596,11 +931,345 @@ fn main() -> anyhow::Result<()> {
             println!("{out_json}");
             process::exit(0);
         }
+
+        Command::InvertPatch { doc, patch, out, min, min_before_len } => {
+            use std::process;
+
+            let doc = read_document_or_exit(&doc, legacy_xxh64);
+            let forward = read_patch_or_exit(&patch, legacy_xxh64);
+
+            let mut opts = patch::ValidateOptions::default();
+            if let Some(n) = min_before_len {
+                opts.min_before_len = n;
+            }
+
+            let inverse = match patch::invert_patch_with_options(&doc, &forward, opts) {
+                Ok(p) => p,
+                Err(e) => {
+                    eprintln!("{e}");
+                    process::exit(2);
+                }
+            };
+
+            write_json_or_exit(&inverse, out.as_deref(), min);
+        }
+
+        Command::UpgradePatch { patch, out, min } => {
+            let mut upgraded = read_versioned_patch_or_exit(&patch).upgrade();
+            if legacy_xxh64 {
+                upgraded.ha = upgraded.ha.as_deref().map(migrate_legacy_hash_algorithm);
+            }
+            write_json_or_exit(&upgraded, out.as_deref(), min);
+        }
+
+        Command::RebasePatch { base, new, patch, out, report, min, min_before_len } => {
+            use std::process;
+
+            let base = read_document_or_exit(&base, legacy_xxh64);
+            let new = read_document_or_exit(&new, legacy_xxh64);
+            let input = read_patch_or_exit(&patch, legacy_xxh64);
+
+            let mut opts = patch::ValidateOptions::default();
+            if let Some(n) = min_before_len {
+                opts.min_before_len = n;
+            }
+
+            let rebased = match patch::rebase_patch_with_options(&base, &new, &input, opts) {
+                Ok(r) => r,
+                Err(e) => {
+                    eprintln!("{}", e.legacy_message());
+                    process::exit(2);
+                }
+            };
+
+            write_json_or_exit(&rebased.patch, out.as_deref(), min);
+            if let Some(report_path) = report {
+                write_json_or_exit(&rebased, Some(&report_path), min);
+            }
+
+            for c in &rebased.conflicts {
+                eprintln!("{}", c.message);
+            }
+            if !rebased.is_clean() {
+                process::exit(2);
+            }
+        }
+
+        Command::MergePatches { edit_packet, patches, out, report, min, min_before_len } => {
+            use std::process;
+
+            let packet = read_edit_packet_or_exit(&edit_packet, legacy_xxh64);
+            let inputs: Vec<patch::PatchV1> =
+                patches.iter().map(|p| read_patch_or_exit(p, legacy_xxh64)).collect();
+
+            let mut opts = patch::ValidateOptions::default();
+            if let Some(n) = min_before_len {
+                opts.min_before_len = n;
+            }
+
+            let merged = patch::merge_patches_with_options(&packet, &inputs, opts);
+
+            write_json_or_exit(&merged.patch, out.as_deref(), min);
+            if let Some(report_path) = report {
+                write_json_or_exit(&merged, Some(&report_path), min);
+            }
+
+            for c in &merged.conflicts {
+                eprintln!("{}", c.message);
+            }
+            if !merged.is_clean() {
+                process::exit(2);
+            }
+        }
+
+        Command::Diff { original, updated, out, min, min_before_len } => {
+            use std::process;
+
+            let original = read_document_or_exit(&original, legacy_xxh64);
+            let updated = read_document_or_exit(&updated, legacy_xxh64);
+
+            let mut opts = patch::ValidateOptions::default();
+            if let Some(n) = min_before_len {
+                opts.min_before_len = n;
+            }
+
+            let diff = match patch::diff_documents_with_options(&original, &updated, opts) {
+                Ok(p) => p,
+                Err(e) => {
+                    eprintln!("{e}");
+                    process::exit(2);
+                }
+            };
+
+            write_json_or_exit(&diff, out.as_deref(), min);
+        }
+
+        Command::Render { input, format, out, core_only } => {
+            let doc = read_document_or_exit(&input, legacy_xxh64);
+            let opts = bdir_render::RenderOptions { core_only };
+            let rendered = bdir_render::render_with_options(&doc, format, &opts);
+
+            match out {
+                Some(path) => {
+                    if let Err(e) = fs::write(&path, rendered) {
+                        eprintln!("{e}");
+                        std::process::exit(1);
+                    }
+                }
+                None => print!("{rendered}"),
+            }
+        }
     }
 
     Ok(())
 }
 
+/// Read a Document JSON file, normalize its hash label and recompute hashes.
+///
+/// Exits with code 1 on IO or parse errors.
+fn read_document_or_exit(path: &str, legacy_xxh64: bool) -> Document {
+    let s = fs::read_to_string(path).unwrap_or_else(|e| {
+        eprintln!("{e}");
+        std::process::exit(1);
+    });
+    let mut doc = parse_input_document(path, &s).unwrap_or_else(|e| {
+        eprintln!("{e}");
+        std::process::exit(1);
+    });
+    if legacy_xxh64 {
+        doc.hash_algorithm = migrate_legacy_hash_algorithm(&doc.hash_algorithm);
+    }
+    doc.recompute_hashes();
+    doc
+}
+
+/// Parse the contents `s` of an input document at `path`.
+///
+/// Markdown files (`.md`, `.markdown`) are imported with `bdir_markdown` and HTML
+/// files (`.html`, `.htm`) with `bdir_html` (`html` feature); anything else is read as
+/// Document JSON.
+fn parse_input_document(path: &str, s: &str) -> anyhow::Result<Document> {
+    if is_markdown_path(path) {
+        Ok(bdir_markdown::parse_markdown_str(s))
+    } else if is_html_path(path) {
+        parse_html_input(s)
+    } else {
+        Ok(parse_document_json_str(s)?)
+    }
+}
+
+#[cfg(feature = "html")]
+fn parse_html_input(s: &str) -> anyhow::Result<Document> {
+    Ok(bdir_html::parse_html_str(s))
+}
+
+#[cfg(not(feature = "html"))]
+fn parse_html_input(_s: &str) -> anyhow::Result<Document> {
+    anyhow::bail!("HTML input requires bdir built with the `html` feature")
+}
+
+/// True for `.md` / `.markdown` paths (case-insensitive).
+fn is_markdown_path(path: &str) -> bool {
+    matches!(path_extension(path).as_deref(), Some("md" | "markdown"))
+}
+
+/// True for `.html` / `.htm` paths (case-insensitive).
+fn is_html_path(path: &str) -> bool {
+    matches!(path_extension(path).as_deref(), Some("html" | "htm"))
+}
+
+fn path_extension(path: &str) -> Option<String> {
+    std::path::Path::new(path)
+        .extension()
+        .and_then(|e| e.to_str())
+        .map(str::to_ascii_lowercase)
+}
+
+/// Read an Edit Packet JSON file and validate it against the embedded v1 schema.
+///
+/// Exits with code 1 on IO, parse or schema errors.
+fn read_edit_packet_or_exit(path: &str, legacy_xxh64: bool) -> editpacket::EditPacketV1 {
+    let s = fs::read_to_string(path).unwrap_or_else(|e| {
+        eprintln!("{e}");
+        std::process::exit(1);
+    });
+    let val: Value = serde_json::from_str(&s).unwrap_or_else(|e| {
+        eprintln!("{e}");
+        std::process::exit(1);
+    });
+    validate_json_or_exit(&EDIT_PACKET_V1_SCHEMA, &val);
+    let mut packet: editpacket::EditPacketV1 = serde_json::from_value(val).unwrap_or_else(|e| {
+        eprintln!("{e}");
+        std::process::exit(1);
+    });
+    if legacy_xxh64 {
+        packet.ha = migrate_legacy_hash_algorithm(&packet.ha);
+    }
+    packet
+}
+
+/// Validate a Patch JSON value against the embedded schema for its `v` and parse it.
+///
+/// Patches without a known `v` are checked against the v1 schema, which reports the
+/// offending field. Exits with code 1 on schema or parse errors.
+fn parse_patch_value_or_exit(val: Value) -> patch::VersionedPatch {
+    let schema: &Validator = match val.get("v").and_then(Value::as_u64) {
+        Some(2) => &PATCH_V2_SCHEMA,
+        _ => &PATCH_V1_SCHEMA,
+    };
+    validate_json_or_exit(schema, &val);
+    serde_json::from_value(val).unwrap_or_else(|e| {
+        eprintln!("{e}");
+        std::process::exit(1);
+    })
+}
+
+/// Fill in missing `new_block_id`s on a Patch JSON value with `assign`.
+///
+/// The patch schemas require `new_block_id`, so engine-assigned ids are written into the
+/// raw value before the schema check. Values that do not parse as a patch are left for
+/// `parse_patch_value_or_exit` to report.
+fn fill_new_block_ids(val: &mut Value, assign: impl FnOnce(&patch::PatchV1) -> patch::PatchV1) {
+    let Ok(parsed) = serde_json::from_value::<patch::VersionedPatch>(val.clone()) else {
+        return;
+    };
+    let assigned = assign(&parsed.into_patch());
+    let Some(ops) = val.get_mut("ops").and_then(Value::as_array_mut) else {
+        return;
+    };
+    for (op, filled) in ops.iter_mut().zip(&assigned.ops) {
+        if let (Some(op), Some(id)) = (op.as_object_mut(), &filled.new_block_id) {
+            op.entry("new_block_id").or_insert_with(|| Value::String(id.clone()));
+        }
+    }
+}
+
+/// Read a versioned Patch JSON file and validate it against the embedded schema for its `v`.
+///
+/// Exits with code 1 on IO, parse or schema errors.
+fn read_versioned_patch_or_exit(path: &str) -> patch::VersionedPatch {
+    let s = fs::read_to_string(path).unwrap_or_else(|e| {
+        eprintln!("{e}");
+        std::process::exit(1);
+    });
+    let val: Value = serde_json::from_str(&s).unwrap_or_else(|e| {
+        eprintln!("{e}");
+        std::process::exit(1);
+    });
+    parse_patch_value_or_exit(val)
+}
+
+/// Read a Patch JSON file (any supported version) into the engine's patch model.
+///
+/// Exits with code 1 on IO, parse or schema errors.
+fn read_patch_or_exit(path: &str, legacy_xxh64: bool) -> patch::PatchV1 {
+    let mut patch = read_versioned_patch_or_exit(path).into_patch();
+    if legacy_xxh64 {
+        patch.ha = patch.ha.as_deref().map(migrate_legacy_hash_algorithm);
+    }
+    patch
+}
+
+/// Serialize `value` as JSON to `out` (or stdout when `None`).
+///
+/// Exits with code 1 if the output file cannot be written.
+fn write_json_or_exit<T: serde::Serialize>(value: &T, out: Option<&str>, min: bool) {
+    let json = if min {
+        serde_json::to_string(value).unwrap()
+    } else {
+        serde_json::to_string_pretty(value).unwrap()
+    };
+
+    match out {
+        Some(path) => {
+            if let Err(e) = fs::write(path, json) {
+                eprintln!("{e}");
+                std::process::exit(1);
+            }
+        }
+        None => println!("{json}"),
+    }
+}
+
+/// Print an apply failure to stderr in the same formats `validate-patch` uses.
+fn report_apply_error(
+    err: &patch::ApplyError,
+    tel: &bdir_io::prelude::PatchTelemetry,
+    telemetry_json: bool,
+    diagnostics_json: bool,
+) {
+    if diagnostics_json && telemetry_json {
+        // Combined machine-readable report.
+        let report = serde_json::json!({"telemetry": tel, "diagnostics": err.diagnostics()});
+        eprintln!("{}", serde_json::to_string(&report).unwrap());
+    } else if diagnostics_json {
+        eprintln!("{}", serde_json::to_string(&err.diagnostics()).unwrap());
+    } else if telemetry_json {
+        // Keep stderr parseable: emit a single JSON object.
+        let report = serde_json::json!({"telemetry": tel, "error": err.to_string()});
+        eprintln!("{}", serde_json::to_string(&report).unwrap());
+    } else {
+        eprintln!("{err}");
+    }
+}
+
+/// Report ops that only matched under `--tolerant-matching` (success path).
+///
+/// With `--telemetry-json` stderr stays a single JSON object; the telemetry already
+/// lists the ops in `tolerant_match_ops`.
+fn report_tolerant_matches(notes: &[patch::ValidationDiagnostic], telemetry_json: bool, diagnostics_json: bool) {
+    if notes.is_empty() || telemetry_json {
+        return;
+    }
+    if diagnostics_json {
+        eprintln!("{}", serde_json::to_string(&serde_json::json!({ "diagnostics": notes })).unwrap());
+    } else {
+        for note in notes {
+            eprintln!("note: {}", note.message);
+        }
+    }
+}
+
 fn validate_json_or_exit(validator: &Validator, instance: &Value) {
     let errors: Vec<_> = validator.iter_errors(instance).collect();
     if errors.is_empty() {
@@ -621,7 +1290,7 @@ fn make_preview(s: &str, max_chars: usize) -> String {
     let mut truncated = false;
 
     let mut it = s.chars().peekable();
-    while let Some(ch) = it.next() {
+    for ch in it.by_ref() {
         if ch.is_whitespace() {
             prev_was_ws = true;
             continue;
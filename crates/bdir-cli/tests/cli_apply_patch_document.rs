@@ -56,3 +56,90 @@ fn cli_apply_patch_document_writes_updated_document_json() {
 
     let _ = fs::remove_file(&out_path);
 }
+
+#[test]
+fn cli_apply_patch_document_legacy_xxh64_keeps_old_bindings_valid() {
+    use std::fs;
+    use std::time::{SystemTime, UNIX_EPOCH};
+
+    let input = example_document_path();
+
+    let pid = std::process::id();
+    let nanos = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap()
+        .as_nanos();
+
+    // Patch bound to the page hash that older engines computed (xxh3 labeled "xxh64").
+    let patch_path = std::env::temp_dir().join(format!("bdir_legacy_patch_{pid}_{nanos}.json"));
+    let patch_json = r#"{
+      "v": 1,
+      "h": "4a0d9b1ad0795617",
+      "ha": "xxh64",
+      "ops": [
+        {"op": "replace", "block_id": "p1", "before": "example paragraph with a typo", "after": "example paragraph with a typo: the"}
+      ]
+    }"#;
+    fs::write(&patch_path, patch_json).unwrap();
+
+    // Without the compatibility flag the binding no longer matches.
+    let mut cmd = cargo_bin_cmd!("bdir");
+    cmd.args([
+        "apply-patch",
+        "--doc",
+        input.to_str().unwrap(),
+        "--patch",
+        patch_path.to_str().unwrap(),
+    ]);
+    cmd.assert().failure().code(2);
+
+    let mut cmd = cargo_bin_cmd!("bdir");
+    cmd.args([
+        "apply-patch",
+        "--legacy-xxh64",
+        "--doc",
+        input.to_str().unwrap(),
+        "--patch",
+        patch_path.to_str().unwrap(),
+    ]);
+    let output = cmd.assert().success().get_output().stdout.clone();
+    let updated: Document = serde_json::from_slice(&output).unwrap();
+    assert_eq!(updated.hash_algorithm, "xxh3");
+
+    let _ = fs::remove_file(&patch_path);
+}
+
+#[test]
+fn cli_apply_patch_document_section_rejects_ops_outside_it() {
+    use std::fs;
+    use std::time::{SystemTime, UNIX_EPOCH};
+
+    let input = patch_fixture_path("document.sections.json");
+
+    let pid = std::process::id();
+    let nanos = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap()
+        .as_nanos();
+
+    let patch_path = std::env::temp_dir().join(format!("bdir_section_patch_{pid}_{nanos}.json"));
+    let mut doc: Document = serde_json::from_str(&fs::read_to_string(&input).unwrap()).unwrap();
+    doc.recompute_hashes();
+    let patch_json = serde_json::json!({
+        "v": 1,
+        "h": doc.page_hash,
+        "ha": doc.hash_algorithm,
+        "ops": [{ "op": "suggest", "block_id": "p3", "message": "Mention leashes." }]
+    });
+    fs::write(&patch_path, patch_json.to_string()).unwrap();
+
+    let mut cmd = cargo_bin_cmd!("bdir");
+    cmd.args(["apply-patch", "--doc", input.to_str().unwrap(), "--patch", patch_path.to_str().unwrap()]);
+    cmd.args(["--section", "h1"]);
+    cmd.assert()
+        .failure()
+        .code(2)
+        .stderr(predicates::str::contains("references a block outside section 'h1' ('p3')"));
+
+    let _ = fs::remove_file(&patch_path);
+}
@@ -38,3 +38,21 @@ fn cli_apply_patch_edit_packet_stdout_is_updated_edit_packet() {
     let p1 = updated.b.iter().find(|t| t.0 == "p1").unwrap();
     assert!(p1.3.contains("example paragraph with a typo: the"));
 }
+
+#[test]
+fn cli_apply_patch_edit_packet_can_emit_structured_diagnostics_json() {
+    let packet = edit_packet_path();
+    let patch = patch_fixture_path("patch.before_too_short.json");
+
+    let mut cmd = cargo_bin_cmd!("bdir");
+    cmd.args([
+        "apply-patch",
+        packet.to_str().unwrap(),
+        patch.to_str().unwrap(),
+        "--diagnostics-json",
+    ]);
+
+    let output = cmd.assert().failure().code(2).get_output().stderr.clone();
+    let report: bdir_io::prelude::ValidationError = serde_json::from_slice(&output).unwrap();
+    assert_eq!(report.diagnostics[0].code, bdir_io::prelude::DiagnosticCode::BeforeTooShort);
+}
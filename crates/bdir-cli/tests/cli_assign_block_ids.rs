@@ -0,0 +1,93 @@
+use assert_cmd::cargo::cargo_bin_cmd;
+
+use bdir_io::prelude::Document;
+
+fn example_path(file: &str) -> std::path::PathBuf {
+    std::path::Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("..")
+        .join("..")
+        .join("examples")
+        .join(file)
+}
+
+fn temp_path(name: &str) -> std::path::PathBuf {
+    use std::time::{SystemTime, UNIX_EPOCH};
+
+    let nanos = SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_nanos();
+    std::env::temp_dir().join(format!("bdir_assign_ids_{}_{nanos}_{name}", std::process::id()))
+}
+
+/// Patch inserting a paragraph after `p1` without a `new_block_id`.
+fn write_insert_patch() -> std::path::PathBuf {
+    let doc_path = example_path("document.json");
+    let mut doc: Document = serde_json::from_str(&std::fs::read_to_string(doc_path).unwrap()).unwrap();
+    doc.recompute_hashes();
+    let patch = serde_json::json!({
+        "v": 1,
+        "h": doc.page_hash,
+        "ha": doc.hash_algorithm,
+        "ops": [{ "op": "insert_after", "block_id": "p1", "kind_code": 2, "text": "A new paragraph." }]
+    });
+    let path = temp_path("patch.json");
+    std::fs::write(&path, patch.to_string()).unwrap();
+    path
+}
+
+#[test]
+fn cli_apply_patch_rejects_missing_new_block_id_by_default() {
+    let patch = write_insert_patch();
+
+    let mut cmd = cargo_bin_cmd!("bdir");
+    cmd.args([
+        "apply-patch",
+        "--doc",
+        example_path("document.json").to_str().unwrap(),
+        "--patch",
+        patch.to_str().unwrap(),
+    ]);
+    cmd.assert().code(1).stderr(predicates::str::contains("new_block_id"));
+
+    let _ = std::fs::remove_file(&patch);
+}
+
+#[test]
+fn cli_apply_patch_assigns_block_ids_for_documents_and_edit_packets() {
+    let patch = write_insert_patch();
+
+    let mut cmd = cargo_bin_cmd!("bdir");
+    cmd.args([
+        "apply-patch",
+        "--doc",
+        example_path("document.json").to_str().unwrap(),
+        "--patch",
+        patch.to_str().unwrap(),
+        "--assign-block-ids",
+        "positional",
+    ]);
+    let out = cmd.assert().success().get_output().stdout.clone();
+    let updated: Document = serde_json::from_slice(&out).unwrap();
+    let ids: Vec<&str> = updated.blocks.iter().map(|b| b.id.as_str()).collect();
+    assert_eq!(ids, vec!["t1", "p1", "p2", "b1"]);
+
+    let mut cmd = cargo_bin_cmd!("bdir");
+    cmd.args([
+        "apply-patch",
+        example_path("edit-packet.json").to_str().unwrap(),
+        patch.to_str().unwrap(),
+        "--assign-block-ids",
+        "content",
+    ]);
+    let out = cmd.assert().success().get_output().stdout.clone();
+    let packet: serde_json::Value = serde_json::from_slice(&out).unwrap();
+    let new_id = packet["b"][2][0].as_str().unwrap();
+    assert!(new_id.starts_with("p-") && new_id.len() == 10, "unexpected id {new_id}");
+
+    let _ = std::fs::remove_file(&patch);
+}
+
+#[test]
+fn cli_apply_patch_rejects_unknown_id_strategies() {
+    let mut cmd = cargo_bin_cmd!("bdir");
+    cmd.args(["apply-patch", "--doc", "doc.json", "--patch", "patch.json", "--assign-block-ids", "random"]);
+    cmd.assert().failure().stderr(predicates::str::contains("unknown id strategy 'random'"));
+}
@@ -0,0 +1,81 @@
+use assert_cmd::cargo::cargo_bin_cmd;
+
+use bdir_io::prelude::{OpType, PatchV1};
+
+fn example_document_path() -> std::path::PathBuf {
+    std::path::Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("..")
+        .join("..")
+        .join("examples")
+        .join("document.json")
+}
+
+/// Write a copy of the example document with its blocks modified by `edit`.
+fn write_updated_document(tag: &str, edit: impl FnOnce(&mut Vec<serde_json::Value>)) -> std::path::PathBuf {
+    let mut doc: serde_json::Value =
+        serde_json::from_str(&std::fs::read_to_string(example_document_path()).unwrap()).unwrap();
+    edit(doc["blocks"].as_array_mut().unwrap());
+
+    let path = std::env::temp_dir().join(format!("bdir_diff_{tag}_{}.json", std::process::id()));
+    std::fs::write(&path, serde_json::to_string(&doc).unwrap()).unwrap();
+    path
+}
+
+#[test]
+fn cli_diff_emits_patch_bound_to_original() {
+    let original = example_document_path();
+    let updated = write_updated_document("ok", |blocks| {
+        blocks[1]["text"] = "This is an example paragraph with a typo the.".into();
+    });
+
+    let output = cargo_bin_cmd!("bdir")
+        .args(["diff", original.to_str().unwrap(), updated.to_str().unwrap()])
+        .assert()
+        .success()
+        .get_output()
+        .stdout
+        .clone();
+
+    let patch: PatchV1 = serde_json::from_slice(&output).unwrap();
+    assert_eq!(patch.h.as_deref(), Some("ff0e1875ab7f8425"));
+    assert_eq!(patch.ops.len(), 1);
+    assert_eq!(patch.ops[0].before.as_deref(), Some("typo teh."));
+
+    let _ = std::fs::remove_file(updated);
+}
+
+#[test]
+fn cli_diff_removed_block_becomes_delete_block() {
+    let original = example_document_path();
+    let updated = write_updated_document("removed", |blocks| blocks.retain(|b| b["id"] != "b1"));
+
+    let output = cargo_bin_cmd!("bdir")
+        .args(["diff", original.to_str().unwrap(), updated.to_str().unwrap()])
+        .assert()
+        .success()
+        .get_output()
+        .stdout
+        .clone();
+
+    let patch: PatchV1 = serde_json::from_slice(&output).unwrap();
+    assert_eq!(patch.ops.len(), 1);
+    assert_eq!(patch.ops[0].op, OpType::DeleteBlock);
+    assert_eq!(patch.ops[0].block_id, "b1");
+
+    let _ = std::fs::remove_file(updated);
+}
+
+#[test]
+fn cli_diff_unrepresentable_change_exits_2() {
+    let original = example_document_path();
+    let updated = write_updated_document("duplicate", |blocks| blocks.push(blocks[1].clone()));
+
+    cargo_bin_cmd!("bdir")
+        .args(["diff", original.to_str().unwrap(), updated.to_str().unwrap()])
+        .assert()
+        .failure()
+        .code(2)
+        .stderr("block id 'p1' appears more than once in the updated document\n");
+
+    let _ = std::fs::remove_file(updated);
+}
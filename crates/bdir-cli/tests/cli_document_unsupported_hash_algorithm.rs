@@ -29,7 +29,7 @@ fn unsupported_hash_algorithm_is_rejected() {
     cmd.assert()
         .failure()
         .stderr(contains("Unsupported hash_algorithm"))
-        .stderr(contains("Supported algorithms"));
+        .stderr(contains("Supported algorithms: blake3, sha256, sha512_256, xxh3, xxh64."));
 
     let _ = fs::remove_file(&path);
 }
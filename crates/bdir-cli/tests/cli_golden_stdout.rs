@@ -20,25 +20,25 @@ fn cli_edit_packet_pretty_stdout_golden() {
         .stdout(
             r#"{
   "v": 1,
-  "h": "4a0d9b1ad0795617",
+  "h": "ff0e1875ab7f8425",
   "ha": "xxh64",
   "b": [
     [
       "t1",
       0,
-      "2d85646dba5758f4",
+      "f932d646637097b0",
       "Example Page Title"
     ],
     [
       "p1",
       2,
-      "a3c9cb84972dd67e",
+      "6dcea198d9a3abcd",
       "This is an example paragraph with a typo teh."
     ],
     [
       "b1",
       20,
-      "7a6ea7f684209672",
+      "4f7d6d674ab4edac",
       "Home > Section > Page"
     ]
   ]
@@ -59,7 +59,35 @@ fn cli_edit_packet_minified_stdout_golden() {
         // NOTE: println! adds a trailing newline. If you switch to print! in the CLI,
         // remove the trailing "\n" here.
         .stdout(
-            r#"{"v":1,"h":"4a0d9b1ad0795617","ha":"xxh64","b":[["t1",0,"2d85646dba5758f4","Example Page Title"],["p1",2,"a3c9cb84972dd67e","This is an example paragraph with a typo teh."],["b1",20,"7a6ea7f684209672","Home > Section > Page"]]}
+            r#"{"v":1,"h":"ff0e1875ab7f8425","ha":"xxh64","b":[["t1",0,"f932d646637097b0","Example Page Title"],["p1",2,"6dcea198d9a3abcd","This is an example paragraph with a typo teh."],["b1",20,"4f7d6d674ab4edac","Home > Section > Page"]]}
 "#,
         );
 }
+
+#[test]
+fn cli_edit_packet_hash_prefix_len_truncates_hashes() {
+    let input = example_document_path();
+
+    let mut cmd = cargo_bin_cmd!("bdir");
+    cmd.args(["edit-packet", input.to_str().unwrap(), "--min", "--hash-prefix-len", "8"]);
+
+    cmd.assert()
+        .success()
+        .stdout(
+            r#"{"v":1,"h":"ff0e1875","ha":"xxh64","b":[["t1",0,"f932d646","Example Page Title"],["p1",2,"6dcea198","This is an example paragraph with a typo teh."],["b1",20,"4f7d6d67","Home > Section > Page"]]}
+"#,
+        );
+}
+
+#[test]
+fn cli_edit_packet_hash_prefix_len_below_minimum_fails() {
+    let input = example_document_path();
+
+    let mut cmd = cargo_bin_cmd!("bdir");
+    cmd.args(["edit-packet", input.to_str().unwrap(), "--hash-prefix-len", "7"]);
+
+    cmd.assert()
+        .failure()
+        .code(1)
+        .stderr("--hash-prefix-len must be at least 8 (got 7)\n");
+}
@@ -0,0 +1,25 @@
+#![cfg(feature = "html")]
+
+use assert_cmd::cargo::cargo_bin_cmd;
+
+#[test]
+fn cli_inspect_accepts_html_input_and_filters_chrome() {
+    let input = std::path::Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("..")
+        .join("bdir-html")
+        .join("tests")
+        .join("fixtures")
+        .join("article.html");
+
+    let mut cmd = cargo_bin_cmd!("bdir");
+    cmd.args(["inspect", input.to_str().unwrap(), "--kind", "20-59"]);
+    let out = cmd.assert().success().get_output().stdout.clone();
+    let kinds: Vec<String> = String::from_utf8(out)
+        .unwrap()
+        .lines()
+        .skip(1)
+        .map(|l| l.split('\t').nth(1).unwrap().to_string())
+        .collect();
+
+    assert_eq!(kinds, ["24", "21", "20", "40", "22", "22", "23", "42", "40"]);
+}
@@ -17,9 +17,9 @@ fn cli_inspect_stdout_golden() {
 
     cmd.assert().success().stdout(
         "blockId\tkindCode\timportance\ttextHash\tpreview\n\
-t1\t0\tcore\t2d85646dba5758f4\tExample Page Title\n\
-p1\t2\tcore\ta3c9cb84972dd67e\tThis is an example paragraph with a typo teh.\n\
-b1\t20\tboilerplate\t7a6ea7f684209672\tHome > Section > Page\n",
+t1\t0\tcore\tf932d646637097b0\tExample Page Title\n\
+p1\t2\tcore\t6dcea198d9a3abcd\tThis is an example paragraph with a typo teh.\n\
+b1\t20\tboilerplate\t4f7d6d674ab4edac\tHome > Section > Page\n",
     );
 }
 
@@ -32,7 +32,7 @@ fn cli_inspect_filters_work() {
     cmd.args(["inspect", input.to_str().unwrap(), "--kind", "0"]);
     cmd.assert().success().stdout(
         "blockId\tkindCode\timportance\ttextHash\tpreview\n\
-t1\t0\tcore\t2d85646dba5758f4\tExample Page Title\n",
+t1\t0\tcore\tf932d646637097b0\tExample Page Title\n",
     );
 
     // --kind range
@@ -40,8 +40,8 @@ t1\t0\tcore\t2d85646dba5758f4\tExample Page Title\n",
     cmd.args(["inspect", input.to_str().unwrap(), "--kind", "0-2"]);
     cmd.assert().success().stdout(
         "blockId\tkindCode\timportance\ttextHash\tpreview\n\
-t1\t0\tcore\t2d85646dba5758f4\tExample Page Title\n\
-p1\t2\tcore\ta3c9cb84972dd67e\tThis is an example paragraph with a typo teh.\n",
+t1\t0\tcore\tf932d646637097b0\tExample Page Title\n\
+p1\t2\tcore\t6dcea198d9a3abcd\tThis is an example paragraph with a typo teh.\n",
     );
 
     // --id exact
@@ -49,7 +49,7 @@ p1\t2\tcore\ta3c9cb84972dd67e\tThis is an example paragraph with a typo teh.\n",
     cmd.args(["inspect", input.to_str().unwrap(), "--id", "b1"]);
     cmd.assert().success().stdout(
         "blockId\tkindCode\timportance\ttextHash\tpreview\n\
-b1\t20\tboilerplate\t7a6ea7f684209672\tHome > Section > Page\n",
+b1\t20\tboilerplate\t4f7d6d674ab4edac\tHome > Section > Page\n",
     );
 
     // --grep substring
@@ -57,7 +57,7 @@ b1\t20\tboilerplate\t7a6ea7f684209672\tHome > Section > Page\n",
     cmd.args(["inspect", input.to_str().unwrap(), "--grep", "typo"]);
     cmd.assert().success().stdout(
         "blockId\tkindCode\timportance\ttextHash\tpreview\n\
-p1\t2\tcore\ta3c9cb84972dd67e\tThis is an example paragraph with a typo teh.\n",
+p1\t2\tcore\t6dcea198d9a3abcd\tThis is an example paragraph with a typo teh.\n",
     );
 }
 
@@ -98,3 +98,28 @@ fn cli_inspect_preview_is_bounded() {
 
     let _ = fs::remove_file(&path);
 }
+
+#[test]
+fn cli_inspect_section_filter_shows_nested_blocks() {
+    let input = std::path::Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("..")
+        .join("bdir-patch")
+        .join("tests")
+        .join("fixtures")
+        .join("document.sections.json");
+
+    let mut cmd = cargo_bin_cmd!("bdir");
+    cmd.args(["inspect", input.to_str().unwrap(), "--section", "h1a"]);
+    let out = cmd.assert().success().get_output().stdout.clone();
+    let ids: Vec<String> = String::from_utf8(out)
+        .unwrap()
+        .lines()
+        .skip(1)
+        .map(|l| l.split('\t').next().unwrap().to_string())
+        .collect();
+    assert_eq!(ids, ["h1a", "p2"]);
+
+    let mut cmd = cargo_bin_cmd!("bdir");
+    cmd.args(["inspect", input.to_str().unwrap(), "--section", "missing"]);
+    cmd.assert().failure().stderr(predicates::str::contains("unknown section block id 'missing'"));
+}
@@ -0,0 +1,96 @@
+use assert_cmd::cargo::cargo_bin_cmd;
+
+use bdir_io::prelude::Document;
+
+fn example_document_path() -> std::path::PathBuf {
+    std::path::Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("..")
+        .join("..")
+        .join("examples")
+        .join("document.json")
+}
+
+fn patch_fixture_path(file: &str) -> std::path::PathBuf {
+    std::path::Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("..")
+        .join("bdir-patch")
+        .join("tests")
+        .join("fixtures")
+        .join(file)
+}
+
+fn temp_path(tag: &str) -> std::path::PathBuf {
+    use std::time::{SystemTime, UNIX_EPOCH};
+
+    let pid = std::process::id();
+    let nanos = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap()
+        .as_nanos();
+    std::env::temp_dir().join(format!("bdir_invert_{tag}_{pid}_{nanos}.json"))
+}
+
+#[test]
+fn cli_invert_patch_round_trips_through_apply_patch() {
+    use std::fs;
+
+    let input = example_document_path();
+    let forward = patch_fixture_path("patch.valid.json");
+    let updated_path = temp_path("updated");
+    let inverse_path = temp_path("inverse");
+    let restored_path = temp_path("restored");
+
+    cargo_bin_cmd!("bdir")
+        .args(["apply-patch", "--doc", input.to_str().unwrap(), "--patch", forward.to_str().unwrap()])
+        .args(["--out", updated_path.to_str().unwrap()])
+        .assert()
+        .success();
+
+    cargo_bin_cmd!("bdir")
+        .args(["invert-patch", "--doc", input.to_str().unwrap(), "--patch", forward.to_str().unwrap()])
+        .args(["--out", inverse_path.to_str().unwrap()])
+        .assert()
+        .success();
+
+    cargo_bin_cmd!("bdir")
+        .args(["apply-patch", "--doc", updated_path.to_str().unwrap(), "--patch", inverse_path.to_str().unwrap()])
+        .args(["--out", restored_path.to_str().unwrap()])
+        .assert()
+        .success();
+
+    let original: Document = serde_json::from_str(&fs::read_to_string(&input).unwrap()).unwrap();
+    let restored: Document = serde_json::from_str(&fs::read_to_string(&restored_path).unwrap()).unwrap();
+    let texts = |d: &Document| d.blocks.iter().map(|b| b.text.clone()).collect::<Vec<_>>();
+    assert_eq!(texts(&restored), texts(&original));
+
+    for p in [updated_path, inverse_path, restored_path] {
+        let _ = fs::remove_file(p);
+    }
+}
+
+#[test]
+fn cli_invert_patch_deleting_every_block_exits_2() {
+    let input = example_document_path();
+    let patch = std::env::temp_dir().join(format!("bdir_invert_delete_all_{}.json", std::process::id()));
+    std::fs::write(
+        &patch,
+        concat!(
+            r#"{"v":1,"h":"ff0e1875ab7f8425","ha":"xxh64","ops":["#,
+            r#"{"op":"delete_block","block_id":"t1","text_hash":"f932d646637097b0"},"#,
+            r#"{"op":"delete_block","block_id":"p1","text_hash":"6dcea198d9a3abcd"},"#,
+            r#"{"op":"delete_block","block_id":"b1","text_hash":"4f7d6d674ab4edac"}]}"#,
+        ),
+    )
+    .unwrap();
+
+    cargo_bin_cmd!("bdir")
+        .args(["invert-patch", "--doc", input.to_str().unwrap(), "--patch", patch.to_str().unwrap()])
+        .assert()
+        .failure()
+        .code(2)
+        .stderr(
+            "ops[0] (delete_block) cannot be inverted: no block of the original document survives to anchor block 't1'\n",
+        );
+
+    let _ = std::fs::remove_file(patch);
+}
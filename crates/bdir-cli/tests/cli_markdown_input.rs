@@ -0,0 +1,96 @@
+use assert_cmd::cargo::cargo_bin_cmd;
+use serde_json::Value;
+
+fn markdown_fixture_path(file: &str) -> std::path::PathBuf {
+    std::path::Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("..")
+        .join("bdir-markdown")
+        .join("tests")
+        .join("fixtures")
+        .join(file)
+}
+
+#[test]
+fn cli_inspect_accepts_markdown_input() {
+    let input = markdown_fixture_path("guide.md");
+
+    let mut cmd = cargo_bin_cmd!("bdir");
+    cmd.args(["inspect", input.to_str().unwrap(), "--kind", "0-1"]);
+    let out = cmd.assert().success().get_output().stdout.clone();
+    let rows: Vec<Vec<String>> = String::from_utf8(out)
+        .unwrap()
+        .lines()
+        .skip(1)
+        .map(|l| l.split('\t').map(str::to_string).collect())
+        .collect();
+
+    let ids_and_previews: Vec<(&str, &str)> = rows.iter().map(|r| (r[0].as_str(), r[4].as_str())).collect();
+    assert_eq!(
+        ids_and_previews,
+        [("t1", "Field Guide"), ("h1", "Care"), ("h2", "Grooming tools"), ("h3", "Training")]
+    );
+}
+
+#[test]
+fn cli_edit_packet_accepts_markdown_input() {
+    let input = markdown_fixture_path("guide.md");
+
+    let mut cmd = cargo_bin_cmd!("bdir");
+    cmd.args(["edit-packet", input.to_str().unwrap(), "--min"]);
+    let out = cmd.assert().success().get_output().stdout.clone();
+    let packet: Value = serde_json::from_slice(&out).unwrap();
+
+    assert_eq!(packet["ha"], "sha256");
+    assert_eq!(packet["b"][1][0], "p1");
+    assert_eq!(packet["b"][1][3], "Cats are small, furry animals. They sleep a lot.");
+    assert_eq!(packet["p"]["code1"], "h2");
+}
+
+fn temp_path(tag: &str, ext: &str) -> std::path::PathBuf {
+    std::env::temp_dir().join(format!("bdir_md_{tag}_{}.{ext}", std::process::id()))
+}
+
+fn write_guide_patch(tag: &str, ops: Value) -> std::path::PathBuf {
+    let src = std::fs::read_to_string(markdown_fixture_path("guide.md")).unwrap();
+    let doc = bdir_markdown::parse_markdown_str(&src);
+    let patch = serde_json::json!({ "v": 1, "h": doc.page_hash, "ha": doc.hash_algorithm, "ops": ops });
+    let path = temp_path(tag, "json");
+    std::fs::write(&path, patch.to_string()).unwrap();
+    path
+}
+
+#[test]
+fn cli_apply_writes_patched_markdown_back_into_the_source() {
+    let input = markdown_fixture_path("guide.md");
+    let patch = write_guide_patch(
+        "apply",
+        serde_json::json!([
+            { "op": "replace", "block_id": "p1", "before": "small, furry", "after": "tiny, furry" },
+            { "op": "replace", "block_id": "l1", "before": "Brush weekly", "after": "Brush daily" }
+        ]),
+    );
+    let out = temp_path("apply_out", "md");
+
+    let mut cmd = cargo_bin_cmd!("bdir");
+    cmd.args(["apply", input.to_str().unwrap(), patch.to_str().unwrap(), "--out", out.to_str().unwrap()]);
+    cmd.assert().success();
+
+    let original = std::fs::read_to_string(&input).unwrap();
+    let written = std::fs::read_to_string(&out).unwrap();
+    assert_eq!(written, original.replace("*small*", "*tiny*").replace("Brush weekly", "Brush daily"));
+}
+
+#[test]
+fn cli_apply_fails_closed_when_markdown_cannot_be_written_back() {
+    let input = markdown_fixture_path("guide.md");
+    let patch = write_guide_patch(
+        "unmappable",
+        serde_json::json!([{ "op": "delete", "block_id": "p1", "before": "small, furry " }]),
+    );
+
+    let mut cmd = cargo_bin_cmd!("bdir");
+    cmd.args(["apply-patch", "--doc", input.to_str().unwrap(), "--patch", patch.to_str().unwrap()]);
+    cmd.assert()
+        .code(2)
+        .stderr(predicates::str::contains("edit in block 'p1' does not map onto verbatim Markdown text"));
+}
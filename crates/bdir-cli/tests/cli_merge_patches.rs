@@ -0,0 +1,68 @@
+use assert_cmd::cargo::cargo_bin_cmd;
+
+use bdir_io::prelude::PatchV1;
+
+fn example_edit_packet_path() -> std::path::PathBuf {
+    std::path::Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("..")
+        .join("..")
+        .join("examples")
+        .join("edit-packet.json")
+}
+
+fn write_patch(tag: &str, ops: serde_json::Value) -> std::path::PathBuf {
+    let packet: serde_json::Value =
+        serde_json::from_str(&std::fs::read_to_string(example_edit_packet_path()).unwrap()).unwrap();
+    let patch = serde_json::json!({ "v": 1, "h": packet["h"], "ha": packet["ha"], "ops": ops });
+
+    let path = std::env::temp_dir().join(format!("bdir_merge_{tag}_{}.json", std::process::id()));
+    std::fs::write(&path, serde_json::to_string(&patch).unwrap()).unwrap();
+    path
+}
+
+#[test]
+fn cli_merge_patches_clean_merge_exits_0() {
+    let packet = example_edit_packet_path();
+    let a = write_patch("clean_a", serde_json::json!([
+        { "op": "replace", "block_id": "p1", "before": "with a typo teh", "after": "with a typo the" }
+    ]));
+    let b = write_patch("clean_b", serde_json::json!([
+        { "op": "replace", "block_id": "t1", "before": "Example Page", "after": "Sample Page" }
+    ]));
+
+    let output = cargo_bin_cmd!("bdir")
+        .args(["merge-patches", packet.to_str().unwrap(), a.to_str().unwrap(), b.to_str().unwrap()])
+        .assert()
+        .success()
+        .get_output()
+        .stdout
+        .clone();
+
+    let merged: PatchV1 = serde_json::from_slice(&output).unwrap();
+    let targets: Vec<&str> = merged.ops.iter().map(|o| o.block_id.as_str()).collect();
+    assert_eq!(targets, vec!["t1", "p1"]);
+
+    let _ = std::fs::remove_file(a);
+    let _ = std::fs::remove_file(b);
+}
+
+#[test]
+fn cli_merge_patches_reports_conflicts_and_exits_2() {
+    let packet = example_edit_packet_path();
+    let a = write_patch("conflict_a", serde_json::json!([
+        { "op": "replace", "block_id": "t1", "before": "Example Page", "after": "Sample Page" }
+    ]));
+    let b = write_patch("conflict_b", serde_json::json!([
+        { "op": "replace", "block_id": "t1", "before": "Example Page", "after": "Demo Page" }
+    ]));
+
+    cargo_bin_cmd!("bdir")
+        .args(["merge-patches", packet.to_str().unwrap(), a.to_str().unwrap(), b.to_str().unwrap()])
+        .assert()
+        .failure()
+        .code(2)
+        .stderr("patches[0].ops[0] and patches[1].ops[0] rewrite the same text in block 't1' differently\n");
+
+    let _ = std::fs::remove_file(a);
+    let _ = std::fs::remove_file(b);
+}
@@ -0,0 +1,77 @@
+use assert_cmd::cargo::cargo_bin_cmd;
+
+use bdir_io::prelude::PatchV1;
+
+fn example_document_path() -> std::path::PathBuf {
+    std::path::Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("..")
+        .join("..")
+        .join("examples")
+        .join("document.json")
+}
+
+fn patch_fixture_path(file: &str) -> std::path::PathBuf {
+    std::path::Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("..")
+        .join("bdir-patch")
+        .join("tests")
+        .join("fixtures")
+        .join(file)
+}
+
+/// Write a copy of the example document with block `id` set to `text` (or removed when `None`).
+fn write_new_document(tag: &str, id: &str, text: Option<&str>) -> std::path::PathBuf {
+    let mut doc: serde_json::Value =
+        serde_json::from_str(&std::fs::read_to_string(example_document_path()).unwrap()).unwrap();
+    let blocks = doc["blocks"].as_array_mut().unwrap();
+    match text {
+        Some(t) => {
+            let b = blocks.iter_mut().find(|b| b["id"] == id).unwrap();
+            b["text"] = serde_json::Value::String(t.to_string());
+        }
+        None => blocks.retain(|b| b["id"] != id),
+    }
+
+    let path = std::env::temp_dir().join(format!("bdir_rebase_{tag}_{}.json", std::process::id()));
+    std::fs::write(&path, serde_json::to_string(&doc).unwrap()).unwrap();
+    path
+}
+
+#[test]
+fn cli_rebase_patch_clean_rebase_exits_0() {
+    let base = example_document_path();
+    let new = write_new_document("clean", "t1", Some("Updated Page Title"));
+    let patch = patch_fixture_path("patch.valid.json");
+
+    let output = cargo_bin_cmd!("bdir")
+        .args(["rebase-patch", "--base", base.to_str().unwrap(), "--new", new.to_str().unwrap()])
+        .args(["--patch", patch.to_str().unwrap()])
+        .assert()
+        .success()
+        .get_output()
+        .stdout
+        .clone();
+
+    let rebased: PatchV1 = serde_json::from_slice(&output).unwrap();
+    assert_eq!(rebased.ops.len(), 1);
+    assert_ne!(rebased.h.as_deref(), Some("ff0e1875ab7f8425"));
+
+    let _ = std::fs::remove_file(new);
+}
+
+#[test]
+fn cli_rebase_patch_reports_conflicts_and_exits_2() {
+    let base = example_document_path();
+    let new = write_new_document("conflict", "p1", None);
+    let patch = patch_fixture_path("patch.valid.json");
+
+    cargo_bin_cmd!("bdir")
+        .args(["rebase-patch", "--base", base.to_str().unwrap(), "--new", new.to_str().unwrap()])
+        .args(["--patch", patch.to_str().unwrap()])
+        .assert()
+        .failure()
+        .code(2)
+        .stderr("ops[0] targets block 'p1', which no longer exists\n");
+
+    let _ = std::fs::remove_file(new);
+}
@@ -0,0 +1,66 @@
+use assert_cmd::cargo::cargo_bin_cmd;
+
+fn example_document_path() -> std::path::PathBuf {
+    std::path::Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("..")
+        .join("..")
+        .join("examples")
+        .join("document.json")
+}
+
+fn markdown_fixture_path(file: &str) -> std::path::PathBuf {
+    std::path::Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("..")
+        .join("bdir-markdown")
+        .join("tests")
+        .join("fixtures")
+        .join(file)
+}
+
+fn render(args: &[&str]) -> String {
+    let mut cmd = cargo_bin_cmd!("bdir");
+    cmd.arg("render").args(args);
+    String::from_utf8(cmd.assert().success().get_output().stdout.clone()).unwrap()
+}
+
+#[test]
+fn cli_render_supports_md_html_and_txt() {
+    let input = markdown_fixture_path("guide.md");
+    let input = input.to_str().unwrap();
+
+    let md = render(&[input, "--format", "md"]);
+    assert!(md.starts_with("# Field Guide\n\nCats are small, furry animals. They sleep a lot.\n\n## Care\n"));
+    assert!(md.contains("\n### Grooming tools\n\n```\nbrush --gentle\ncomb\n```\n"));
+    assert_eq!(render(&[input]), md);
+
+    let html = render(&[input, "--format", "html"]);
+    assert!(html.starts_with("<h1>Field Guide</h1>\n<p>Cats are small, furry animals. They sleep a lot.</p>\n"));
+    assert!(html.contains("<h3>Grooming tools</h3>\n<pre><code>brush --gentle\ncomb</code></pre>\n"));
+
+    let txt = render(&[input, "--format", "txt"]);
+    assert!(txt.starts_with("Field Guide\n\nCats are small, furry animals. They sleep a lot.\n\nCare\n\n- Feed twice a day\n"));
+}
+
+#[test]
+fn cli_render_writes_document_json_to_a_file() {
+    let input = example_document_path();
+    let out = std::env::temp_dir().join(format!("bdir_render_{}.txt", std::process::id()));
+
+    let mut cmd = cargo_bin_cmd!("bdir");
+    cmd.args(["render", input.to_str().unwrap(), "--format", "txt", "--core-only", "--out", out.to_str().unwrap()]);
+    cmd.assert().success().stdout("");
+
+    let written = std::fs::read_to_string(&out).unwrap();
+    assert_eq!(written, "Example Page Title\n\nThis is an example paragraph with a typo teh.\n");
+}
+
+#[test]
+fn cli_render_rejects_unknown_formats() {
+    let input = markdown_fixture_path("guide.md");
+
+    let mut cmd = cargo_bin_cmd!("bdir");
+    cmd.args(["render", input.to_str().unwrap(), "--format", "pdf"]);
+    cmd.assert()
+        .code(2)
+        .stderr(predicates::str::contains("unknown render format 'pdf' (expected md, html or txt)"));
+}
@@ -0,0 +1,46 @@
+use assert_cmd::cargo::cargo_bin_cmd;
+use serde_json::{json, Value};
+
+fn patch_fixture_path(file: &str) -> std::path::PathBuf {
+    std::path::Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("..")
+        .join("bdir-patch")
+        .join("tests")
+        .join("fixtures")
+        .join(file)
+}
+
+fn upgrade(file: &str) -> Value {
+    let patch = patch_fixture_path(file);
+    let output = cargo_bin_cmd!("bdir")
+        .args(["upgrade-patch", "--patch", patch.to_str().unwrap(), "--min"])
+        .output()
+        .unwrap();
+    assert!(output.status.success(), "stderr: {}", String::from_utf8_lossy(&output.stderr));
+    serde_json::from_slice(&output.stdout).unwrap()
+}
+
+#[test]
+fn cli_upgrade_patch_rewrites_v1_as_v2() {
+    let upgraded = upgrade("patch.valid.json");
+    let expected: Value = serde_json::from_str(&std::fs::read_to_string(patch_fixture_path("patch.v2.valid.json")).unwrap())
+        .unwrap();
+    assert_eq!(upgraded, expected);
+}
+
+#[test]
+fn cli_upgrade_patch_keeps_v2_unchanged() {
+    let upgraded = upgrade("patch.v2.valid.json");
+    assert_eq!(upgraded["v"], json!(2));
+    assert_eq!(upgraded["h"], json!("ff0e1875ab7f8425"));
+}
+
+#[test]
+fn cli_upgrade_patch_rejects_unknown_versions() {
+    let patch = patch_fixture_path("patch.unsupported_version.json");
+    cargo_bin_cmd!("bdir")
+        .args(["upgrade-patch", "--patch", patch.to_str().unwrap()])
+        .assert()
+        .failure()
+        .code(1);
+}
@@ -90,3 +90,147 @@ fn validate_patch_schema_invalid_exits_1() {
 
     cmd.assert().failure().code(1);
 }
+
+#[test]
+fn validate_patch_tampered_block_text_fails_with_verify_block_hashes() {
+    let packet = patch_fixture_path("edit-packet.tampered_text.json");
+    let patch = patch_fixture_path("patch.valid.json");
+
+    let mut cmd = cargo_bin_cmd!("bdir");
+    cmd.args(["validate-patch", packet.to_str().unwrap(), patch.to_str().unwrap()]);
+    cmd.assert().success().code(0).stdout("OK\n");
+
+    let mut cmd = cargo_bin_cmd!("bdir");
+    cmd.args([
+        "validate-patch",
+        packet.to_str().unwrap(),
+        patch.to_str().unwrap(),
+        "--verify-block-hashes",
+        "--diagnostics-json",
+    ]);
+
+    cmd.assert()
+        .failure()
+        .code(2)
+        .stderr(predicate::str::contains("block_hash_mismatch"));
+}
+
+#[test]
+fn validate_patch_all_diagnostics_reports_every_failing_op() {
+    let packet = edit_packet_path();
+    let patch = patch_fixture_path("patch.multiple_errors.json");
+
+    let mut cmd = cargo_bin_cmd!("bdir");
+    cmd.args(["validate-patch", packet.to_str().unwrap(), patch.to_str().unwrap()]);
+    cmd.assert()
+        .failure()
+        .code(2)
+        .stderr("ops[0] references unknown block_id 'nope'\n");
+
+    let mut cmd = cargo_bin_cmd!("bdir");
+    cmd.args([
+        "validate-patch",
+        packet.to_str().unwrap(),
+        patch.to_str().unwrap(),
+        "--all-diagnostics",
+    ]);
+    cmd.assert().failure().code(2).stderr(
+        "ops[0] references unknown block_id 'nope'\nops[1] (replace) before substring not found in block 'p1'\n",
+    );
+
+    let mut cmd = cargo_bin_cmd!("bdir");
+    cmd.args([
+        "validate-patch",
+        packet.to_str().unwrap(),
+        patch.to_str().unwrap(),
+        "--all-diagnostics",
+        "--telemetry-json",
+    ]);
+    let out = cmd.assert().failure().code(2).get_output().stderr.clone();
+    let report: serde_json::Value = serde_json::from_slice(&out).unwrap();
+    assert_eq!(report["error"], "ops[0] references unknown block_id 'nope'");
+    assert_eq!(
+        report["errors"],
+        serde_json::json!([
+            "ops[0] references unknown block_id 'nope'",
+            "ops[1] (replace) before substring not found in block 'p1'"
+        ])
+    );
+}
+
+#[test]
+fn validate_patch_tolerant_matching_is_opt_in_and_reported() {
+    let packet = edit_packet_path();
+    let patch = patch_fixture_path("patch.tolerant_whitespace.json");
+
+    let mut cmd = cargo_bin_cmd!("bdir");
+    cmd.args(["validate-patch", packet.to_str().unwrap(), patch.to_str().unwrap()]);
+    cmd.assert()
+        .failure()
+        .code(2)
+        .stderr("ops[0] (replace) before substring not found in block 'p1'\n");
+
+    let mut cmd = cargo_bin_cmd!("bdir");
+    cmd.args([
+        "validate-patch",
+        packet.to_str().unwrap(),
+        patch.to_str().unwrap(),
+        "--tolerant-matching",
+    ]);
+    cmd.assert().success().code(0).stdout("OK\n").stderr(
+        "note: ops[0] (replace) before matched block 'p1' only after whitespace/typography normalization\n",
+    );
+
+    let mut cmd = cargo_bin_cmd!("bdir");
+    cmd.args([
+        "validate-patch",
+        packet.to_str().unwrap(),
+        patch.to_str().unwrap(),
+        "--tolerant-matching",
+        "--telemetry-json",
+    ]);
+    cmd.assert()
+        .success()
+        .stderr(predicate::str::contains("\"tolerant_match_ops\":[0]"));
+}
+
+#[test]
+fn validate_patch_accepts_v2_patches() {
+    let packet = edit_packet_path();
+    let patch = patch_fixture_path("patch.v2.valid.json");
+
+    let mut cmd = cargo_bin_cmd!("bdir");
+    cmd.args(["validate-patch", packet.to_str().unwrap(), patch.to_str().unwrap()]);
+
+    cmd.assert().success().code(0).stdout("OK\n");
+}
+
+#[test]
+fn validate_patch_rejects_unknown_versions_at_the_schema() {
+    let packet = edit_packet_path();
+    let patch = patch_fixture_path("patch.unsupported_version.json");
+
+    let mut cmd = cargo_bin_cmd!("bdir");
+    cmd.args(["validate-patch", packet.to_str().unwrap(), patch.to_str().unwrap()]);
+
+    cmd.assert().failure().code(1).stderr(predicate::str::contains("1 was expected"));
+}
+
+#[test]
+fn validate_patch_short_page_hash_reaches_the_validator() {
+    let packet = edit_packet_path();
+    let patch = patch_fixture_path("patch.hash_too_short.json");
+
+    let mut cmd = cargo_bin_cmd!("bdir");
+    cmd.args([
+        "validate-patch",
+        packet.to_str().unwrap(),
+        patch.to_str().unwrap(),
+        "--diagnostics-json",
+    ]);
+
+    cmd.assert()
+        .failure()
+        .code(2)
+        .stderr(predicate::str::contains("hash_too_short"));
+}
@@ -37,6 +37,29 @@ pub mod ranges {
     pub const UNKNOWN: u16 = 99;
 }
 
+/// Named kindCodes from `spec/codebooks/kind-codebook.v2.json`.
+///
+/// Codebook v2 extends the frozen v1 codebook; `CODE`, `SITE_FOOTER`, `SITE_HEADER`
+/// and `CONSENT_BANNER` are only defined in v2.
+pub mod codes {
+    pub const TITLE: u16 = 0;
+    pub const HEADING: u16 = 1;
+    pub const PARAGRAPH: u16 = 2;
+    pub const LIST: u16 = 3;
+    pub const TABLE: u16 = 4;
+    pub const FIGURE: u16 = 5;
+    pub const CODE: u16 = 6;
+    pub const BREADCRUMB: u16 = 20;
+    pub const SECONDARY_NAV: u16 = 21;
+    pub const RELATED_CONTENT: u16 = 22;
+    pub const SITE_FOOTER: u16 = 23;
+    pub const SITE_HEADER: u16 = 24;
+    pub const UI_CONTROL: u16 = 40;
+    pub const ICON_ONLY: u16 = 41;
+    pub const CONSENT_BANNER: u16 = 42;
+    pub const UNKNOWN: u16 = 99;
+}
+
 pub fn importance(kind_code: u16) -> KindImportance {
     use ranges::*;
     match kind_code {
@@ -72,6 +95,29 @@ pub fn is_unknown(kind_code: u16) -> bool {
     matches!(importance(kind_code), KindImportance::Unknown)
 }
 
+/// Short block id prefix for `kind_code`, used by importers and id generation
+/// (`p` for paragraphs, `nav` for secondary navigation, …); `x` for anything unnamed.
+pub fn id_prefix(kind_code: u16) -> &'static str {
+    match kind_code {
+        codes::TITLE => "t",
+        codes::HEADING => "h",
+        codes::PARAGRAPH => "p",
+        codes::LIST => "l",
+        codes::TABLE => "tbl",
+        codes::FIGURE => "fig",
+        codes::CODE => "code",
+        codes::BREADCRUMB => "crumb",
+        codes::SECONDARY_NAV => "nav",
+        codes::RELATED_CONTENT => "rel",
+        codes::SITE_FOOTER => "foot",
+        codes::SITE_HEADER => "head",
+        codes::UI_CONTROL => "ui",
+        codes::ICON_ONLY => "icon",
+        codes::CONSENT_BANNER => "consent",
+        _ => "x",
+    }
+}
+
 /// Returns true if `kind_code` is within the RFC-0001 v1 importance ranges.
 ///
 /// RFC-0001 defines the following canonical ranges:
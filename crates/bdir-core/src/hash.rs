@@ -1,5 +1,9 @@
+use std::collections::BTreeMap;
+use std::sync::{Arc, OnceLock, RwLock};
+
 use xxhash_rust::xxh3::xxh3_64;
-use sha2::{Digest, Sha256};
+use xxhash_rust::xxh64::xxh64;
+use sha2::{Digest, Sha256, Sha512_256};
 use unicode_normalization::UnicodeNormalization;
 
 /// Canonicalize text for hashing.
@@ -23,11 +27,11 @@ pub fn canonicalize_text(input: &str) -> String {
     // So we iterate manually while preserving '\n' exactly.
     for segment in normalized.split_inclusive('\n') {
         if let Some(stripped) = segment.strip_suffix('\n') {
-            out.push_str(stripped.trim_end_matches(|c: char| c == ' ' || c == '\t'));
+            out.push_str(stripped.trim_end_matches([' ', '\t']));
             out.push('\n');
         } else {
             // Last segment (no trailing '\n')
-            out.push_str(segment.trim_end_matches(|c: char| c == ' ' || c == '\t'));
+            out.push_str(segment.trim_end_matches([' ', '\t']));
         }
     }
 
@@ -42,13 +46,13 @@ pub fn normalize_nfc(input: &str) -> String {
     input.nfc().collect()
 }
 
-/// Compute an xxh64-style hash (hex) over UTF-8 bytes.
+/// Compute an XXH64 hash (hex) over UTF-8 bytes.
 ///
 /// Implementation detail:
-/// - Uses xxh3_64 (from `xxhash-rust`) for speed and stability.
+/// - Spec-accurate XXH64 with seed 0, so digests match other XXH64 implementations.
 /// - Returned as fixed-width 16-char lowercase hex.
 pub fn xxh64_hex(input: &str) -> String {
-    format!("{:016x}", xxh3_64(input.as_bytes()))
+    format!("{:016x}", xxh64(input.as_bytes(), 0))
 }
 
 /// Convenience: hash canonicalized text.
@@ -57,6 +61,19 @@ pub fn xxh64_canon_hex(input: &str) -> String {
     xxh64_hex(&canon)
 }
 
+/// Compute an XXH3 (64-bit) hash (hex) over UTF-8 bytes.
+///
+/// Returned as fixed-width 16-char lowercase hex.
+pub fn xxh3_hex(input: &str) -> String {
+    format!("{:016x}", xxh3_64(input.as_bytes()))
+}
+
+/// Convenience: xxh3 over canonicalized text.
+pub fn xxh3_canon_hex(input: &str) -> String {
+    let canon = canonicalize_text(input);
+    xxh3_hex(&canon)
+}
+
 /// Compute a sha256 hash (hex) over UTF-8 bytes.
 ///
 /// Returned as fixed-width 64-char lowercase hex.
@@ -73,17 +90,29 @@ pub fn sha256_canon_hex(input: &str) -> String {
     sha256_hex(&canon)
 }
 
+/// Compute a SHA-512/256 hash (hex) over UTF-8 bytes.
+///
+/// Returned as fixed-width 64-char lowercase hex.
+pub fn sha512_256_hex(input: &str) -> String {
+    let mut hasher = Sha512_256::new();
+    hasher.update(input.as_bytes());
+    let digest = hasher.finalize();
+    format!("{:x}", digest)
+}
+
+/// Compute a BLAKE3 (256-bit) hash (hex) over UTF-8 bytes.
+///
+/// Returned as fixed-width 64-char lowercase hex.
+pub fn blake3_hex(input: &str) -> String {
+    blake3::hash(input.as_bytes()).to_hex().to_string()
+}
+
 /// Hash helper that respects a declared algorithm name.
 ///
-/// Supported algorithms:
-/// - "xxh64" (implemented via xxh3_64)
-/// - "sha256"
+/// The name is resolved through the process-wide hash algorithm registry
+/// (see [`lookup_hash_algorithm`]). Returns `None` for unregistered names.
 pub fn hash_hex(algo: &str, input: &str) -> Option<String> {
-    match algo {
-        "xxh64" => Some(xxh64_hex(input)),
-        "sha256" => Some(sha256_hex(input)),
-        _ => None,
-    }
+    lookup_hash_algorithm(algo).map(|a| a.hash_hex(input.as_bytes()))
 }
 
 /// Hash canonicalized text using the declared algorithm.
@@ -91,3 +120,252 @@ pub fn hash_canon_hex(algo: &str, input: &str) -> Option<String> {
     let canon = canonicalize_text(input);
     hash_hex(algo, &canon)
 }
+
+/// Minimum length (in hex characters) of a truncated hash prefix (RFC-0001 §5.1.4).
+pub const MIN_HASH_PREFIX_LEN: usize = 8;
+
+/// Truncate a hex digest to a prefix of `len` characters (RFC-0001 §5.1.4).
+///
+/// `len` is raised to `MIN_HASH_PREFIX_LEN` so the result is always a valid
+/// prefix. Digests that are already shorter than `len` are returned unchanged.
+pub fn truncate_hash(hash: &str, len: usize) -> String {
+    let len = len.max(MIN_HASH_PREFIX_LEN);
+    hash.get(..len).unwrap_or(hash).to_string()
+}
+
+/// Compare two hash values, either of which may be a truncated prefix (RFC-0001 §5.1.4).
+///
+/// Returns true when the shorter value is a prefix of the longer one (equal values
+/// match trivially). Callers are responsible for rejecting values shorter than
+/// `MIN_HASH_PREFIX_LEN` before relying on the result.
+pub fn hash_prefix_matches(a: &str, b: &str) -> bool {
+    let (short, long) = if a.len() <= b.len() { (a, b) } else { (b, a) };
+    long.starts_with(short)
+}
+
+/// Migrate a `hash_algorithm` label written by older engine versions.
+///
+/// Engine versions before the XXH64 fix computed XXH3 (64-bit) digests but labeled
+/// them `"xxh64"`. Relabeling such artifacts as `"xxh3"` keeps every stored digest
+/// valid, whereas re-reading them as `"xxh64"` would change every hash.
+///
+/// Any other label is returned unchanged.
+pub fn migrate_legacy_hash_algorithm(algo: &str) -> String {
+    if algo.trim().eq_ignore_ascii_case("xxh64") {
+        "xxh3".to_string()
+    } else {
+        algo.to_string()
+    }
+}
+
+// -----------------------------------------------------------------------------
+// Hash algorithm registry
+// -----------------------------------------------------------------------------
+
+/// A hash algorithm usable as a `hash_algorithm` / `ha` value.
+///
+/// Implementations MUST be deterministic for identical input bytes and MUST
+/// return lowercase hex of a fixed width (RFC-0001 §5.1.2).
+pub trait HashAlgorithm: Send + Sync {
+    /// Stable lowercase token used on the wire (e.g. "sha256").
+    fn name(&self) -> &str;
+
+    /// Length of a full (untruncated) digest in hex characters.
+    fn digest_hex_len(&self) -> usize;
+
+    /// Hash raw bytes and return lowercase hex.
+    fn hash_hex(&self, bytes: &[u8]) -> String;
+}
+
+/// Built-in "xxh64" (XXH64, seed 0).
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Xxh64Hash;
+
+impl HashAlgorithm for Xxh64Hash {
+    fn name(&self) -> &str {
+        "xxh64"
+    }
+    fn digest_hex_len(&self) -> usize {
+        16
+    }
+    fn hash_hex(&self, bytes: &[u8]) -> String {
+        format!("{:016x}", xxh64(bytes, 0))
+    }
+}
+
+/// Built-in "xxh3" (64-bit XXH3).
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Xxh3Hash;
+
+impl HashAlgorithm for Xxh3Hash {
+    fn name(&self) -> &str {
+        "xxh3"
+    }
+    fn digest_hex_len(&self) -> usize {
+        16
+    }
+    fn hash_hex(&self, bytes: &[u8]) -> String {
+        format!("{:016x}", xxh3_64(bytes))
+    }
+}
+
+/// Built-in "sha256" (the RFC-0001 baseline algorithm).
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Sha256Hash;
+
+impl HashAlgorithm for Sha256Hash {
+    fn name(&self) -> &str {
+        "sha256"
+    }
+    fn digest_hex_len(&self) -> usize {
+        64
+    }
+    fn hash_hex(&self, bytes: &[u8]) -> String {
+        format!("{:x}", Sha256::digest(bytes))
+    }
+}
+
+/// Built-in "sha512_256" (SHA-512/256, FIPS 180-4).
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Sha512_256Hash;
+
+impl HashAlgorithm for Sha512_256Hash {
+    fn name(&self) -> &str {
+        "sha512_256"
+    }
+    fn digest_hex_len(&self) -> usize {
+        64
+    }
+    fn hash_hex(&self, bytes: &[u8]) -> String {
+        format!("{:x}", Sha512_256::digest(bytes))
+    }
+}
+
+/// Built-in "blake3" (256-bit output).
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Blake3Hash;
+
+impl HashAlgorithm for Blake3Hash {
+    fn name(&self) -> &str {
+        "blake3"
+    }
+    fn digest_hex_len(&self) -> usize {
+        64
+    }
+    fn hash_hex(&self, bytes: &[u8]) -> String {
+        blake3::hash(bytes).to_hex().to_string()
+    }
+}
+
+/// A set of hash algorithms keyed by their wire name.
+///
+/// Iteration order is lexicographic by name, so listings are deterministic.
+#[derive(Clone, Default)]
+pub struct HashRegistry {
+    algorithms: BTreeMap<String, Arc<dyn HashAlgorithm>>,
+}
+
+impl HashRegistry {
+    /// An empty registry.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// A registry containing every built-in algorithm.
+    pub fn with_builtins() -> Self {
+        let mut reg = Self::new();
+        for algo in [
+            Arc::new(Xxh64Hash) as Arc<dyn HashAlgorithm>,
+            Arc::new(Xxh3Hash),
+            Arc::new(Sha256Hash),
+            Arc::new(Sha512_256Hash),
+            Arc::new(Blake3Hash),
+        ] {
+            reg.algorithms.insert(algo.name().to_string(), algo);
+        }
+        reg
+    }
+
+    /// Register an algorithm.
+    ///
+    /// Returns an error if the name is not a lowercase token (`[a-z0-9_-]+`) or is
+    /// already registered, or if the algorithm does not produce lowercase hex of
+    /// `digest_hex_len` characters (at least `MIN_HASH_PREFIX_LEN`). Existing algorithms
+    /// are never replaced, so a name always means the same digest within a process.
+    pub fn register(&mut self, algo: Arc<dyn HashAlgorithm>) -> Result<(), String> {
+        let name = algo.name();
+        let valid = !name.is_empty()
+            && name
+                .bytes()
+                .all(|b| matches!(b, b'a'..=b'z' | b'0'..=b'9' | b'_' | b'-'));
+        if !valid {
+            return Err(format!(
+                "invalid hash algorithm name '{name}' (expected a lowercase token)"
+            ));
+        }
+        if self.algorithms.contains_key(name) {
+            return Err(format!("hash algorithm '{name}' is already registered"));
+        }
+        let len = algo.digest_hex_len();
+        let probe = algo.hash_hex(b"");
+        let lower_hex = probe.bytes().all(|b| matches!(b, b'0'..=b'9' | b'a'..=b'f'));
+        if len < MIN_HASH_PREFIX_LEN || probe.len() != len || !lower_hex {
+            return Err(format!(
+                "hash algorithm '{name}' must produce {len} lowercase hex chars \
+                 (at least {MIN_HASH_PREFIX_LEN}), got '{probe}'"
+            ));
+        }
+        self.algorithms.insert(name.to_string(), algo);
+        Ok(())
+    }
+
+    /// Look up an algorithm by its exact wire name.
+    pub fn get(&self, name: &str) -> Option<Arc<dyn HashAlgorithm>> {
+        self.algorithms.get(name).cloned()
+    }
+
+    /// Registered names in lexicographic order.
+    pub fn names(&self) -> Vec<String> {
+        self.algorithms.keys().cloned().collect()
+    }
+}
+
+impl std::fmt::Debug for HashRegistry {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("HashRegistry")
+            .field("algorithms", &self.names())
+            .finish()
+    }
+}
+
+fn global_registry() -> &'static RwLock<HashRegistry> {
+    static REGISTRY: OnceLock<RwLock<HashRegistry>> = OnceLock::new();
+    REGISTRY.get_or_init(|| RwLock::new(HashRegistry::with_builtins()))
+}
+
+/// Register an additional algorithm with the process-wide registry.
+///
+/// Every engine surface that resolves `hash_algorithm` / `ha` values
+/// (document normalization, validation, application) consults this registry.
+pub fn register_hash_algorithm(algo: Arc<dyn HashAlgorithm>) -> Result<(), String> {
+    global_registry()
+        .write()
+        .unwrap_or_else(|e| e.into_inner())
+        .register(algo)
+}
+
+/// Look up an algorithm in the process-wide registry by its exact wire name.
+pub fn lookup_hash_algorithm(name: &str) -> Option<Arc<dyn HashAlgorithm>> {
+    global_registry()
+        .read()
+        .unwrap_or_else(|e| e.into_inner())
+        .get(name)
+}
+
+/// Names of all algorithms in the process-wide registry, in lexicographic order.
+pub fn supported_hash_algorithms() -> Vec<String> {
+    global_registry()
+        .read()
+        .unwrap_or_else(|e| e.into_inner())
+        .names()
+}
@@ -0,0 +1,173 @@
+//! Deterministic block id generation.
+//!
+//! Producers (importers, or the patch engine when it assigns ids to inserted blocks)
+//! ask an `IdGenerator` for one id per block, in document order. Three strategies
+//! are supported:
+//!
+//! | strategy | example | id is derived from |
+//! |---|---|---|
+//! | `positional` | `p3` | kind prefix + running number per prefix |
+//! | `content` | `p-1a2b3c4d` | kind prefix + XXH64 prefix of the block text (and optional key) |
+//! | `hierarchical` | `h2.p3` | parent id + kind prefix + running number per parent and prefix |
+//!
+//! Collisions are resolved deterministically: numbered strategies skip to the next
+//! free number, content-addressed ids append `-2`, `-3`, … in request order. Ids
+//! already present in a Document can be `reserve`d first, so generated ids never
+//! collide with them and numbering continues after the highest existing number.
+
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+use std::str::FromStr;
+
+use crate::hash::xxh64_hex;
+use crate::model::BlockId;
+
+/// Hex digits of the text hash kept in content-addressed ids.
+pub const DEFAULT_ID_HASH_LEN: usize = 8;
+
+/// How an `IdGenerator` derives block ids.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum IdStrategy {
+    /// `{prefix}{n}`: `p1`, `p2`, … numbered per prefix.
+    #[default]
+    Positional,
+    /// `{prefix}-{hash}`: stable while the block text (and key) is unchanged.
+    ContentAddressed,
+    /// `{parent}.{prefix}{n}`: numbered per prefix within the parent; `{prefix}{n}`
+    /// for top-level blocks.
+    Hierarchical,
+}
+
+impl IdStrategy {
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            IdStrategy::Positional => "positional",
+            IdStrategy::ContentAddressed => "content",
+            IdStrategy::Hierarchical => "hierarchical",
+        }
+    }
+}
+
+impl fmt::Display for IdStrategy {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for IdStrategy {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "positional" => Ok(IdStrategy::Positional),
+            "content" | "content-addressed" => Ok(IdStrategy::ContentAddressed),
+            "hierarchical" => Ok(IdStrategy::Hierarchical),
+            other => Err(format!("unknown id strategy '{other}' (expected positional, content or hierarchical)")),
+        }
+    }
+}
+
+/// What the next id is for.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct IdRequest<'a> {
+    /// Kind prefix, e.g. `p` for paragraphs (see `bdir_codebook::id_prefix`).
+    pub prefix: &'a str,
+    /// Block text; only used by `ContentAddressed`.
+    pub text: &'a str,
+    /// Parent block id; only used by `Hierarchical`.
+    pub parent: Option<&'a str>,
+    /// Extra hash input for `ContentAddressed` (e.g. a DOM path), so equal texts in
+    /// different places get different ids.
+    pub key: Option<&'a str>,
+}
+
+/// Hands out unique, deterministic block ids.
+///
+/// The same sequence of `reserve` and `next_id` calls always yields the same ids.
+#[derive(Debug, Clone)]
+pub struct IdGenerator {
+    strategy: IdStrategy,
+    hash_len: usize,
+    taken: HashSet<BlockId>,
+    /// Highest number used per (scope, prefix); the scope is the parent id for
+    /// hierarchical ids and empty otherwise.
+    counters: HashMap<(String, String), u64>,
+}
+
+impl Default for IdGenerator {
+    fn default() -> Self {
+        Self::new(IdStrategy::default())
+    }
+}
+
+impl IdGenerator {
+    pub fn new(strategy: IdStrategy) -> Self {
+        Self::with_hash_len(strategy, DEFAULT_ID_HASH_LEN)
+    }
+
+    /// Like `new`, keeping `hash_len` hex digits (1–16) in content-addressed ids.
+    pub fn with_hash_len(strategy: IdStrategy, hash_len: usize) -> Self {
+        Self { strategy, hash_len: hash_len.clamp(1, 16), taken: HashSet::new(), counters: HashMap::new() }
+    }
+
+    pub fn strategy(&self) -> IdStrategy {
+        self.strategy
+    }
+
+    /// Mark `id` as taken. Numbered ids (`p7`, `h2.p3`) also move their counter, so
+    /// later ids with the same prefix and scope continue after them.
+    pub fn reserve(&mut self, id: &str) {
+        self.taken.insert(id.to_string());
+        let (scope, last) = id.rsplit_once('.').unwrap_or(("", id));
+        let prefix = last.trim_end_matches(|c: char| c.is_ascii_digit());
+        if prefix.is_empty() || prefix.len() == last.len() {
+            return;
+        }
+        if let Ok(n) = last[prefix.len()..].parse::<u64>() {
+            let counter = self.counters.entry((scope.to_string(), prefix.to_string())).or_insert(0);
+            *counter = (*counter).max(n);
+        }
+    }
+
+    /// True if `id` was reserved or generated.
+    pub fn is_taken(&self, id: &str) -> bool {
+        self.taken.contains(id)
+    }
+
+    /// Generate the next id for `req` and mark it taken.
+    pub fn next_id(&mut self, req: &IdRequest<'_>) -> BlockId {
+        let id = match self.strategy {
+            IdStrategy::Positional => self.numbered("", req.prefix),
+            IdStrategy::Hierarchical => self.numbered(req.parent.unwrap_or(""), req.prefix),
+            IdStrategy::ContentAddressed => self.content_addressed(req),
+        };
+        self.taken.insert(id.clone());
+        id
+    }
+
+    fn numbered(&mut self, scope: &str, prefix: &str) -> BlockId {
+        let counter = self.counters.entry((scope.to_string(), prefix.to_string())).or_insert(0);
+        loop {
+            *counter += 1;
+            let id = if scope.is_empty() { format!("{prefix}{counter}") } else { format!("{scope}.{prefix}{counter}") };
+            if !self.taken.contains(&id) {
+                return id;
+            }
+        }
+    }
+
+    fn content_addressed(&self, req: &IdRequest<'_>) -> BlockId {
+        let hash = match req.key {
+            Some(key) => xxh64_hex(&format!("{key}\n{}", req.text)),
+            None => xxh64_hex(req.text),
+        };
+        let base = format!("{}-{}", req.prefix, &hash[..self.hash_len]);
+        let mut id = base.clone();
+        let mut n = 1;
+        while self.taken.contains(&id) {
+            n += 1;
+            id = format!("{base}-{n}");
+        }
+        id
+    }
+}
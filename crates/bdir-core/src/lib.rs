@@ -8,4 +8,5 @@ Use `bdir-io` instead.
 "#]
 
 pub mod model;
-pub mod hash;
\ No newline at end of file
+pub mod hash;
+pub mod ids;
\ No newline at end of file
@@ -1,21 +1,29 @@
+use std::collections::HashMap;
+
 use serde::{Deserialize, Serialize};
-use crate::hash::{hash_canon_hex, hash_hex};
+use crate::hash::{hash_canon_hex, hash_hex, lookup_hash_algorithm, supported_hash_algorithms};
 
 /// A stable identifier for a block.
 pub type BlockId = String;
 
 /// A single semantic block in a document.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct Block {
     pub id: BlockId,
     pub kind_code: u16,
     #[serde(default)]
     pub text_hash: String,
     pub text: String,
+    /// Id of the block this one is nested under, typically the heading that opens its
+    /// section. `None` for top-level blocks.
+    ///
+    /// Structure is metadata: it does not contribute to `text_hash` or `page_hash`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub parent: Option<BlockId>,
 }
 
 /// A document as ordered blocks.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct Document {
     /// Page-level content hash.
     #[serde(default)]
@@ -33,7 +41,8 @@ impl Document {
     /// - Lowercases for canonical representation
     ///
     /// Validation:
-    /// - Returns an error if the algorithm is empty or unsupported.
+    /// - Returns an error if the algorithm is empty or not in the hash algorithm
+    ///   registry (see `bdir_core::hash::register_hash_algorithm`).
     ///
     /// RFC-0001 (v1.0.2) requires receivers to reject unrecognized
     /// `hash_algorithm` values rather than coercing them.
@@ -43,8 +52,11 @@ impl Document {
         if algo.is_empty() {
             return Err("hash_algorithm is empty".to_string());
         }
-        if hash_hex(&algo, "").is_none() {
-            return Err(format!("unsupported hash_algorithm '{algo}'"));
+        if lookup_hash_algorithm(&algo).is_none() {
+            return Err(format!(
+                "unsupported hash_algorithm '{algo}' (supported: {})",
+                supported_hash_algorithms().join(", ")
+            ));
         }
         self.hash_algorithm = algo;
         Ok(())
@@ -76,6 +88,36 @@ impl Document {
         Ok(())
     }
 
+    /// Ids of the section rooted at `root`: the root block and every block whose
+    /// `parent` chain leads to it, in document order.
+    ///
+    /// Returns `None` when `root` is not a block of this document. Parent chains that
+    /// cycle or name unknown blocks simply end.
+    pub fn section_ids(&self, root: &str) -> Option<Vec<&str>> {
+        if !self.blocks.iter().any(|b| b.id == root) {
+            return None;
+        }
+        let parents: HashMap<&str, &str> = self
+            .blocks
+            .iter()
+            .filter_map(|b| b.parent.as_deref().map(|p| (b.id.as_str(), p)))
+            .collect();
+        let in_section = |id: &str| {
+            let mut cur = id;
+            for _ in 0..=parents.len() {
+                if cur == root {
+                    return true;
+                }
+                match parents.get(cur) {
+                    Some(&p) => cur = p,
+                    None => return false,
+                }
+            }
+            false
+        };
+        Some(self.blocks.iter().map(|b| b.id.as_str()).filter(|id| in_section(id)).collect())
+    }
+
     /// Convenience wrapper that panics on unsupported algorithms.
     ///
     /// This preserves the existing API shape for internal callers/tests that
@@ -1,4 +1,5 @@
 mod common;
+use bdir_core::hash::{hash_hex, xxh64_hex};
 use bdir_core::model::Document;
 use common::{util_fs, util_hash}; 
 
@@ -17,22 +18,30 @@ fn golden_hashes_example_document_v1() {
 
     // ---- GOLDEN ASSERTS ----
     assert_eq!(doc.hash_algorithm, "xxh64");
-    assert_eq!(doc.page_hash, "4a0d9b1ad0795617");
+    assert_eq!(doc.page_hash, "ff0e1875ab7f8425");
 
     assert_eq!(doc.blocks.len(), 3);
 
     assert_eq!(doc.blocks[0].id, "t1");
     assert_eq!(doc.blocks[0].kind_code, 0);
-    assert_eq!(doc.blocks[0].text_hash, "2d85646dba5758f4");
+    assert_eq!(doc.blocks[0].text_hash, "f932d646637097b0");
     assert_eq!(doc.blocks[0].text, "Example Page Title");
 
     assert_eq!(doc.blocks[1].id, "p1");
     assert_eq!(doc.blocks[1].kind_code, 2);
-    assert_eq!(doc.blocks[1].text_hash, "a3c9cb84972dd67e");
+    assert_eq!(doc.blocks[1].text_hash, "6dcea198d9a3abcd");
     assert_eq!(doc.blocks[1].text, "This is an example paragraph with a typo teh.");
 
     assert_eq!(doc.blocks[2].id, "b1");
     assert_eq!(doc.blocks[2].kind_code, 20);
-    assert_eq!(doc.blocks[2].text_hash, "7a6ea7f684209672");
+    assert_eq!(doc.blocks[2].text_hash, "4f7d6d674ab4edac");
     assert_eq!(doc.blocks[2].text, "Home > Section > Page");
 }
+
+#[test]
+fn golden_xxh64_known_answer_vectors() {
+    // Reference XXH64 (seed 0) digests; must match every other XXH64 implementation.
+    assert_eq!(xxh64_hex(""), "ef46db3751d8e999");
+    assert_eq!(xxh64_hex("abc"), "44bc2cf5ad770999");
+    assert_eq!(hash_hex("xxh64", "abc").as_deref(), Some("44bc2cf5ad770999"));
+}
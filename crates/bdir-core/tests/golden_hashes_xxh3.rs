@@ -0,0 +1,50 @@
+mod common;
+use bdir_core::hash::{hash_hex, migrate_legacy_hash_algorithm, xxh3_hex};
+use bdir_core::model::Document;
+use common::{util_fs, util_hash};
+
+#[test]
+fn golden_hashes_example_document_xxh3() {
+    let json = util_fs::read_example_document_json();
+    let mut doc: Document = serde_json::from_str(&json).expect("document.json must parse");
+
+    // Switch to xxh3 and recompute.
+    doc.hash_algorithm = "xxh3".to_string();
+    doc.recompute_hashes();
+
+    assert!(util_hash::is_hex16(&doc.page_hash), "page_hash should be 16-char lowercase hex");
+    for b in &doc.blocks {
+        assert!(util_hash::is_hex16(&b.text_hash), "block text_hash should be 16-char lowercase hex");
+    }
+
+    // ---- GOLDEN ASSERTS ----
+    assert_eq!(doc.hash_algorithm, "xxh3");
+    assert_eq!(doc.page_hash, "4a0d9b1ad0795617");
+
+    assert_eq!(doc.blocks.len(), 3);
+
+    assert_eq!(doc.blocks[0].id, "t1");
+    assert_eq!(doc.blocks[0].text_hash, "2d85646dba5758f4");
+
+    assert_eq!(doc.blocks[1].id, "p1");
+    assert_eq!(doc.blocks[1].text_hash, "a3c9cb84972dd67e");
+
+    assert_eq!(doc.blocks[2].id, "b1");
+    assert_eq!(doc.blocks[2].text_hash, "7a6ea7f684209672");
+}
+
+#[test]
+fn golden_xxh3_known_answer_vectors() {
+    // Reference XXH3 64-bit (seed 0) digests.
+    assert_eq!(xxh3_hex(""), "2d06800538d394c2");
+    assert_eq!(xxh3_hex("abc"), "78af5f94892f3950");
+    assert_eq!(hash_hex("xxh3", "abc").as_deref(), Some("78af5f94892f3950"));
+}
+
+#[test]
+fn legacy_xxh64_label_migrates_to_xxh3() {
+    // Older engines emitted xxh3 digests labeled "xxh64"; relabeling keeps them valid.
+    assert_eq!(migrate_legacy_hash_algorithm("xxh64"), "xxh3");
+    assert_eq!(migrate_legacy_hash_algorithm(" XXH64 "), "xxh3");
+    assert_eq!(migrate_legacy_hash_algorithm("sha256"), "sha256");
+}
@@ -0,0 +1,108 @@
+use std::sync::Arc;
+
+use bdir_core::hash::{
+    HashAlgorithm,
+    HashRegistry,
+    hash_canon_hex,
+    hash_hex,
+    register_hash_algorithm,
+    supported_hash_algorithms,
+};
+use bdir_core::model::{Block, Document};
+
+/// Toy algorithm used to exercise registration; NOT a real hash.
+struct ByteLenHash;
+
+impl HashAlgorithm for ByteLenHash {
+    fn name(&self) -> &str {
+        "test-bytelen"
+    }
+    fn digest_hex_len(&self) -> usize {
+        16
+    }
+    fn hash_hex(&self, bytes: &[u8]) -> String {
+        format!("{:016x}", bytes.len())
+    }
+}
+
+#[test]
+fn builtins_are_registered_in_lexicographic_order() {
+    let names = HashRegistry::with_builtins().names();
+    assert_eq!(names, vec!["blake3", "sha256", "sha512_256", "xxh3", "xxh64"]);
+}
+
+#[test]
+fn golden_blake3_and_sha512_256_known_answer_vectors() {
+    assert_eq!(
+        hash_hex("blake3", "").as_deref(),
+        Some("af1349b9f5f9a1a6a0404dea36dcc9499bcb25c9adc112b7cc9a93cae41f3262")
+    );
+    assert_eq!(
+        hash_hex("sha512_256", "abc").as_deref(),
+        Some("53048e2681941ef99b2e29b76b4c7dabe4c2d0c634fc6d46e0e2f13107e7af23")
+    );
+}
+
+#[test]
+fn registry_rejects_duplicates_and_invalid_names() {
+    let mut reg = HashRegistry::with_builtins();
+    let err = reg.register(Arc::new(bdir_core::hash::Sha256Hash)).unwrap_err();
+    assert!(err.contains("already registered"));
+
+    struct BadName;
+    impl HashAlgorithm for BadName {
+        fn name(&self) -> &str {
+            "SHA-1"
+        }
+        fn digest_hex_len(&self) -> usize {
+            40
+        }
+        fn hash_hex(&self, _bytes: &[u8]) -> String {
+            String::new()
+        }
+    }
+    let err = reg.register(Arc::new(BadName)).unwrap_err();
+    assert!(err.contains("lowercase token"));
+
+    struct WrongWidth;
+    impl HashAlgorithm for WrongWidth {
+        fn name(&self) -> &str {
+            "wrong-width"
+        }
+        fn digest_hex_len(&self) -> usize {
+            16
+        }
+        fn hash_hex(&self, _bytes: &[u8]) -> String {
+            "ABCDEF".to_string()
+        }
+    }
+    let err = reg.register(Arc::new(WrongWidth)).unwrap_err();
+    assert_eq!(err, "hash algorithm 'wrong-width' must produce 16 lowercase hex chars (at least 8), got 'ABCDEF'");
+    assert!(reg.get("wrong-width").is_none());
+}
+
+#[test]
+fn registered_algorithm_is_consulted_by_document_hashing() {
+    let mut doc = Document {
+        page_hash: String::new(),
+        hash_algorithm: "test-bytelen".to_string(),
+        blocks: vec![Block {
+            id: "p1".to_string(),
+            kind_code: 2,
+            text_hash: String::new(),
+            text: "Hello".to_string(),
+            parent: None,
+        }],
+    };
+
+    let err = doc.try_recompute_hashes().unwrap_err();
+    assert!(err.contains("unsupported hash_algorithm 'test-bytelen'"));
+    assert!(err.contains("sha256"));
+
+    register_hash_algorithm(Arc::new(ByteLenHash)).unwrap();
+    assert!(supported_hash_algorithms().contains(&"test-bytelen".to_string()));
+
+    doc.try_recompute_hashes().unwrap();
+    assert_eq!(doc.blocks[0].text_hash, "0000000000000005");
+    assert_eq!(hash_canon_hex("test-bytelen", "Hello  ").as_deref(), Some("0000000000000005"));
+}
@@ -0,0 +1,82 @@
+use bdir_core::hash::xxh64_hex;
+use bdir_core::ids::{IdGenerator, IdRequest, IdStrategy};
+
+fn req<'a>(prefix: &'a str, text: &'a str, parent: Option<&'a str>) -> IdRequest<'a> {
+    IdRequest { prefix, text, parent, key: None }
+}
+
+#[test]
+fn positional_ids_number_per_prefix_and_skip_taken_ids() {
+    let mut ids = IdGenerator::new(IdStrategy::Positional);
+    ids.reserve("p2");
+    let got: Vec<String> = ["p", "h", "p", "p"].iter().map(|p| ids.next_id(&req(p, "", None))).collect();
+    // Reserving `p2` moves the `p` counter past it.
+    assert_eq!(got, vec!["p3", "h1", "p4", "p5"]);
+}
+
+#[test]
+fn positional_ids_continue_after_the_highest_reserved_number() {
+    let mut ids = IdGenerator::new(IdStrategy::Positional);
+    for id in ["p7", "p2", "tbl1", "intro", "h2.p9"] {
+        ids.reserve(id);
+    }
+    assert_eq!(ids.next_id(&req("p", "", None)), "p8");
+    assert_eq!(ids.next_id(&req("tbl", "", None)), "tbl2");
+    assert!(ids.is_taken("intro") && ids.is_taken("p8"));
+}
+
+#[test]
+fn content_addressed_ids_hash_text_and_key_and_suffix_repeats() {
+    let mut ids = IdGenerator::new(IdStrategy::ContentAddressed);
+    let a = ids.next_id(&req("p", "Same text", None));
+    let b = ids.next_id(&req("p", "Same text", None));
+    let c = ids.next_id(&req("p", "Same text", None));
+    assert_eq!(a, format!("p-{}", &xxh64_hex("Same text")[..8]));
+    assert_eq!(b, format!("{a}-2"));
+    assert_eq!(c, format!("{a}-3"));
+
+    let keyed = ids.next_id(&IdRequest { prefix: "p", text: "Same text", parent: None, key: Some("/main[1]/p[2]") });
+    assert_eq!(keyed, format!("p-{}", &xxh64_hex("/main[1]/p[2]\nSame text")[..8]));
+
+    let mut short = IdGenerator::with_hash_len(IdStrategy::ContentAddressed, 4);
+    assert_eq!(short.next_id(&req("h", "Same text", None)), format!("h-{}", &xxh64_hex("Same text")[..4]));
+}
+
+#[test]
+fn hierarchical_ids_number_within_their_parent() {
+    let mut ids = IdGenerator::new(IdStrategy::Hierarchical);
+    ids.reserve("h1.p1");
+    let got = vec![
+        ids.next_id(&req("h", "", None)),
+        ids.next_id(&req("p", "", Some("h1"))),
+        ids.next_id(&req("h", "", Some("h1"))),
+        ids.next_id(&req("p", "", Some("h1.h1"))),
+        ids.next_id(&req("h", "", None)),
+        ids.next_id(&req("p", "", Some("h2"))),
+    ];
+    assert_eq!(got, vec!["h1", "h1.p2", "h1.h1", "h1.h1.p1", "h2", "h2.p1"]);
+}
+
+#[test]
+fn generation_is_deterministic() {
+    let run = |strategy| {
+        let mut ids = IdGenerator::new(strategy);
+        ids.reserve("p1");
+        ["A", "B", "A"].iter().map(|t| ids.next_id(&req("p", t, Some("s1")))).collect::<Vec<_>>()
+    };
+    for strategy in [IdStrategy::Positional, IdStrategy::ContentAddressed, IdStrategy::Hierarchical] {
+        assert_eq!(run(strategy), run(strategy));
+    }
+}
+
+#[test]
+fn strategies_parse_from_their_names() {
+    for strategy in [IdStrategy::Positional, IdStrategy::ContentAddressed, IdStrategy::Hierarchical] {
+        assert_eq!(strategy.as_str().parse::<IdStrategy>(), Ok(strategy));
+    }
+    assert_eq!("content".parse::<IdStrategy>(), Ok(IdStrategy::ContentAddressed));
+    assert_eq!(
+        "random".parse::<IdStrategy>(),
+        Err("unknown id strategy 'random' (expected positional, content or hierarchical)".to_string())
+    );
+}
@@ -1,20 +1,46 @@
+use bdir_core::hash::truncate_hash;
 use bdir_core::model::Document;
 
 use crate::schema::{BlockTupleV1, EditPacketV1};
 
+/// Options for Edit Packet generation.
+#[derive(Debug, Clone, Default)]
+pub struct EditPacketOptions {
+    /// Emit truncated hash prefixes of this many hex characters (RFC-0001 §5.1.4).
+    ///
+    /// Applies to both the page hash `h` and every block `text_hash`. Values below
+    /// `MIN_HASH_PREFIX_LEN` (8) are raised to it. `None` emits full digests.
+    pub hash_prefix_len: Option<usize>,
+}
+
 /// Convert a core Document into an ultra-min Edit Packet (v1).
 pub fn from_document(doc: &Document, tid: Option<String>) -> EditPacketV1 {
+    from_document_with_options(doc, tid, EditPacketOptions::default())
+}
+
+/// Convert a core Document into an ultra-min Edit Packet (v1) with explicit options.
+pub fn from_document_with_options(doc: &Document, tid: Option<String>, opts: EditPacketOptions) -> EditPacketV1 {
+    let emit_hash = |h: &str| match opts.hash_prefix_len {
+        Some(len) => truncate_hash(h, len),
+        None => h.to_string(),
+    };
+
     let blocks: Vec<BlockTupleV1> = doc
         .blocks
         .iter()
-        .map(|b| (b.id.clone(), b.kind_code, b.text_hash.clone(), b.text.clone()))
+        .map(|b| (b.id.clone(), b.kind_code, emit_hash(&b.text_hash), b.text.clone()))
         .collect();
 
     EditPacketV1 {
         v: 1,
         tid,
-        h: doc.page_hash.clone(),
+        h: emit_hash(&doc.page_hash),
         ha: doc.hash_algorithm.clone(),
         b: blocks,
+        p: doc
+            .blocks
+            .iter()
+            .filter_map(|b| b.parent.as_ref().map(|p| (b.id.clone(), p.clone())))
+            .collect(),
     }
 }
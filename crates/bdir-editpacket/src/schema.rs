@@ -1,3 +1,5 @@
+use std::collections::BTreeMap;
+
 use serde::{Deserialize, Serialize};
 
 fn default_hash_algorithm() -> String {
@@ -13,7 +15,8 @@ fn default_hash_algorithm() -> String {
 ///   "tid": "optional",
 ///   "h": "pageHash",
 ///   "ha": "xxh64",
-///   "b": [["blockId", kindCode, "textHash", "text"]]
+///   "b": [["blockId", kindCode, "textHash", "text"]],
+///   "p": { "childBlockId": "parentBlockId" }
 /// }
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct EditPacketV1 {
@@ -31,6 +34,10 @@ pub struct EditPacketV1 {
     pub ha: String,
     /// Blocks in reading order
     pub b: Vec<BlockTupleV1>,
+    /// Optional side table of block parents (child id -> parent id), mirroring
+    /// `Block::parent`. Blocks without an entry are top-level; omitted when flat.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub p: BTreeMap<String, String>,
 }
 
 /// Block tuple: [blockId, kindCode, textHash, text]
@@ -0,0 +1,447 @@
+//! HTML import for BDIR Documents.
+//!
+//! Parses a local HTML document (no fetching, no script execution) into blocks and
+//! classifies page chrome into the kindCode ranges of `bdir_codebook::ranges`. Codes
+//! 23, 24 and 42 come from kind codebook v2 (`spec/codebooks/kind-codebook.v2.json`)
+//! and fall in the v1 boilerplate and UI chrome ranges:
+//!
+//! | HTML | kindCode |
+//! |---|---|
+//! | `<nav>` / `role="navigation"` | 21 (secondary-nav), 20 (breadcrumb) when labelled as one |
+//! | `<aside>` / `role="complementary"` | 22 (related-content) |
+//! | page `<footer>` / `role="contentinfo"` | 23 (site-footer) |
+//! | page `<header>` / `role="banner"` | 24 (site-header) |
+//! | `<form>`, `<dialog>`, buttons and form controls | 40 (ui-control) |
+//! | cookie / consent / GDPR banners (by id, class or aria-label) | 42 (consent-banner) |
+//!
+//! Every block inside such a region takes the region's kindCode; the innermost region
+//! wins. Everything else is main content (0–19), mapped like the Markdown importer:
+//! headings, paragraphs, lists, tables, `<pre>` code, figures, and a level-1 heading
+//! that opens the main content becomes the title. Main-content blocks nest under
+//! their heading via `Block::parent`; chrome blocks have no parent.
+//!
+//! `<head>`, scripts, styles, templates and hidden elements (`hidden`,
+//! `aria-hidden="true"`, inline `display:none`) are skipped.
+//!
+//! Block ids combine a kind prefix with a hash of the element's DOM path and its
+//! text (`p-1a2b3c4d`), so they are stable across runs and survive edits elsewhere in
+//! the page that do not move the element. Identical ids get `-2`, `-3`, … in
+//! document order. `HtmlOptions::id_strategy` selects positional or hierarchical ids
+//! instead (see `bdir_core::ids`).
+
+use bdir_codebook::{codes, id_prefix, is_core};
+use bdir_core::ids::{IdGenerator, IdRequest, IdStrategy};
+use bdir_core::model::{Block, BlockId, Document};
+use scraper::{ElementRef, Html, Node};
+
+/// Options for HTML import.
+#[derive(Debug, Clone)]
+pub struct HtmlOptions {
+    /// `hash_algorithm` of the produced Document.
+    ///
+    /// Defaults to `sha256`, the RFC-0001 default.
+    pub hash_algorithm: String,
+    /// How block ids are generated. Defaults to content-addressed ids keyed by the
+    /// element's DOM path.
+    pub id_strategy: IdStrategy,
+}
+
+impl Default for HtmlOptions {
+    fn default() -> Self {
+        Self { hash_algorithm: "sha256".to_string(), id_strategy: IdStrategy::ContentAddressed }
+    }
+}
+
+/// Import an HTML source into a Document with hashes computed.
+pub fn parse_html_str(src: &str) -> Document {
+    parse_html_str_with_options(src, &HtmlOptions::default()).expect("sha256 supported")
+}
+
+/// Import an HTML source with explicit options.
+///
+/// Returns an error if `opts.hash_algorithm` is not a registered hash algorithm.
+pub fn parse_html_str_with_options(src: &str, opts: &HtmlOptions) -> Result<Document, String> {
+    let html = Html::parse_document(src);
+    let mut walker = Walker { ids: IdGenerator::new(opts.id_strategy), ..Walker::default() };
+    let root = html.root_element();
+    walker.walk(root, root.value().name(), Zone::default());
+    walker.flush();
+
+    let mut doc = Document {
+        page_hash: String::new(),
+        hash_algorithm: opts.hash_algorithm.clone(),
+        blocks: walker.blocks,
+    };
+    doc.try_recompute_hashes()?;
+    Ok(doc)
+}
+
+/// Elements whose content is never imported.
+const SKIPPED: &[&str] = &["head", "script", "style", "noscript", "template", "svg", "canvas", "iframe", "object"];
+
+/// Phrasing elements: their text joins the surrounding run instead of forming a block.
+const INLINE: &[&str] = &[
+    "a", "abbr", "b", "bdi", "bdo", "big", "br", "cite", "code", "data", "del", "dfn", "em", "font", "i", "img",
+    "ins", "kbd", "label", "mark", "nobr", "q", "s", "samp", "small", "span", "strong", "sub", "sup", "time", "tt",
+    "u", "var", "wbr",
+];
+
+/// Form controls, imported as one UI-control block each.
+const CONTROLS: &[&str] = &["button", "select", "textarea"];
+
+/// Label words that mark a consent banner or a breadcrumb trail.
+const CONSENT_WORDS: &[&str] = &["cookie", "cookies", "consent", "gdpr"];
+const BREADCRUMB_WORDS: &[&str] = &["breadcrumb", "breadcrumbs"];
+
+/// Words that may accompany those in a chrome label (`cookie-banner`, `breadcrumb-nav`).
+const CHROME_LABEL_WORDS: &[&str] = &[
+    "banner", "bar", "notice", "popup", "modal", "dialog", "overlay", "message", "wall", "box", "container", "wrapper",
+    "nav", "navigation", "trail", "list",
+];
+
+/// Region the walker is in.
+#[derive(Debug, Clone, Copy, Default)]
+struct Zone {
+    /// kindCode forced onto every block in the region (`None` for main content).
+    kind_code: Option<u16>,
+    /// Inside `<main>` or `<article>`, where `<header>` is not page chrome.
+    in_content: bool,
+}
+
+/// Text collected from phrasing content, with whitespace collapsed as it is added.
+#[derive(Default)]
+struct Text {
+    text: String,
+    saw_image: bool,
+    saw_other: bool,
+}
+
+impl Text {
+    fn push(&mut self, s: &str) {
+        for c in s.chars() {
+            if c.is_whitespace() {
+                if !self.text.is_empty() && !self.text.ends_with([' ', '\n']) {
+                    self.text.push(' ');
+                }
+            } else {
+                self.text.push(c);
+                self.saw_other = true;
+            }
+        }
+    }
+
+    fn line_break(&mut self) {
+        let len = self.text.trim_end_matches(' ').len();
+        self.text.truncate(len);
+        self.text.push('\n');
+    }
+
+    fn push_image(&mut self, alt_or_src: &str) {
+        let saw_other = self.saw_other;
+        self.push(alt_or_src);
+        self.saw_other = saw_other;
+        self.saw_image = true;
+    }
+
+    /// Collect the text of `el`'s descendants.
+    fn collect(&mut self, el: ElementRef<'_>) {
+        for child in el.children() {
+            match child.value() {
+                Node::Text(t) => self.push(t),
+                Node::Element(_) => {
+                    let Some(child) = ElementRef::wrap(child).filter(|c| !skipped(*c)) else {
+                        continue;
+                    };
+                    match child.value().name() {
+                        "br" => self.line_break(),
+                        "img" => self.push_image(&image_text(child)),
+                        name if INLINE.contains(&name) => self.collect(child),
+                        // Block-level content nested in a block (e.g. `<p>` in `<li>`).
+                        _ => {
+                            self.push(" ");
+                            self.collect(child);
+                            self.push(" ");
+                        }
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+
+    fn finish(&self) -> String {
+        self.text.trim().to_string()
+    }
+}
+
+#[derive(Default)]
+struct Walker {
+    blocks: Vec<Block>,
+    ids: IdGenerator,
+    /// Open main-content headings, outermost first: (level, id).
+    headings: Vec<(u8, BlockId)>,
+    /// Phrasing content directly inside a container: (DOM path, zone, text).
+    run: Option<(String, Zone, Text)>,
+}
+
+impl Walker {
+    fn walk(&mut self, el: ElementRef<'_>, path: &str, zone: Zone) {
+        let mut seen: Vec<(&str, usize)> = Vec::new();
+        for (index, child) in el.children().enumerate() {
+            match child.value() {
+                Node::Text(t) if !t.trim().is_empty() || self.run.is_some() => {
+                    self.run_text(path, index, zone).push(t);
+                }
+                Node::Element(_) => {
+                    let Some(child) = ElementRef::wrap(child) else {
+                        continue;
+                    };
+                    let name = child.value().name();
+                    let position = match seen.iter_mut().find(|(n, _)| *n == name) {
+                        Some((_, count)) => {
+                            *count += 1;
+                            *count
+                        }
+                        None => {
+                            seen.push((name, 1));
+                            1
+                        }
+                    };
+                    if skipped(child) {
+                        continue;
+                    }
+                    let child_path = format!("{path}/{name}[{position}]");
+                    if INLINE.contains(&name) {
+                        let run = self.run_text(path, index, zone);
+                        match name {
+                            "br" => run.line_break(),
+                            "img" => run.push_image(&image_text(child)),
+                            _ => run.collect(child),
+                        }
+                        continue;
+                    }
+                    self.flush();
+                    self.element(child, &child_path, zone);
+                }
+                _ => {}
+            }
+        }
+        self.flush();
+    }
+
+    fn element(&mut self, el: ElementRef<'_>, path: &str, zone: Zone) {
+        let name = el.value().name();
+        let zone = Zone {
+            kind_code: classify(el, zone.in_content).or(zone.kind_code),
+            in_content: zone.in_content || matches!(name, "main" | "article"),
+        };
+        match name {
+            "h1" | "h2" | "h3" | "h4" | "h5" | "h6" => {
+                let level = name[1..].parse().unwrap_or(1);
+                self.emit(codes::HEADING, level, collect_text(el).finish(), path, zone);
+            }
+            "p" => {
+                let text = collect_text(el);
+                let kind_code = if text.saw_image && !text.saw_other { codes::FIGURE } else { codes::PARAGRAPH };
+                self.emit(kind_code, 0, text.finish(), path, zone);
+            }
+            "ul" | "ol" | "dl" | "menu" => {
+                let mut lines = Vec::new();
+                list_lines(el, 0, &mut lines);
+                self.emit(codes::LIST, 0, lines.join("\n"), path, zone);
+            }
+            "table" => self.emit(codes::TABLE, 0, table_text(el), path, zone),
+            "pre" => {
+                let text: String = el.text().collect();
+                self.emit(codes::CODE, 0, text.trim_end_matches('\n').to_string(), path, zone);
+            }
+            "figure" => self.emit(codes::FIGURE, 0, figure_text(el), path, zone),
+            "hr" => {}
+            _ if CONTROLS.contains(&name) => {
+                let zone = Zone { kind_code: Some(codes::UI_CONTROL), ..zone };
+                self.emit(codes::PARAGRAPH, 0, collect_text(el).finish(), path, zone);
+            }
+            "input" => {}
+            _ => self.walk(el, path, zone),
+        }
+    }
+
+    /// Text run for phrasing content at `index` in the container at `path`.
+    fn run_text(&mut self, path: &str, index: usize, zone: Zone) -> &mut Text {
+        &mut self.run.get_or_insert_with(|| (format!("{path}/#{}", index + 1), zone, Text::default())).2
+    }
+
+    /// Emit the pending phrasing run as a paragraph (or figure, if it is only images).
+    fn flush(&mut self) {
+        if let Some((path, zone, text)) = self.run.take() {
+            let kind_code = if text.saw_image && !text.saw_other { codes::FIGURE } else { codes::PARAGRAPH };
+            self.emit(kind_code, 0, text.finish(), &path, zone);
+        }
+    }
+
+    fn emit(&mut self, kind_code: u16, level: u8, text: String, path: &str, zone: Zone) {
+        if text.is_empty() {
+            return;
+        }
+        let mut kind_code = zone.kind_code.unwrap_or(kind_code);
+        let opens_content = !self.blocks.iter().any(|b| is_core(b.kind_code));
+        if kind_code == codes::HEADING && level == 1 && opens_content {
+            kind_code = codes::TITLE;
+        }
+
+        let opens_section = is_core(kind_code) && level > 0;
+        if opens_section {
+            while self.headings.last().is_some_and(|(l, _)| *l >= level) {
+                self.headings.pop();
+            }
+        }
+        let parent = if is_core(kind_code) { self.headings.last().map(|(_, id)| id.clone()) } else { None };
+        let id = self.ids.next_id(&IdRequest {
+            prefix: id_prefix(kind_code),
+            text: &text,
+            parent: parent.as_deref(),
+            key: Some(path),
+        });
+        if opens_section {
+            self.headings.push((level, id.clone()));
+        }
+
+        self.blocks.push(Block { id, kind_code, text_hash: String::new(), text, parent });
+    }
+}
+
+fn collect_text(el: ElementRef<'_>) -> Text {
+    let mut text = Text::default();
+    text.collect(el);
+    text
+}
+
+/// One line per item, nested lists indented by two spaces per level.
+fn list_lines(list: ElementRef<'_>, depth: usize, lines: &mut Vec<String>) {
+    for item in list.child_elements().filter(|c| !skipped(*c)) {
+        if !matches!(item.value().name(), "li" | "dt" | "dd") {
+            continue;
+        }
+        let mut text = Text::default();
+        let mut nested = Vec::new();
+        for child in item.children() {
+            match ElementRef::wrap(child) {
+                Some(c) if matches!(c.value().name(), "ul" | "ol" | "dl") => nested.push(c),
+                Some(c) if skipped(c) => {}
+                Some(c) if c.value().name() == "br" => text.line_break(),
+                Some(c) if c.value().name() == "img" => text.push_image(&image_text(c)),
+                Some(c) => {
+                    text.push(" ");
+                    text.collect(c);
+                    text.push(" ");
+                }
+                None => {
+                    if let Node::Text(t) = child.value() {
+                        text.push(t);
+                    }
+                }
+            }
+        }
+        let line = text.finish();
+        if !line.is_empty() {
+            lines.push(format!("{}{line}", "  ".repeat(depth)));
+        }
+        for list in nested {
+            list_lines(list, depth + 1, lines);
+        }
+    }
+}
+
+/// One line per row, cells joined with ` | ` (nested tables are not descended into).
+fn table_text(table: ElementRef<'_>) -> String {
+    let mut rows = Vec::new();
+    let mut stack: Vec<ElementRef<'_>> = table.child_elements().collect();
+    stack.reverse();
+    while let Some(el) = stack.pop() {
+        match el.value().name() {
+            "thead" | "tbody" | "tfoot" => stack.extend(el.child_elements().collect::<Vec<_>>().into_iter().rev()),
+            "tr" => {
+                let cells: Vec<String> = el
+                    .child_elements()
+                    .filter(|c| matches!(c.value().name(), "th" | "td"))
+                    .map(|c| collect_text(c).finish())
+                    .collect();
+                if cells.iter().any(|c| !c.is_empty()) {
+                    rows.push(cells.join(" | "));
+                }
+            }
+            _ => {}
+        }
+    }
+    rows.join("\n")
+}
+
+/// Caption, else the images' alt text (or URLs).
+fn figure_text(figure: ElementRef<'_>) -> String {
+    if let Some(caption) = figure.child_elements().find(|c| c.value().name() == "figcaption") {
+        let caption = collect_text(caption).finish();
+        if !caption.is_empty() {
+            return caption;
+        }
+    }
+    collect_text(figure).finish()
+}
+
+/// Alt text of an image, or its URL when it has none.
+fn image_text(img: ElementRef<'_>) -> String {
+    let alt = img.attr("alt").map(str::trim).unwrap_or("");
+    if alt.is_empty() { img.attr("src").unwrap_or("").to_string() } else { alt.to_string() }
+}
+
+fn skipped(el: ElementRef<'_>) -> bool {
+    let style = el.attr("style").unwrap_or("").replace(' ', "").to_ascii_lowercase();
+    SKIPPED.contains(&el.value().name())
+        || el.attr("hidden").is_some()
+        || el.attr("aria-hidden") == Some("true")
+        || style.contains("display:none")
+}
+
+/// Chrome kindCode of the region `el` opens, if any.
+fn classify(el: ElementRef<'_>, in_content: bool) -> Option<u16> {
+    let name = el.value().name();
+    let role = el.attr("role").unwrap_or("");
+    let labels: Vec<String> = el
+        .attr("id")
+        .into_iter()
+        .chain(el.attr("class").into_iter().flat_map(str::split_whitespace))
+        .chain(el.attr("aria-label"))
+        .map(str::to_ascii_lowercase)
+        .collect();
+    let labelled = |words: &[&str]| labels.iter().any(|label| chrome_label(label, words));
+
+    if labelled(CONSENT_WORDS) {
+        return Some(codes::CONSENT_BANNER);
+    }
+    if labelled(BREADCRUMB_WORDS) {
+        return Some(codes::BREADCRUMB);
+    }
+    match (name, role) {
+        ("nav", _) | (_, "navigation") => Some(codes::SECONDARY_NAV),
+        ("aside", _) | (_, "complementary") => Some(codes::RELATED_CONTENT),
+        ("footer", _) if !in_content => Some(codes::SITE_FOOTER),
+        (_, "contentinfo") => Some(codes::SITE_FOOTER),
+        ("header", _) if !in_content => Some(codes::SITE_HEADER),
+        (_, "banner") => Some(codes::SITE_HEADER),
+        ("form" | "dialog", _) | (_, "dialog" | "alertdialog" | "toolbar" | "menubar" | "search") => {
+            Some(codes::UI_CONTROL)
+        }
+        _ => None,
+    }
+}
+
+/// Whether an id, class token or aria-label names a chrome region: it contains one of
+/// `words` and nothing but chrome words (`cookie-banner`, `cookieConsent`, "Cookie
+/// notice"), so `cookie-recipes` or "Consent forms guide" do not count.
+fn chrome_label(label: &str, words: &[&str]) -> bool {
+    let is_chrome_word = |w: &str| words.contains(&w) || CHROME_LABEL_WORDS.contains(&w);
+    let parts: Vec<&str> = label.split(|c: char| !c.is_ascii_alphanumeric()).filter(|p| !p.is_empty()).collect();
+    let matches_word = |p: &str| {
+        words.iter().any(|w| p.strip_prefix(w).is_some_and(|rest| rest.is_empty() || is_chrome_word(rest)))
+    };
+    parts.iter().any(|p| matches_word(p)) && parts.iter().all(|p| is_chrome_word(p) || matches_word(p))
+}
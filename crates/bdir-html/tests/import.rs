@@ -0,0 +1,139 @@
+use std::path::Path;
+
+use bdir_codebook::{codes, is_boilerplate, is_core, is_ui_chrome};
+use bdir_core::model::Document;
+use bdir_html::{parse_html_str, parse_html_str_with_options, HtmlOptions};
+
+fn read_fixture(name: &str) -> String {
+    let path = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests").join("fixtures").join(name);
+    std::fs::read_to_string(&path).unwrap_or_else(|e| panic!("failed to read {}: {e}", path.display()))
+}
+
+/// (kind_code, text, text of the parent block).
+fn rows(doc: &Document) -> Vec<(u16, &str, Option<&str>)> {
+    doc.blocks
+        .iter()
+        .map(|b| {
+            let parent = b.parent.as_deref().map(|p| doc.blocks.iter().find(|x| x.id == p).unwrap().text.as_str());
+            (b.kind_code, b.text.as_str(), parent)
+        })
+        .collect()
+}
+
+#[test]
+fn chrome_is_classified_and_main_content_keeps_core_kinds() {
+    let doc = parse_html_str(&read_fixture("article.html"));
+
+    assert_eq!(
+        rows(&doc),
+        vec![
+            (codes::SITE_HEADER, "Pet Guides", None),
+            (codes::SECONDARY_NAV, "Cats\nDogs", None),
+            (codes::BREADCRUMB, "Home\nCats", None),
+            (codes::TITLE, "Caring for cats", None),
+            (codes::PARAGRAPH, "Cats are small, furry animals.\nThey sleep a lot.", Some("Caring for cats")),
+            (codes::HEADING, "Feeding", Some("Caring for cats")),
+            (codes::LIST, "Feed twice a day\nFresh water\n  change daily", Some("Feeding")),
+            (codes::FIGURE, "A tabby cat at dinner", Some("Feeding")),
+            (codes::HEADING, "Breeds", Some("Caring for cats")),
+            (codes::TABLE, "Breed | Coat\nTabby | Striped", Some("Breeds")),
+            (codes::CODE, "brush --gentle\ncomb", Some("Breeds")),
+            (codes::PARAGRAPH, "Loose text in a div.", Some("Breeds")),
+            (codes::UI_CONTROL, "Share", None),
+            (codes::RELATED_CONTENT, "Related", None),
+            (codes::RELATED_CONTENT, "Caring for dogs", None),
+            (codes::SITE_FOOTER, "\u{a9} 2026 Pet Guides", None),
+            (codes::CONSENT_BANNER, "We use cookies.", None),
+            (codes::UI_CONTROL, "Accept", None),
+        ]
+    );
+
+    for block in &doc.blocks {
+        assert!(is_core(block.kind_code) || is_boilerplate(block.kind_code) || is_ui_chrome(block.kind_code));
+    }
+    assert!(doc.blocks.iter().all(|b| !b.text.contains("analytics") && b.text != "Hidden note"));
+}
+
+#[test]
+fn ids_come_from_dom_position_and_content() {
+    let src = read_fixture("article.html");
+    let doc = parse_html_str(&src);
+    assert_eq!(doc, parse_html_str(&src));
+    assert_eq!(doc.blocks[3].id.split('-').next(), Some("t"));
+    assert!(doc.blocks.iter().all(|b| b.id.len() > 2 && !b.id.contains(' ')));
+
+    // Editing one paragraph only changes that paragraph's id.
+    let edited = parse_html_str(&src.replace("Loose text in a div.", "Different loose text."));
+    let changed: Vec<usize> = (0..doc.blocks.len()).filter(|&i| doc.blocks[i].id != edited.blocks[i].id).collect();
+    assert_eq!(changed, vec![11]);
+
+    // Content appended after a block does not move it.
+    let appended = parse_html_str(&src.replace("</footer>", "<p>Contact us</p></footer>"));
+    assert_eq!(appended.blocks.len(), doc.blocks.len() + 1);
+    assert_eq!(appended.blocks[..16], doc.blocks[..16]);
+}
+
+#[test]
+fn repeated_blocks_get_distinct_ids_and_roles_classify_like_elements() {
+    let src = r#"<body>
+        <div role="navigation"><a href="/a">A</a></div>
+        <div role="contentinfo">Footer text</div>
+        <section class="gdpr-notice"><p>Consent needed</p></section>
+        <form><label>Email</label></form>
+        <p>Same</p><p>Same</p>
+        <p><img src="only.png"></p>
+    </body>"#;
+    let doc = parse_html_str(src);
+
+    let kinds: Vec<(u16, &str)> = doc.blocks.iter().map(|b| (b.kind_code, b.text.as_str())).collect();
+    assert_eq!(
+        kinds,
+        vec![
+            (codes::SECONDARY_NAV, "A"),
+            (codes::SITE_FOOTER, "Footer text"),
+            (codes::CONSENT_BANNER, "Consent needed"),
+            (codes::UI_CONTROL, "Email"),
+            (codes::PARAGRAPH, "Same"),
+            (codes::PARAGRAPH, "Same"),
+            (codes::FIGURE, "only.png"),
+        ]
+    );
+    assert_ne!(doc.blocks[4].id, doc.blocks[5].id);
+}
+
+#[test]
+fn chrome_labels_match_whole_tokens_and_content_footers_stay_content() {
+    let src = r#"<body>
+        <main class="cookie-recipes">
+            <article id="consent-forms-guide">
+                <p>Bake the cookies.</p>
+                <footer><p>Posted in Baking</p></footer>
+            </article>
+        </main>
+        <div class="modal cookieConsent"><p>Accept cookies?</p></div>
+        <footer><p>Site footer</p></footer>
+    </body>"#;
+    let doc = parse_html_str(src);
+
+    let kinds: Vec<(u16, &str)> = doc.blocks.iter().map(|b| (b.kind_code, b.text.as_str())).collect();
+    assert_eq!(
+        kinds,
+        vec![
+            (codes::PARAGRAPH, "Bake the cookies."),
+            (codes::PARAGRAPH, "Posted in Baking"),
+            (codes::CONSENT_BANNER, "Accept cookies?"),
+            (codes::SITE_FOOTER, "Site footer"),
+        ]
+    );
+}
+
+#[test]
+fn hash_algorithm_is_configurable_and_checked() {
+    let opts = HtmlOptions { hash_algorithm: "xxh64".to_string(), ..HtmlOptions::default() };
+    let doc = parse_html_str_with_options("<p>Hello</p>", &opts).unwrap();
+    assert_eq!(doc.hash_algorithm, "xxh64");
+    assert_eq!(doc.blocks[0].text_hash.len(), 16);
+
+    let bad = HtmlOptions { hash_algorithm: "md5".to_string(), ..HtmlOptions::default() };
+    assert!(parse_html_str_with_options("<p>Hello</p>", &bad).is_err());
+}
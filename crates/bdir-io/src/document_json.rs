@@ -8,7 +8,7 @@
 use std::fmt;
 
 use bdir_core::model::Document;
-use bdir_core::hash::hash_hex;
+use bdir_core::hash::{lookup_hash_algorithm, supported_hash_algorithms};
 use serde::de::Error as _;
 use serde_json::Value;
 
@@ -58,7 +58,8 @@ impl fmt::Display for DocumentJsonError {
             DocumentJsonError::UnsupportedHashAlgorithm(algo) => {
                 write!(
                     f,
-                    "Unsupported hash_algorithm '{algo}'. Supported algorithms: sha256, xxh64."
+                    "Unsupported hash_algorithm '{algo}'. Supported algorithms: {}.",
+                    supported_hash_algorithms().join(", ")
                 )
             }
         }
@@ -105,7 +106,7 @@ pub fn parse_document_json_str(s: &str) -> Result<Document, DocumentJsonError> {
 
     // RFC-0001: receivers MUST reject unrecognized hash algorithms.
     let algo = doc.hash_algorithm.trim().to_lowercase();
-    if algo.is_empty() || hash_hex(&algo, "").is_none() {
+    if algo.is_empty() || lookup_hash_algorithm(&algo).is_none() {
         return Err(DocumentJsonError::UnsupportedHashAlgorithm(doc.hash_algorithm));
     }
     doc.hash_algorithm = algo;
@@ -3,7 +3,8 @@
 //! application).
 //!
 //! This crate intentionally contains **no** HTML extraction, crawling, or AI logic.
-//! Those belong in higher layers. `bdir-io` focuses on:
+//! Those belong in higher layers (HTML import lives in the optional `bdir-html` crate).
+//! `bdir-io` focuses on:
 //! - stable types
 //! - canonical JSON
 //! - hashing
@@ -21,7 +22,16 @@
 #[doc(hidden)]
 pub mod core {
     pub use bdir_core::model::{Block, BlockId, Document};
-    pub use bdir_core::hash::{canonicalize_text, xxh64_hex};
+    pub use bdir_core::hash::{canonicalize_text, migrate_legacy_hash_algorithm, xxh3_hex, xxh64_hex};
+    pub use bdir_core::hash::{
+        HashAlgorithm,
+        HashRegistry,
+        lookup_hash_algorithm,
+        register_hash_algorithm,
+        supported_hash_algorithms,
+    };
+    pub use bdir_core::hash::{MIN_HASH_PREFIX_LEN, hash_prefix_matches, truncate_hash};
+    pub use bdir_core::ids::{IdGenerator, IdRequest, IdStrategy};
 }
 
 /// Deterministic JSON canonicalization helpers.
@@ -35,6 +45,9 @@ pub mod hashing;
 /// Helpers for parsing Document JSON with improved diagnostics.
 pub mod document_json;
 
+/// Helpers for parsing Patch JSON of any supported wire version.
+pub mod patch_json;
+
 /// Version constants for RFC conformance and CI gating.
 pub mod version;
 
@@ -42,21 +55,24 @@ pub mod version;
 #[doc(hidden)]
 pub mod editpacket {
     pub use bdir_editpacket::schema::{BlockTupleV1, EditPacketV1};
-    pub use bdir_editpacket::convert::from_document;
+    pub use bdir_editpacket::convert::{from_document, from_document_with_options, EditPacketOptions};
     pub use bdir_editpacket::serialize::{to_minified_json, to_pretty_json};
 }
 
 // Re-export patch schema + helpers.
 #[doc(hidden)]
 pub mod patch {
-    pub use bdir_patch::schema::{OpType, PatchOpV1, PatchV1};
-    pub use bdir_patch::{DiagnosticCode, ValidationDiagnostic, ValidationError};
+    pub use bdir_patch::schema::{OpType, PatchOpV1, PatchV1, PatchV2, VersionedPatch};
+    pub use bdir_patch::{ApplyError, ClosestMatch, DiagnosticCode, ValidationDiagnostic, ValidationError};
     pub use bdir_patch::{
         apply_patch_against_edit_packet,
         apply_patch_against_edit_packet_with_options,
         apply_patch_against_document,
         apply_patch_against_document_with_options,
+        assign_new_block_ids,
+        assign_new_block_ids_against_edit_packet,
         KindCodePolicy,
+        MatchMode,
         ValidateOptions,
         validate_patch,
         validate_patch_with_options,
@@ -66,8 +82,27 @@ pub mod patch {
         validate_patch_against_edit_packet_with_diagnostics,
         validate_patch_with_telemetry,
         validate_patch_against_edit_packet_with_telemetry,
+        tolerant_match_diagnostics,
+        tolerant_match_diagnostics_against_edit_packet,
         apply_patch_against_edit_packet_with_telemetry,
         apply_patch_against_document_with_telemetry,
+        diff_documents,
+        diff_documents_with_options,
+        DiffError,
+        invert_patch,
+        invert_patch_with_options,
+        InvertError,
+        merge_patches,
+        merge_patches_with_options,
+        MergeConflict,
+        MergeConflictKind,
+        MergeOpRef,
+        MergeReport,
+        rebase_patch,
+        rebase_patch_with_options,
+        RebaseConflict,
+        RebaseConflictReason,
+        RebaseReport,
     };
 }
 
@@ -76,12 +111,14 @@ pub mod patch {
 /// This is the **only supported** import surface for external users.
 pub mod prelude {
     pub use crate::core::{Block, BlockId, Document};
-    pub use crate::editpacket::{BlockTupleV1, EditPacketV1};
-    pub use crate::patch::{OpType, PatchOpV1, PatchV1};
+    pub use crate::core::{HashAlgorithm, register_hash_algorithm, supported_hash_algorithms};
+    pub use crate::editpacket::{BlockTupleV1, EditPacketOptions, EditPacketV1};
+    pub use crate::patch::{OpType, PatchOpV1, PatchV1, PatchV2, VersionedPatch};
     pub use bdir_patch::PatchTelemetry;
-    pub use crate::patch::{DiagnosticCode, ValidationDiagnostic, ValidationError};
+    pub use crate::patch::{ApplyError, ClosestMatch, DiagnosticCode, ValidationDiagnostic, ValidationError};
     pub use crate::{canonical_json, hashing};
     pub use crate::document_json::{parse_document_json_str, DocumentJsonError};
+    pub use crate::patch_json::{parse_patch_json_str, PatchJsonError};
 }
 
 /// Internal validation helpers.
@@ -89,6 +126,7 @@ pub mod prelude {
 pub mod validate {
     pub use bdir_patch::{
         KindCodePolicy,
+        MatchMode,
         ValidateOptions,
         validate_patch,
         validate_patch_with_options,
@@ -96,6 +134,9 @@ pub mod validate {
         validate_patch_against_edit_packet,
         validate_patch_against_edit_packet_with_options,
         validate_patch_against_edit_packet_with_diagnostics,
+        tolerant_match_diagnostics,
+        tolerant_match_diagnostics_against_edit_packet,
+        ClosestMatch,
         DiagnosticCode,
         ValidationDiagnostic,
         ValidationError,
@@ -105,5 +146,5 @@ pub mod validate {
 /// Internal application helpers.
 #[doc(hidden)]
 pub mod apply {
-    pub use bdir_patch::{apply_patch_against_edit_packet, apply_patch_against_document};
+    pub use bdir_patch::{apply_patch_against_edit_packet, apply_patch_against_document, ApplyError};
 }
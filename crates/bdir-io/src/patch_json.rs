@@ -0,0 +1,80 @@
+//! Helpers for parsing Patch JSON of any supported wire version.
+//!
+//! The parser reads `v` first and dispatches to the matching wire type, so callers
+//! get a clear "unsupported version" error instead of a serde shape error when a
+//! producer is ahead of (or behind) this engine.
+
+use std::fmt;
+
+use bdir_patch::schema::{VersionedPatch, SUPPORTED_PATCH_VERSIONS};
+use serde::de::Error as _;
+use serde_json::Value;
+
+/// A structured error for parsing a Patch JSON payload.
+#[derive(Debug)]
+pub enum PatchJsonError {
+    /// The input was not valid JSON.
+    InvalidJson(serde_json::Error),
+    /// The input JSON was valid, but had no integer `v` field.
+    MissingVersion,
+    /// The patch declared a wire version this engine does not support.
+    UnsupportedVersion(u64),
+    /// JSON was valid, but did not match the Patch schema/shape for its version.
+    InvalidPatchShape(serde_json::Error),
+}
+
+fn supported_versions() -> String {
+    SUPPORTED_PATCH_VERSIONS.iter().map(u8::to_string).collect::<Vec<_>>().join(", ")
+}
+
+impl fmt::Display for PatchJsonError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PatchJsonError::InvalidJson(e) => {
+                write!(f, "Invalid JSON: {e}")
+            }
+            PatchJsonError::MissingVersion => {
+                write!(
+                    f,
+                    "Invalid Patch JSON: missing integer field `v`. Supported versions: {}.",
+                    supported_versions()
+                )
+            }
+            PatchJsonError::UnsupportedVersion(v) => {
+                write!(f, "Unsupported patch version {v}. Supported versions: {}.", supported_versions())
+            }
+            PatchJsonError::InvalidPatchShape(e) => {
+                write!(f, "Invalid Patch JSON shape: {e}.")
+            }
+        }
+    }
+}
+
+impl std::error::Error for PatchJsonError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            PatchJsonError::InvalidJson(e) => Some(e),
+            PatchJsonError::InvalidPatchShape(e) => Some(e),
+            PatchJsonError::MissingVersion => None,
+            PatchJsonError::UnsupportedVersion(_) => None,
+        }
+    }
+}
+
+/// Parse a Patch JSON string, dispatching on its `v` field.
+///
+/// Use `VersionedPatch::into_patch` to validate or apply the result, or
+/// `VersionedPatch::upgrade` to convert it to the latest wire version.
+pub fn parse_patch_json_str(s: &str) -> Result<VersionedPatch, PatchJsonError> {
+    let v: Value = serde_json::from_str(s).map_err(PatchJsonError::InvalidJson)?;
+    let obj = v
+        .as_object()
+        .ok_or_else(|| PatchJsonError::InvalidPatchShape(serde_json::Error::custom("expected a JSON object")))?;
+
+    let version = obj.get("v").and_then(Value::as_u64).ok_or(PatchJsonError::MissingVersion)?;
+    if !SUPPORTED_PATCH_VERSIONS.iter().any(|&s| u64::from(s) == version) {
+        return Err(PatchJsonError::UnsupportedVersion(version));
+    }
+
+    serde_json::from_value(v).map_err(PatchJsonError::InvalidPatchShape)
+}
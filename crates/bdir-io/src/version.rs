@@ -11,7 +11,16 @@ pub const EDIT_PACKET_V: u8 = 1;
 /// Patch wire format version.
 pub const PATCH_V: u8 = 1;
 
+/// Latest Patch wire format version (`spec/schemas/patch.v2.schema.json`).
+///
+/// `PATCH_V` stays the default for emitted patches; parsers accept every version in
+/// `SUPPORTED_PATCH_VERSIONS`.
+pub const PATCH_V2: u8 = 2;
+
+/// Patch wire format versions accepted by the parser and validator.
+pub const SUPPORTED_PATCH_VERSIONS: &[u8] = bdir_patch::schema::SUPPORTED_PATCH_VERSIONS;
+
 /// JSON Schema bundle version for on-disk schemas under `spec/schemas/`.
 ///
 /// Bump this if the schema constraints change (even if `v` stays the same).
-pub const SCHEMA_BUNDLE_V: u8 = 1;
+pub const SCHEMA_BUNDLE_V: u8 = 12;
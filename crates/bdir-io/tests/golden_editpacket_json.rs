@@ -27,25 +27,25 @@ fn golden_edit_packet_pretty_json() {
 
     let expected = r#"{
   "v": 1,
-  "h": "4a0d9b1ad0795617",
+  "h": "ff0e1875ab7f8425",
   "ha": "xxh64",
   "b": [
     [
       "t1",
       0,
-      "2d85646dba5758f4",
+      "f932d646637097b0",
       "Example Page Title"
     ],
     [
       "p1",
       2,
-      "a3c9cb84972dd67e",
+      "6dcea198d9a3abcd",
       "This is an example paragraph with a typo teh."
     ],
     [
       "b1",
       20,
-      "7a6ea7f684209672",
+      "4f7d6d674ab4edac",
       "Home > Section > Page"
     ]
   ]
@@ -64,7 +64,7 @@ fn golden_edit_packet_minified_json() {
 
     let min = editpacket::to_minified_json(&packet).expect("minified json");
 
-    let expected = r#"{"v":1,"h":"4a0d9b1ad0795617","ha":"xxh64","b":[["t1",0,"2d85646dba5758f4","Example Page Title"],["p1",2,"a3c9cb84972dd67e","This is an example paragraph with a typo teh."],["b1",20,"7a6ea7f684209672","Home > Section > Page"]]}"#;
+    let expected = r#"{"v":1,"h":"ff0e1875ab7f8425","ha":"xxh64","b":[["t1",0,"f932d646637097b0","Example Page Title"],["p1",2,"6dcea198d9a3abcd","This is an example paragraph with a typo teh."],["b1",20,"4f7d6d674ab4edac","Home > Section > Page"]]}"#;
 
     assert_eq!(min, expected);
 }
@@ -25,6 +25,16 @@ static PATCH_SCHEMA: Lazy<Result<Validator, String>> = Lazy::new(|| {
         .map_err(|e| format!("compile patch schema: {e}"))
 });
 
+static PATCH_V2_SCHEMA: Lazy<Result<Validator, String>> = Lazy::new(|| {
+    let schema_json: Value = serde_json::from_str(include_str!(
+        "../../../spec/schemas/patch.v2.schema.json"
+    ))
+    .map_err(|e| format!("invalid patch v2 schema JSON: {e}"))?;
+
+    Validator::new(&schema_json)
+        .map_err(|e| format!("compile patch v2 schema: {e}"))
+});
+
 fn edit_packet_schema() -> &'static Validator {
     EDIT_PACKET_SCHEMA.as_ref().unwrap()
 }
@@ -33,6 +43,10 @@ fn patch_schema() -> &'static Validator {
     PATCH_SCHEMA.as_ref().unwrap()
 }
 
+fn patch_v2_schema() -> &'static Validator {
+    PATCH_V2_SCHEMA.as_ref().unwrap()
+}
+
 fn assert_valid(schema: &Validator, instance: &Value) {
     let mut errors = schema.iter_errors(instance).peekable();
     if errors.peek().is_some() {
@@ -67,6 +81,7 @@ fn current_wire_types_conform_to_json_schemas() -> Result<()> {
             kind_code: 2,
             text_hash: String::new(),
             text: "Hello world".to_string(),
+            parent: None,
         }],
     };
     doc.recompute_hashes();
@@ -94,6 +109,11 @@ fn current_wire_types_conform_to_json_schemas() -> Result<()> {
             new_block_id: None,
             kind_code: None,
             text: None,
+            text_hash: None,
+            after_block_id: None,
+            context_before: None,
+            context_after: None,
+            next_block_id: None,
             message: Some("Looks good".to_string()),
             severity: None,
         }],
@@ -123,3 +143,61 @@ fn edit_packet_ha_is_optional_and_defaults_to_sha256() -> Result<()> {
 
     Ok(())
 }
+
+#[test]
+fn upgraded_patches_conform_to_the_v2_schema() -> Result<()> {
+    let patch = parse_patch_json_str(include_str!("../../../examples/patch.valid.json"))?;
+    assert_eq!(patch.version(), bdir_io::version::PATCH_V);
+
+    let upgraded = patch.upgrade();
+    assert_eq!(upgraded.v, bdir_io::version::PATCH_V2);
+    let upgraded_json: Value = serde_json::to_value(&upgraded)?;
+    assert_valid(patch_v2_schema(), &upgraded_json);
+
+    // v2 drops the v1 spelling aliases and the `"first"` occurrence.
+    let legacy = serde_json::json!({
+        "v": 2,
+        "ops": [{ "op": "delete", "block_id": "p1", "before": "a typo", "occurrence": "first" }]
+    });
+    assert!(!patch_v2_schema().is_valid(&legacy));
+    let aliased = serde_json::json!({
+        "v": 2,
+        "ops": [{ "op": "insert_after", "blockId": "p1", "new_block_id": "p2", "kind_code": 2, "text": "x" }]
+    });
+    assert!(!patch_v2_schema().is_valid(&aliased));
+
+    Ok(())
+}
+
+#[test]
+fn patch_json_parser_reports_version_errors() {
+    let err = parse_patch_json_str(r#"{"v": 9, "ops": []}"#).unwrap_err();
+    assert!(matches!(err, PatchJsonError::UnsupportedVersion(9)));
+    assert_eq!(err.to_string(), "Unsupported patch version 9. Supported versions: 1, 2.");
+
+    let err = parse_patch_json_str(r#"{"ops": []}"#).unwrap_err();
+    assert!(matches!(err, PatchJsonError::MissingVersion));
+
+    let err = parse_patch_json_str(r#"{"v": 2, "ops": [{"op": "replace"}]}"#).unwrap_err();
+    assert!(matches!(err, PatchJsonError::InvalidPatchShape(_)));
+}
+
+#[test]
+fn edit_packet_parent_table_conforms_to_json_schema() -> Result<()> {
+    let packet_json: Value = serde_json::json!({
+        "v": 1,
+        "h": "deadbeef",
+        "ha": "xxh64",
+        "b": [["h1", 1, "cafebabe", "Cats"], ["p1", 2, "cafebabe", "Cats sleep."]],
+        "p": { "p1": "h1" }
+    });
+    assert_valid(edit_packet_schema(), &packet_json);
+
+    let packet: EditPacketV1 = serde_json::from_value(packet_json)?;
+    assert_eq!(packet.p.get("p1").map(String::as_str), Some("h1"));
+
+    let bad = serde_json::json!({ "v": 1, "h": "deadbeef", "b": [], "p": { "p1": 7 } });
+    assert!(!edit_packet_schema().is_valid(&bad));
+
+    Ok(())
+}
@@ -0,0 +1,336 @@
+//! Event-stream builder mapping Markdown blocks onto Document blocks.
+
+use std::ops::Range;
+
+use bdir_codebook::{codes, id_prefix};
+use bdir_core::ids::{IdGenerator, IdRequest};
+use bdir_core::model::{Block, BlockId, Document};
+use pulldown_cmark::{Event, HeadingLevel, Options, Parser, Tag, TagEnd};
+use serde::{Deserialize, Serialize};
+
+use crate::MarkdownOptions;
+
+/// A run of block text copied verbatim from the source.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TextSegment {
+    /// Byte range in the block text.
+    pub text: Range<usize>,
+    /// Byte range in the Markdown source; `source` holds exactly the same bytes as `text`.
+    pub source: Range<usize>,
+    /// Code (inline or block) or raw HTML: edits are written without Markdown escaping.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub raw: bool,
+}
+
+/// Where one block came from in the Markdown source.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BlockSpan {
+    pub block_id: BlockId,
+    /// Byte range of the whole Markdown block (markup included).
+    pub range: Range<usize>,
+    /// Verbatim text runs in block-text order. Text produced by markup (list
+    /// indentation, table separators, entities, image URLs) has no segment.
+    pub segments: Vec<TextSegment>,
+}
+
+/// A Document imported from Markdown, with one source span per block (same order).
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MarkdownImport {
+    pub document: Document,
+    pub spans: Vec<BlockSpan>,
+}
+
+/// Import a Markdown source, recording source spans for every block.
+///
+/// The spans are what `write_back` uses to edit the original file in place.
+/// Returns an error if `opts.hash_algorithm` is not a registered hash algorithm.
+pub fn import_markdown_with_spans(src: &str, opts: &MarkdownOptions) -> Result<MarkdownImport, String> {
+    let mut builder = Builder { src, ids: IdGenerator::new(opts.id_strategy), ..Builder::default() };
+    for (event, range) in Parser::new_ext(src, Options::ENABLE_TABLES).into_offset_iter() {
+        builder.event(event, range);
+    }
+
+    let mut document = Document {
+        page_hash: String::new(),
+        hash_algorithm: opts.hash_algorithm.clone(),
+        blocks: builder.blocks,
+    };
+    document.try_recompute_hashes()?;
+    Ok(MarkdownImport { document, spans: builder.spans })
+}
+
+/// Block being collected from the event stream.
+struct Open {
+    kind_code: u16,
+    /// Heading level (1–6); 0 for non-headings.
+    level: u8,
+    text: String,
+    /// Source offset of the block start.
+    start: usize,
+    segments: Vec<TextSegment>,
+    /// Paragraphs only: an image was seen / text outside any image was seen.
+    saw_image: bool,
+    saw_other: bool,
+}
+
+impl Open {
+    fn new(kind_code: u16, start: usize) -> Self {
+        Self {
+            kind_code,
+            level: 0,
+            text: String::new(),
+            start,
+            segments: Vec::new(),
+            saw_image: false,
+            saw_other: false,
+        }
+    }
+}
+
+#[derive(Default)]
+struct Builder<'a> {
+    src: &'a str,
+    blocks: Vec<Block>,
+    spans: Vec<BlockSpan>,
+    ids: IdGenerator,
+    /// Open headings, outermost first: (level, id).
+    headings: Vec<(u8, BlockId)>,
+    open: Option<Open>,
+    /// Nesting depth of lists (the outermost list is the block).
+    list_depth: usize,
+    /// Paragraphs seen in the current list item (separates loose-list paragraphs).
+    item_paragraphs: usize,
+    /// Open images: (text length at the image start, destination URL).
+    images: Vec<(usize, String)>,
+    table_cells: usize,
+    in_code_block: bool,
+}
+
+impl Builder<'_> {
+    fn event(&mut self, event: Event<'_>, range: Range<usize>) {
+        match event {
+            Event::Start(tag) => self.start(tag, range.start),
+            Event::End(tag) => self.end(tag, range.end),
+            Event::Text(t) => {
+                let source = self.verbatim(&t, range, false);
+                let raw = self.in_code_block;
+                self.push_text(&t, source, raw);
+            }
+            Event::Code(t) => {
+                let source = self.verbatim(&t, range, true);
+                self.push_text(&t, source, true);
+            }
+            Event::SoftBreak => self.push_text(" ", None, false),
+            Event::HardBreak => self.push_text("\n", None, false),
+            Event::Html(t) if self.open_is(codes::UNKNOWN) || self.in_container() => {
+                let source = self.verbatim(&t, range, false);
+                self.push_text(&t, source, true);
+            }
+            _ => {}
+        }
+    }
+
+    /// Source range holding `t` verbatim: the event range itself, or for code spans
+    /// the content between the backticks.
+    fn verbatim(&self, t: &str, range: Range<usize>, inner: bool) -> Option<Range<usize>> {
+        let found = self.src.get(range.clone())?;
+        if found == t {
+            return Some(range);
+        }
+        let offset = if inner { found.find(t)? } else { return None };
+        Some(range.start + offset..range.start + offset + t.len())
+    }
+
+    fn start(&mut self, tag: Tag<'_>, at: usize) {
+        match tag {
+            // Block-level content of a list item or table becomes lines of that block.
+            Tag::Paragraph | Tag::Heading { .. } | Tag::Table(_) | Tag::HtmlBlock if self.in_container() => {
+                // Loose list items: one line per paragraph.
+                if self.item_paragraphs > 0 {
+                    self.push_line_break();
+                }
+                self.item_paragraphs += 1;
+            }
+            Tag::Heading { level, .. } => {
+                let mut open = Open::new(codes::HEADING, at);
+                open.level = heading_level(level);
+                self.open = Some(open);
+            }
+            Tag::Paragraph => self.open = Some(Open::new(codes::PARAGRAPH, at)),
+            Tag::List(_) => {
+                if self.list_depth == 0 && self.open.is_none() {
+                    self.open = Some(Open::new(codes::LIST, at));
+                }
+                self.list_depth += 1;
+            }
+            Tag::Item => {
+                self.item_paragraphs = 0;
+                self.push_line_break();
+            }
+            Tag::Table(_) => self.open = Some(Open::new(codes::TABLE, at)),
+            Tag::TableHead | Tag::TableRow => {
+                self.table_cells = 0;
+                self.push_line_break();
+            }
+            Tag::TableCell => {
+                if self.table_cells > 0 {
+                    self.push_text(" | ", None, false);
+                }
+                self.table_cells += 1;
+            }
+            Tag::CodeBlock(_) => {
+                self.in_code_block = true;
+                if self.open.is_none() {
+                    self.open = Some(Open::new(codes::CODE, at));
+                } else {
+                    self.push_line_break();
+                }
+            }
+            Tag::HtmlBlock => self.open = Some(Open::new(codes::UNKNOWN, at)),
+            Tag::Image { dest_url, .. } => {
+                let start = self.open.as_ref().map_or(0, |o| o.text.len());
+                self.images.push((start, dest_url.to_string()));
+                if let Some(open) = self.open.as_mut() {
+                    open.saw_image = true;
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn end(&mut self, tag: TagEnd, at: usize) {
+        match tag {
+            TagEnd::Heading(_) if self.open_is(codes::HEADING) => self.close(at),
+            TagEnd::Table if self.open_is(codes::TABLE) => self.close(at),
+            TagEnd::HtmlBlock if self.open_is(codes::UNKNOWN) => self.close(at),
+            TagEnd::Paragraph if !self.in_container() => self.close(at),
+            TagEnd::CodeBlock => {
+                self.in_code_block = false;
+                if self.open_is(codes::CODE) {
+                    self.close(at);
+                }
+            }
+            TagEnd::List(_) => {
+                self.list_depth -= 1;
+                if self.list_depth == 0 && self.open_is(codes::LIST) {
+                    self.close(at);
+                }
+            }
+            TagEnd::Image => {
+                // Images without alt text are represented by their URL.
+                if let Some((start, url)) = self.images.pop()
+                    && let Some(open) = self.open.as_mut()
+                    && open.text.len() == start
+                {
+                    open.text.push_str(&url);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn open_is(&self, kind_code: u16) -> bool {
+        self.open.as_ref().is_some_and(|o| o.kind_code == kind_code)
+    }
+
+    /// Inside a list or table, whose text absorbs nested paragraphs, headings, tables,
+    /// HTML and code.
+    fn in_container(&self) -> bool {
+        self.open.as_ref().is_some_and(|o| matches!(o.kind_code, codes::LIST | codes::TABLE))
+    }
+
+    fn push_text(&mut self, t: &str, source: Option<Range<usize>>, raw: bool) {
+        let in_image = !self.images.is_empty();
+        if let Some(open) = self.open.as_mut() {
+            if !in_image && !t.trim().is_empty() {
+                open.saw_other = true;
+            }
+            let start = open.text.len();
+            open.text.push_str(t);
+            if let Some(source) = source.filter(|_| !t.is_empty()) {
+                open.segments.push(TextSegment { text: start..open.text.len(), source, raw });
+            }
+        }
+    }
+
+    /// Start a new line in a list or table block (indenting nested list items).
+    fn push_line_break(&mut self) {
+        let depth = self.list_depth;
+        if let Some(open) = self.open.as_mut().filter(|o| !o.text.is_empty()) {
+            if !open.text.ends_with('\n') {
+                open.text.push('\n');
+            }
+            if open.kind_code == codes::LIST {
+                open.text.push_str(&"  ".repeat(depth.saturating_sub(1)));
+            }
+        }
+    }
+
+    fn close(&mut self, end: usize) {
+        let Some(open) = self.open.take() else {
+            return;
+        };
+        let mut kind_code = open.kind_code;
+        if kind_code == codes::PARAGRAPH && open.saw_image && !open.saw_other {
+            kind_code = codes::FIGURE;
+        }
+        if kind_code == codes::HEADING && open.level == 1 && self.blocks.is_empty() {
+            kind_code = codes::TITLE;
+        }
+        let text = match kind_code {
+            codes::CODE => open.text.trim_end_matches('\n'),
+            _ => open.text.trim(),
+        };
+        let lead = open.text.len() - open.text.trim_start().len();
+        let lead = if kind_code == codes::CODE { 0 } else { lead };
+        let segments = trim_segments(&open.segments, lead, text.len());
+        let text = text.to_string();
+
+        if open.level > 0 {
+            while self.headings.last().is_some_and(|(level, _)| *level >= open.level) {
+                self.headings.pop();
+            }
+        }
+        let parent = self.headings.last().map(|(_, id)| id.clone());
+        let id = self.ids.next_id(&IdRequest {
+            prefix: id_prefix(kind_code),
+            text: &text,
+            parent: parent.as_deref(),
+            key: None,
+        });
+        if open.level > 0 {
+            self.headings.push((open.level, id.clone()));
+        }
+
+        self.spans.push(BlockSpan { block_id: id.clone(), range: open.start..end, segments });
+        self.blocks.push(Block { id, kind_code, text_hash: String::new(), text, parent });
+    }
+}
+
+/// Re-base segments onto the trimmed text `lead..lead + len`, clipping the trimmed
+/// whitespace off segments that overlap it.
+fn trim_segments(segments: &[TextSegment], lead: usize, len: usize) -> Vec<TextSegment> {
+    segments
+        .iter()
+        .filter_map(|seg| {
+            let start = seg.text.start.max(lead);
+            let end = seg.text.end.min(lead + len);
+            (start < end).then(|| TextSegment {
+                text: start - lead..end - lead,
+                source: seg.source.start + (start - seg.text.start)..seg.source.start + (end - seg.text.start),
+                raw: seg.raw,
+            })
+        })
+        .collect()
+}
+
+fn heading_level(level: HeadingLevel) -> u8 {
+    match level {
+        HeadingLevel::H1 => 1,
+        HeadingLevel::H2 => 2,
+        HeadingLevel::H3 => 3,
+        HeadingLevel::H4 => 4,
+        HeadingLevel::H5 => 5,
+        HeadingLevel::H6 => 6,
+    }
+}
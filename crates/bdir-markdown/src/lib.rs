@@ -0,0 +1,70 @@
+//! CommonMark import for BDIR Documents.
+//!
+//! Maps Markdown block structure onto the kindCode codebook
+//! (`spec/codebooks/kind-codebook.v2.json`; code blocks use kindCode 6, which v2 adds
+//! to v1):
+//!
+//! | Markdown | kindCode | id prefix |
+//! |---|---|---|
+//! | level-1 heading that opens the document | 0 (title) | `t` |
+//! | other headings | 1 (heading) | `h` |
+//! | paragraph | 2 (paragraph) | `p` |
+//! | list (one block per top-level list) | 3 (list) | `l` |
+//! | table (GFM pipe table) | 4 (table) | `tbl` |
+//! | paragraph holding only images | 5 (figure) | `fig` |
+//! | fenced or indented code block | 6 (code) | `code` |
+//! | raw HTML block | 99 (unknown) | `x` |
+//!
+//! Block ids are positional per prefix (`p1`, `p2`, …) by default, so importing the
+//! same source always yields the same ids; `MarkdownOptions::id_strategy` selects
+//! content-addressed or hierarchical ids instead (see `bdir_core::ids`). Blocks are
+//! nested under the heading that opens their section via `Block::parent`; headings
+//! nest under the nearest lower-level heading.
+//!
+//! Block text is plain text: inline markup is dropped (link and image text are kept),
+//! soft line breaks become spaces, list items and table rows are one line each
+//! (nested items indented by two spaces per level, cells joined with ` | `).
+//!
+//! `import_markdown_with_spans` additionally records where each block's text came
+//! from in the source, and `write_back` uses those spans to carry text edits back
+//! into the original file: only the edited characters change, every other byte of
+//! the source is kept as written.
+
+mod import;
+mod write_back;
+
+pub use import::{import_markdown_with_spans, BlockSpan, MarkdownImport, TextSegment};
+pub use write_back::{write_back, WriteBackError};
+
+use bdir_core::ids::IdStrategy;
+use bdir_core::model::Document;
+
+/// Options for Markdown import.
+#[derive(Debug, Clone)]
+pub struct MarkdownOptions {
+    /// `hash_algorithm` of the produced Document.
+    ///
+    /// Defaults to `sha256`, the RFC-0001 default.
+    pub hash_algorithm: String,
+    /// How block ids are generated. Defaults to positional ids.
+    pub id_strategy: IdStrategy,
+}
+
+impl Default for MarkdownOptions {
+    fn default() -> Self {
+        Self { hash_algorithm: "sha256".to_string(), id_strategy: IdStrategy::Positional }
+    }
+}
+
+/// Import a CommonMark (plus GFM tables) source into a Document with hashes computed.
+pub fn parse_markdown_str(src: &str) -> Document {
+    parse_markdown_str_with_options(src, &MarkdownOptions::default()).expect("sha256 supported")
+}
+
+/// Import a Markdown source with explicit options.
+///
+/// Returns an error if `opts.hash_algorithm` is not a registered hash algorithm.
+pub fn parse_markdown_str_with_options(src: &str, opts: &MarkdownOptions) -> Result<Document, String> {
+    import_markdown_with_spans(src, opts).map(|import| import.document)
+}
+
@@ -0,0 +1,209 @@
+//! Carry text edits of an imported Document back into its Markdown source.
+//!
+//! Each changed block is diffed against its imported text; every changed run must
+//! fall inside one verbatim `TextSegment`, whose source bytes are then spliced. All
+//! other source bytes are copied unchanged. Write-back fails closed: spans that no
+//! longer match the source, structural changes, edits that touch markup-derived
+//! text, and outputs that do not re-import to the updated Document are all errors.
+
+use std::fmt;
+use std::ops::Range;
+
+use bdir_core::model::{Block, Document};
+use similar::{capture_diff_slices, Algorithm, DiffTag};
+
+use crate::import::{import_markdown_with_spans, MarkdownImport, TextSegment};
+use crate::MarkdownOptions;
+
+/// Why an updated Document could not be written back into its Markdown source.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum WriteBackError {
+    /// The recorded spans of this block no longer match the source bytes.
+    SpanMismatch { block_id: String },
+    /// Blocks were inserted, removed, reordered or reclassified.
+    StructureChanged { detail: String },
+    /// An edit touches block text that has no verbatim source (markup, entities,
+    /// list indentation, table separators) or needs a line break inside inline text.
+    UnmappableEdit { block_id: String },
+    /// The written Markdown does not import back to the updated text of this block.
+    Diverged { block_id: String },
+}
+
+impl fmt::Display for WriteBackError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            WriteBackError::SpanMismatch { block_id } => {
+                write!(f, "source spans of block '{block_id}' no longer match the Markdown source")
+            }
+            WriteBackError::StructureChanged { detail } => {
+                write!(f, "block structure changed ({detail}); only text edits can be written back")
+            }
+            WriteBackError::UnmappableEdit { block_id } => {
+                write!(f, "edit in block '{block_id}' does not map onto verbatim Markdown text")
+            }
+            WriteBackError::Diverged { block_id } => {
+                write!(f, "written Markdown does not reproduce the updated text of block '{block_id}'")
+            }
+        }
+    }
+}
+
+impl std::error::Error for WriteBackError {}
+
+/// Write the text edits in `updated` back into `src`, the Markdown source `import`
+/// was produced from.
+///
+/// `updated` must have the same blocks (ids, kinds, order) as `import.document`;
+/// only block text may differ. Returns the edited Markdown source.
+pub fn write_back(src: &str, import: &MarkdownImport, updated: &Document) -> Result<String, WriteBackError> {
+    let original = &import.document;
+    if import.spans.len() != original.blocks.len() {
+        return Err(WriteBackError::SpanMismatch {
+            block_id: original.blocks.get(import.spans.len()).map_or_else(String::new, |b| b.id.clone()),
+        });
+    }
+    for (block, span) in original.blocks.iter().zip(&import.spans) {
+        let intact = span.block_id == block.id
+            && src.get(span.range.clone()).is_some()
+            && span.segments.iter().all(|seg| {
+                src.get(seg.source.clone()).is_some_and(|s| block.text.get(seg.text.clone()) == Some(s))
+            });
+        if !intact {
+            return Err(WriteBackError::SpanMismatch { block_id: block.id.clone() });
+        }
+    }
+    check_structure(&original.blocks, &updated.blocks)?;
+
+    let mut edits: Vec<(Range<usize>, String)> = Vec::new();
+    for ((old, new), span) in original.blocks.iter().zip(&updated.blocks).zip(&import.spans) {
+        if old.text == new.text {
+            continue;
+        }
+        for (hunk, replacement) in text_hunks(&old.text, &new.text) {
+            let unmappable = || WriteBackError::UnmappableEdit { block_id: old.id.clone() };
+            // An insertion between two segments goes into the non-raw one, so it
+            // lands outside code spans rather than inside them.
+            let seg = span
+                .segments
+                .iter()
+                .filter(|seg| seg.text.start <= hunk.start && hunk.end <= seg.text.end)
+                .min_by_key(|seg| seg.raw)
+                .ok_or_else(unmappable)?;
+            let replacement = escape_for_segment(seg, old.kind_code, &replacement).ok_or_else(unmappable)?;
+            let start = seg.source.start + (hunk.start - seg.text.start);
+            let end = seg.source.start + (hunk.end - seg.text.start);
+            edits.push((start..end, replacement));
+        }
+    }
+
+    let mut out = src.to_string();
+    edits.sort_by_key(|(range, _)| range.start);
+    for (range, replacement) in edits.into_iter().rev() {
+        out.replace_range(range, &replacement);
+    }
+
+    verify(&out, updated)?;
+    Ok(out)
+}
+
+fn check_structure(old: &[Block], new: &[Block]) -> Result<(), WriteBackError> {
+    if old.len() != new.len() {
+        return Err(WriteBackError::StructureChanged {
+            detail: format!("{} blocks became {}", old.len(), new.len()),
+        });
+    }
+    for (a, b) in old.iter().zip(new) {
+        if a.id != b.id {
+            return Err(WriteBackError::StructureChanged {
+                detail: format!("expected block '{}', found '{}'", a.id, b.id),
+            });
+        }
+        if a.kind_code != b.kind_code {
+            return Err(WriteBackError::StructureChanged {
+                detail: format!("block '{}' changed kindCode {} -> {}", a.id, a.kind_code, b.kind_code),
+            });
+        }
+    }
+    Ok(())
+}
+
+/// Changed runs between `old` and `new`: byte ranges of `old` with their replacement.
+fn text_hunks(old: &str, new: &str) -> Vec<(Range<usize>, String)> {
+    let old_chars: Vec<char> = old.chars().collect();
+    let new_chars: Vec<char> = new.chars().collect();
+    let old_offsets = char_offsets(old);
+
+    let mut hunks: Vec<(Range<usize>, String)> = Vec::new();
+    let mut pending: Option<(Range<usize>, String)> = None;
+    for op in capture_diff_slices(Algorithm::Myers, &old_chars, &new_chars) {
+        let (tag, old_range, new_range) = op.as_tag_tuple();
+        if tag == DiffTag::Equal {
+            hunks.extend(pending.take());
+            continue;
+        }
+        let (range, replacement) =
+            pending.get_or_insert_with(|| (old_offsets[old_range.start]..old_offsets[old_range.start], String::new()));
+        range.end = old_offsets[old_range.end];
+        replacement.extend(&new_chars[new_range]);
+    }
+    hunks.extend(pending);
+    hunks
+}
+
+/// Byte offset of every char of `s`, plus `s.len()`.
+fn char_offsets(s: &str) -> Vec<usize> {
+    s.char_indices().map(|(i, _)| i).chain(std::iter::once(s.len())).collect()
+}
+
+/// `text` as it must be written into `seg`: verbatim in code and raw HTML, with
+/// Markdown punctuation backslash-escaped elsewhere. `None` when inline text would
+/// need a line break.
+fn escape_for_segment(seg: &TextSegment, kind_code: u16, text: &str) -> Option<String> {
+    if seg.raw {
+        return Some(text.to_string());
+    }
+    if text.contains(['\n', '\r']) {
+        return None;
+    }
+    let mut out = String::with_capacity(text.len());
+    for c in text.chars() {
+        let special = matches!(c, '\\' | '`' | '*' | '_' | '[' | ']' | '<' | '>' | '&')
+            || (c == '|' && kind_code == bdir_codebook::codes::TABLE);
+        if special {
+            out.push('\\');
+        }
+        out.push(c);
+    }
+    Some(out)
+}
+
+/// Re-import `out` and require that it reproduces `updated` block for block.
+///
+/// Ids depend on the id strategy the source was imported with, so blocks and
+/// parents are compared by position rather than by id.
+fn verify(out: &str, updated: &Document) -> Result<(), WriteBackError> {
+    let opts = MarkdownOptions { hash_algorithm: updated.hash_algorithm.clone(), ..MarkdownOptions::default() };
+    let reimported = import_markdown_with_spans(out, &opts)
+        .map_err(|_| WriteBackError::Diverged { block_id: String::new() })?
+        .document;
+    if reimported.blocks.len() != updated.blocks.len() {
+        return Err(WriteBackError::StructureChanged {
+            detail: format!("written Markdown has {} blocks, expected {}", reimported.blocks.len(), updated.blocks.len()),
+        });
+    }
+    let (got_parents, want_parents) = (parent_positions(&reimported), parent_positions(updated));
+    for (i, (got, want)) in reimported.blocks.iter().zip(&updated.blocks).enumerate() {
+        if got.kind_code != want.kind_code || got.text != want.text || got_parents[i] != want_parents[i] {
+            return Err(WriteBackError::Diverged { block_id: want.id.clone() });
+        }
+    }
+    Ok(())
+}
+
+/// Position of each block's parent in `doc.blocks`.
+fn parent_positions(doc: &Document) -> Vec<Option<usize>> {
+    doc.blocks
+        .iter()
+        .map(|b| b.parent.as_deref().and_then(|p| doc.blocks.iter().position(|other| other.id == p)))
+        .collect()
+}
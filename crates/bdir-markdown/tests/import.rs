@@ -0,0 +1,114 @@
+use std::path::Path;
+
+use bdir_core::ids::IdStrategy;
+use bdir_markdown::{parse_markdown_str, parse_markdown_str_with_options, MarkdownOptions};
+
+fn read_fixture(name: &str) -> String {
+    let path = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests").join("fixtures").join(name);
+    std::fs::read_to_string(&path).unwrap_or_else(|e| panic!("failed to read {}: {e}", path.display()))
+}
+
+fn rows(src: &str) -> Vec<(String, u16, String, Option<String>)> {
+    parse_markdown_str(src)
+        .blocks
+        .into_iter()
+        .map(|b| (b.id, b.kind_code, b.text, b.parent))
+        .collect()
+}
+
+fn row(id: &str, kind_code: u16, text: &str, parent: Option<&str>) -> (String, u16, String, Option<String>) {
+    (id.to_string(), kind_code, text.to_string(), parent.map(str::to_string))
+}
+
+#[test]
+fn blocks_map_onto_codebook_kinds_with_positional_ids_and_sections() {
+    assert_eq!(
+        rows(&read_fixture("guide.md")),
+        vec![
+            row("t1", 0, "Field Guide", None),
+            row("p1", 2, "Cats are small, furry animals. They sleep a lot.", Some("t1")),
+            row("h1", 1, "Care", Some("t1")),
+            row("l1", 3, "Feed twice a day\nBrush weekly\n  more often in spring\nVisit the vet", Some("h1")),
+            row("fig1", 5, "A tabby cat", Some("h1")),
+            row("tbl1", 4, "Breed | Coat\nTabby | Striped\nSiamese | Pointed", Some("h1")),
+            row("h2", 1, "Grooming tools", Some("h1")),
+            row("code1", 6, "brush --gentle\ncomb", Some("h2")),
+            row("h3", 1, "Training", Some("t1")),
+            row("l2", 3, "Use treats as rewards.\nKeep sessions short.", Some("h3")),
+            row("x1", 99, "<div class=\"note\">Raw HTML</div>", Some("h3")),
+        ]
+    );
+}
+
+#[test]
+fn import_is_deterministic_and_hashed() {
+    let src = read_fixture("guide.md");
+    let a = parse_markdown_str(&src);
+    let b = parse_markdown_str(&src);
+    assert_eq!(a, b);
+    assert_eq!(a.hash_algorithm, "sha256");
+    assert_eq!(a.page_hash.len(), 64);
+    assert!(a.blocks.iter().all(|b| !b.text_hash.is_empty()));
+}
+
+#[test]
+fn a_heading_after_content_is_not_the_title() {
+    assert_eq!(
+        rows("Intro text.\n\n# Big heading\n\nBody.\n"),
+        vec![
+            row("p1", 2, "Intro text.", None),
+            row("h1", 1, "Big heading", None),
+            row("p2", 2, "Body.", Some("h1")),
+        ]
+    );
+}
+
+#[test]
+fn headings_tables_and_html_inside_list_items_are_lines_of_the_list() {
+    assert_eq!(rows("- # Foo\n- bar\n"), vec![row("l1", 3, "Foo\nbar", None)]);
+    assert_eq!(
+        rows("- item\n\n  | a | b |\n  |---|---|\n  | 1 | 2 |\n"),
+        vec![row("l1", 3, "item\na | b\n1 | 2", None)]
+    );
+    assert_eq!(
+        rows("- item\n\n  <div>note</div>\n\nAfter.\n"),
+        vec![row("l1", 3, "item\n<div>note</div>", None), row("p1", 2, "After.", None)]
+    );
+}
+
+#[test]
+fn images_without_alt_text_use_their_url_and_mixed_paragraphs_stay_paragraphs() {
+    assert_eq!(
+        rows("![](diagram.svg)\n\nSee ![icon](i.png) here.\n"),
+        vec![row("fig1", 5, "diagram.svg", None), row("p1", 2, "See icon here.", None)]
+    );
+}
+
+#[test]
+fn hash_algorithm_is_configurable_and_checked() {
+    let opts = MarkdownOptions { hash_algorithm: "xxh64".to_string(), ..MarkdownOptions::default() };
+    let doc = parse_markdown_str_with_options("Hello.\n", &opts).unwrap();
+    assert_eq!(doc.hash_algorithm, "xxh64");
+
+    let opts = MarkdownOptions { hash_algorithm: "md5".to_string(), ..MarkdownOptions::default() };
+    assert!(parse_markdown_str_with_options("Hello.\n", &opts).is_err());
+}
+
+#[test]
+fn id_strategy_is_configurable() {
+    let opts = MarkdownOptions { id_strategy: IdStrategy::Hierarchical, ..MarkdownOptions::default() };
+    let doc = parse_markdown_str_with_options(&read_fixture("guide.md"), &opts).unwrap();
+    let ids: Vec<&str> = doc.blocks.iter().map(|b| b.id.as_str()).collect();
+    assert_eq!(
+        ids,
+        vec![
+            "t1", "t1.p1", "t1.h1", "t1.h1.l1", "t1.h1.fig1", "t1.h1.tbl1", "t1.h1.h1", "t1.h1.h1.code1", "t1.h2",
+            "t1.h2.l1", "t1.h2.x1",
+        ]
+    );
+    assert_eq!(doc.blocks[7].parent.as_deref(), Some("t1.h1.h1"));
+
+    let opts = MarkdownOptions { id_strategy: IdStrategy::ContentAddressed, ..MarkdownOptions::default() };
+    let doc = parse_markdown_str_with_options("Same.\n\nSame.\n", &opts).unwrap();
+    assert_eq!(doc.blocks[1].id, format!("{}-2", doc.blocks[0].id));
+}
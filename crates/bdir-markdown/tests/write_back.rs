@@ -0,0 +1,142 @@
+use std::path::Path;
+
+use bdir_core::ids::IdStrategy;
+use bdir_core::model::Document;
+use bdir_markdown::{import_markdown_with_spans, write_back, MarkdownImport, MarkdownOptions, WriteBackError};
+
+fn read_fixture(name: &str) -> String {
+    let path = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests").join("fixtures").join(name);
+    std::fs::read_to_string(&path).unwrap_or_else(|e| panic!("failed to read {}: {e}", path.display()))
+}
+
+fn import(src: &str) -> MarkdownImport {
+    import_markdown_with_spans(src, &MarkdownOptions::default()).unwrap()
+}
+
+fn edited(import: &MarkdownImport, edits: &[(&str, &str)]) -> Document {
+    let mut doc = import.document.clone();
+    for (id, text) in edits {
+        let block = doc.blocks.iter_mut().find(|b| b.id == *id).unwrap();
+        block.text = text.to_string();
+    }
+    doc.recompute_hashes();
+    doc
+}
+
+#[test]
+fn segments_point_at_verbatim_source_text() {
+    let src = read_fixture("guide.md");
+    let import = import(&src);
+
+    assert_eq!(import.spans.len(), import.document.blocks.len());
+    for (block, span) in import.document.blocks.iter().zip(&import.spans) {
+        assert_eq!(span.block_id, block.id);
+        for seg in &span.segments {
+            assert_eq!(&src[seg.source.clone()], &block.text[seg.text.clone()]);
+        }
+    }
+    assert_eq!(&src[import.spans[0].range.clone()], "# Field Guide\n");
+
+    let round_trip: MarkdownImport = serde_json::from_str(&serde_json::to_string(&import).unwrap()).unwrap();
+    assert_eq!(round_trip, import);
+}
+
+#[test]
+fn text_edits_keep_every_other_byte_of_the_source() {
+    let src = read_fixture("guide.md");
+    let import = import(&src);
+    let updated = edited(
+        &import,
+        &[
+            ("p1", "Cats are tiny, furry animals. They sleep a lot."),
+            ("h1", "Daily care"),
+            ("l1", "Feed twice a day\nBrush daily\n  more often in spring\nVisit the vet"),
+            ("tbl1", "Breed | Coat\nTabby | Striped\nSiamese | Colorpoint"),
+            ("code1", "brush --gentle\ncomb --fine"),
+            ("l2", "Use `treats` as rewards.\nKeep sessions short."),
+        ],
+    );
+
+    let out = write_back(&src, &import, &updated).unwrap();
+    let expected = src
+        .replace("*small*", "*tiny*")
+        .replace("## Care", "## Daily care")
+        .replace("Brush weekly", "Brush daily")
+        .replace("| Pointed |", "| Colorpoint |")
+        .replace("comb\n", "comb --fine\n")
+        .replace("1. Use `treats`", "1. Use \\``treats`\\`");
+    assert_eq!(out, expected);
+}
+
+#[test]
+fn inserted_markdown_punctuation_is_escaped() {
+    let src = "Price: 5 dollars.\n\n| A | B |\n|---|---|\n| x | y |\n";
+    let import = import(src);
+    let updated = edited(&import, &[("p1", "Price: 5 *US* dollars_total."), ("tbl1", "A | B\nx | y|z")]);
+
+    let out = write_back(src, &import, &updated).unwrap();
+    assert_eq!(out, "Price: 5 \\*US\\* dollars\\_total.\n\n| A | B |\n|---|---|\n| x | y\\|z |\n");
+}
+
+#[test]
+fn headings_and_tables_inside_list_items_write_back_in_place() {
+    let src = "- # Foo\n- bar\n\n- item\n\n  | a | b |\n  |---|---|\n  | 1 | 2 |\n";
+    let import = import(src);
+    let updated = edited(&import, &[("l1", "Food\nbar\nitem\na | b\n1 | 3")]);
+
+    let out = write_back(src, &import, &updated).unwrap();
+    assert_eq!(out, src.replace("# Foo", "# Food").replace("| 2 |", "| 3 |"));
+}
+
+#[test]
+fn unchanged_document_writes_back_identical_bytes() {
+    let src = read_fixture("guide.md");
+    let import = import(&src);
+    assert_eq!(write_back(&src, &import, &import.document).unwrap(), src);
+}
+
+#[test]
+fn write_back_fails_closed() {
+    let src = read_fixture("guide.md");
+    let import = import(&src);
+
+    let shifted = format!("Intro.\n\n{src}");
+    let err = write_back(&shifted, &import, &import.document).unwrap_err();
+    assert_eq!(err, WriteBackError::SpanMismatch { block_id: "t1".to_string() });
+
+    let mut removed = import.document.clone();
+    removed.blocks.remove(1);
+    let err = write_back(&src, &import, &removed).unwrap_err();
+    assert!(matches!(err, WriteBackError::StructureChanged { .. }));
+    assert_eq!(err.to_string(), "block structure changed (11 blocks became 10); only text edits can be written back");
+
+    // Deleting "small, " would have to remove emphasis markup as well.
+    let across_markup = edited(&import, &[("p1", "Cats are furry animals. They sleep a lot.")]);
+    let err = write_back(&src, &import, &across_markup).unwrap_err();
+    assert_eq!(err, WriteBackError::UnmappableEdit { block_id: "p1".to_string() });
+
+    let line_break = edited(&import, &[("p1", "Cats are small, furry animals.\nThey sleep a lot.")]);
+    let err = write_back(&src, &import, &line_break).unwrap_err();
+    assert_eq!(err, WriteBackError::UnmappableEdit { block_id: "p1".to_string() });
+}
+
+#[test]
+fn edits_that_would_change_block_structure_diverge() {
+    let src = "First line.\n\n```\ncode\n```\n";
+    let import = import(src);
+    let updated = edited(&import, &[("code1", "code\n```\nescaped")]);
+
+    let err = write_back(src, &import, &updated).unwrap_err();
+    assert!(matches!(err, WriteBackError::StructureChanged { .. } | WriteBackError::Diverged { .. }));
+}
+
+#[test]
+fn write_back_works_with_non_positional_ids() {
+    let src = read_fixture("guide.md");
+    let opts = MarkdownOptions { id_strategy: IdStrategy::Hierarchical, ..MarkdownOptions::default() };
+    let import = import_markdown_with_spans(&src, &opts).unwrap();
+    let updated = edited(&import, &[("t1.p1", "Cats are small, fluffy animals. They sleep a lot.")]);
+
+    let out = write_back(&src, &import, &updated).unwrap();
+    assert_eq!(out, src.replace("furry", "fluffy"));
+}
@@ -0,0 +1,193 @@
+//! Anchoring helpers shared by the validator, apply and the op-authoring tools.
+//!
+//! When the engine itself authors ops (inversion, rebase, diff), `anchored_replace`
+//! emits the smallest `before` window that covers a change and still satisfies the
+//! validator's uniqueness and `min_before_len` rules instead of replacing a whole block.
+//!
+//! The rest locate anchors in block text: match counting (`count_non_overlapping`),
+//! context-anchor selection (`context_matches`), near-miss hints for diagnostics
+//! (`closest_match`), and the text splitting and joining behind `split_block` /
+//! `merge_block` (`split_at_anchor`, `join_texts`).
+
+use bdir_core::hash::normalize_nfc;
+
+use crate::diagnostics::ClosestMatch;
+use crate::schema::PatchOpV1;
+
+/// Build a `replace` op that turns `current` into `target` within one block.
+///
+/// Both texts are compared in NFC (RFC-0001 §2.2). The changed region is widened
+/// one char at a time on both sides until `before` is at least `min_before_len`
+/// chars, not blank, and occurs exactly once in `current` (at the changed region).
+///
+/// Returns `None` when the texts are equal, or when `current` is empty and so
+/// offers nothing to anchor on.
+pub(crate) fn anchored_replace(
+    block_id: &str,
+    current: &str,
+    target: &str,
+    min_before_len: usize,
+) -> Option<PatchOpV1> {
+    let cur: Vec<char> = normalize_nfc(current).chars().collect();
+    let tgt: Vec<char> = normalize_nfc(target).chars().collect();
+    if cur == tgt || cur.is_empty() {
+        return None;
+    }
+
+    let max_common = cur.len().min(tgt.len());
+    let prefix = cur.iter().zip(&tgt).take_while(|(a, b)| a == b).count();
+    let suffix = cur
+        .iter()
+        .rev()
+        .zip(tgt.iter().rev())
+        .take(max_common - prefix)
+        .take_while(|(a, b)| a == b)
+        .count();
+
+    let cur_s: String = cur.iter().collect();
+    let mut lo = prefix;
+    let mut cur_hi = cur.len() - suffix;
+    let mut tgt_hi = tgt.len() - suffix;
+
+    loop {
+        let before: String = cur[lo..cur_hi].iter().collect();
+        let whole = lo == 0 && cur_hi == cur.len();
+        let anchored = before.chars().count() >= min_before_len
+            && !before.trim().is_empty()
+            && is_unique_at(&cur_s, &before, byte_offset(&cur, lo));
+        if anchored || whole {
+            let after: String = tgt[lo..tgt_hi].iter().collect();
+            return Some(PatchOpV1::replace(block_id, before, after));
+        }
+        lo = lo.saturating_sub(1);
+        if cur_hi < cur.len() {
+            cur_hi += 1;
+            tgt_hi += 1;
+        }
+    }
+}
+
+/// True when `needle` occurs exactly once in `haystack`, starting at byte `pos`.
+///
+/// Counting alone is not enough: a window can overlap an earlier occurrence of
+/// itself, in which case the single non-overlapping match is not the one we mean.
+pub(crate) fn is_unique_at(haystack: &str, needle: &str, pos: usize) -> bool {
+    count_non_overlapping(haystack, needle) == 1 && haystack.find(needle) == Some(pos)
+}
+
+/// Byte offset of char index `idx` in `chars`.
+pub(crate) fn byte_offset(chars: &[char], idx: usize) -> usize {
+    chars[..idx].iter().map(|c| c.len_utf8()).sum()
+}
+
+/// Count non-overlapping occurrences of `needle` in `haystack`.
+///
+/// Neither string is normalized here; callers pass NFC (or tolerant-folded) text.
+pub(crate) fn count_non_overlapping(haystack: &str, needle: &str) -> usize {
+    if needle.is_empty() {
+        return 0;
+    }
+    haystack.matches(needle).count()
+}
+
+/// Split NFC `text` at the first occurrence of `anchor`.
+///
+/// The head keeps everything before the anchor with trailing whitespace trimmed; the
+/// tail starts with the anchor itself. Returns `None` when the anchor does not occur.
+pub(crate) fn split_at_anchor(text: &str, anchor: &str) -> Option<(String, String)> {
+    let text = normalize_nfc(text);
+    let anchor = normalize_nfc(anchor);
+    if anchor.is_empty() {
+        return None;
+    }
+    let pos = text.find(&anchor)?;
+    Some((text[..pos].trim_end().to_string(), text[pos..].to_string()))
+}
+
+/// Join two block texts (NFC) with exactly one space between them.
+///
+/// Whitespace at the seam is collapsed; when either side is blank no space is added.
+pub(crate) fn join_texts(first: &str, second: &str) -> String {
+    let first = normalize_nfc(first);
+    let second = normalize_nfc(second);
+    let (head, tail) = (first.trim_end(), second.trim_start());
+    if head.is_empty() || tail.is_empty() {
+        format!("{head}{tail}")
+    } else {
+        format!("{head} {tail}")
+    }
+}
+
+/// 1-based indices of the non-overlapping `before` matches in `text` that the context
+/// anchors select: `context_before` must end right at the match and `context_after`
+/// must start right after it. An absent anchor accepts every match.
+///
+/// All strings are compared in NFC; matches are enumerated the same way as
+/// `occurrence` indices, so a returned index can be used as one.
+pub(crate) fn context_matches(
+    text: &str,
+    before: &str,
+    context_before: Option<&str>,
+    context_after: Option<&str>,
+) -> Vec<usize> {
+    let text = normalize_nfc(text);
+    let before = normalize_nfc(before);
+    if before.is_empty() {
+        return Vec::new();
+    }
+    let context_before = context_before.map(normalize_nfc);
+    let context_after = context_after.map(normalize_nfc);
+    text.match_indices(&before)
+        .enumerate()
+        .filter(|(_, (pos, _))| {
+            context_before.as_deref().is_none_or(|c| text[..*pos].ends_with(c))
+                && context_after.as_deref().is_none_or(|c| text[pos + before.len()..].starts_with(c))
+        })
+        .map(|(k, _)| k + 1)
+        .collect()
+}
+
+/// Upper bound on `before` chars × block chars for the closest-match search, so a
+/// huge block cannot turn a failed lookup into an expensive one.
+const MAX_CLOSEST_MATCH_CELLS: usize = 4_000_000;
+
+/// Substring of `text` with the smallest edit distance to `needle` (both NFC).
+///
+/// Approximate substring matching: the candidate may start and end anywhere in the
+/// text, and only edits inside it count. Ties go to the candidate closest in length to
+/// `needle`, then to the earliest one. Returns `None`
+/// when more than half of `needle` would have to change, since such a candidate is
+/// noise rather than a hint.
+pub(crate) fn closest_match(text: &str, needle: &str) -> Option<ClosestMatch> {
+    let t: Vec<char> = normalize_nfc(text).chars().collect();
+    let p: Vec<char> = normalize_nfc(needle).chars().collect();
+    if p.is_empty() || t.is_empty() || p.len().saturating_mul(t.len()) > MAX_CLOSEST_MATCH_CELLS {
+        return None;
+    }
+
+    // (cost, start of the candidate in `t`) for needle prefix i ending at text char j.
+    let mut prev: Vec<(usize, usize)> = (0..=t.len()).map(|j| (0, j)).collect();
+    let mut cur = vec![(0, 0); t.len() + 1];
+    for (i, pc) in p.iter().enumerate() {
+        cur[0] = (i + 1, 0);
+        for (j, tc) in t.iter().enumerate() {
+            let substitute = (prev[j].0 + usize::from(pc != tc), prev[j].1);
+            let skip_needle = (prev[j + 1].0 + 1, prev[j + 1].1);
+            let skip_text = (cur[j].0 + 1, cur[j].1);
+            cur[j + 1] = [substitute, skip_needle, skip_text]
+                .into_iter()
+                .min_by_key(|c| c.0)
+                .expect("non-empty");
+        }
+        std::mem::swap(&mut prev, &mut cur);
+    }
+
+    let (end, &(distance, start)) = prev
+        .iter()
+        .enumerate()
+        .min_by_key(|(end, (cost, start))| (*cost, (end - start).abs_diff(p.len())))?;
+    if start == end || distance * 2 > p.len() {
+        return None;
+    }
+    Some(ClosestMatch { text: t[start..end].iter().collect(), offset: start, distance })
+}
@@ -1,13 +1,23 @@
-use crate::schema::{DeleteOccurrence, Occurrence, OpType, PatchV1};
+use std::collections::BTreeMap;
+
+use crate::anchor::{context_matches, join_texts, split_at_anchor};
+use crate::assign_ids::{assign_new_block_ids, assign_new_block_ids_against_edit_packet};
+use crate::diagnostics::ApplyError;
+use crate::schema::{DeleteOccurrence, Occurrence, OpType, PatchOpV1, PatchV1};
 use crate::telemetry::PatchTelemetry;
+use crate::tolerant::{tolerant_edit, tolerant_split};
 use crate::validate::{
-    validate_patch,
-    validate_patch_with_options,
-    validate_patch_against_edit_packet_with_options,
+    validate_patch_with_diagnostics,
+    validate_patch_against_edit_packet_with_diagnostics,
+    tolerant_match_diagnostics,
+    tolerant_match_diagnostics_against_edit_packet,
+    MatchMode,
     ValidateOptions,
 };
-use bdir_core::hash::{hash_canon_hex, hash_hex, normalize_nfc};
-use bdir_core::model::{Block, Document};
+use bdir_core::hash::{
+    hash_canon_hex, hash_hex, lookup_hash_algorithm, normalize_nfc, supported_hash_algorithms,
+};
+use bdir_core::model::{Block, BlockId, Document};
 use bdir_editpacket::{BlockTupleV1, EditPacketV1};
 
 /// Apply a patch against an Edit Packet and return an updated Edit Packet.
@@ -21,10 +31,13 @@ use bdir_editpacket::{BlockTupleV1, EditPacketV1};
 /// Safety:
 /// - Calls `validate_patch_against_edit_packet()` first.
 /// - Recomputes all `textHash` values and the packet hash `h` after applying.
+///
+/// Validation failures are returned as `ApplyError::Validation` with the validator's
+/// diagnostics intact.
 pub fn apply_patch_against_edit_packet(
     packet: &EditPacketV1,
     patch: &PatchV1,
-) -> Result<EditPacketV1, String> {
+) -> Result<EditPacketV1, ApplyError> {
     apply_patch_against_edit_packet_with_options(packet, patch, ValidateOptions::default())
 }
 
@@ -36,49 +49,63 @@ pub fn apply_patch_against_edit_packet_with_options(
     packet: &EditPacketV1,
     patch: &PatchV1,
     opts: ValidateOptions,
-) -> Result<EditPacketV1, String> {
-    // Validate first (stable error messages come from validator).
-    validate_patch_against_edit_packet_with_options(packet, patch, opts)?;
-
-    // Support any algorithm implemented by bdir-core.
+) -> Result<EditPacketV1, ApplyError> {
+    let tolerant = opts.match_mode == MatchMode::Tolerant;
+    let assigned = opts
+        .assign_new_block_ids
+        .map(|strategy| assign_new_block_ids_against_edit_packet(packet, patch, strategy));
+    let patch = assigned.as_ref().unwrap_or(patch);
+
+    // Validate first (stable error messages come from validator). Ids are already assigned.
+    let opts = ValidateOptions { assign_new_block_ids: None, ..opts };
+    validate_patch_against_edit_packet_with_diagnostics(packet, patch, opts)?;
+
+    // Support any algorithm in the bdir-core hash registry.
     let algo = packet.ha.as_str();
-    if hash_hex(algo, "").is_none() {
-        return Err(format!("unsupported hash algorithm '{algo}'"));
+    if lookup_hash_algorithm(algo).is_none() {
+        return Err(ApplyError::UnsupportedHashAlgorithm {
+            algorithm: algo.to_string(),
+            supported: supported_hash_algorithms(),
+        });
     }
 
     let mut out = packet.clone();
 
-    for op in &patch.ops {
+    for (i, op) in patch.ops.iter().enumerate() {
         match op.op {
             OpType::Replace => {
                 let before = op
                     .before
                     .as_deref()
-                    .ok_or_else(|| "ops replace missing before (should be validated)".to_string())?;
+                    .ok_or_else(|| op_failed(i, op, "ops replace missing before (should be validated)"))?;
                 let after = op
                     .after
                     .as_deref()
-                    .ok_or_else(|| "ops replace missing after (should be validated)".to_string())?;
+                    .ok_or_else(|| op_failed(i, op, "ops replace missing after (should be validated)"))?;
 
                 let idx = find_block_index(&out.b, &op.block_id)
-                    .ok_or_else(|| format!("unknown block_id '{}'", op.block_id))?;
+                    .ok_or_else(|| op_failed(i, op, format!("unknown block_id '{}'", op.block_id)))?;
 
                 let current_text = out.b[idx].3.clone();
-                let next_text = match op.occurrence {
+                if tolerant && let Some(edited) = tolerant_edit(op, &current_text, before, after) {
+                    out.b[idx].3 = edited;
+                    continue;
+                }
+                let next_text = match effective_occurrence(i, op, &current_text, before)? {
                     Some(Occurrence::Index(n)) => { 
                         let n = usize::try_from(n)
-                            .map_err(|_| "occurrence index is too large for this platform")?;
+                            .map_err(|_| op_failed(i, op, "occurrence index is too large for this platform"))?;
 
                         replace_nth_non_overlapping(&current_text, before, after, n)
                         .ok_or_else(|| {
-                            format!(
+                            op_failed(i, op, format!(
                                 "replace occurrence out of range (block_id='{}', occurrence={n})",
                                 op.block_id
-                            )
+                            ))
                         })?
                     },
                     Some(Occurrence::Legacy(_)) => {
-                        return Err("replace occurrence must be an integer (legacy string values are delete-only)".to_string());
+                        return Err(op_failed(i, op, "replace occurrence must be an integer (legacy string values are delete-only)"));
                     }
                     None => replace_first(&current_text, before, after),
                 };
@@ -89,15 +116,17 @@ pub fn apply_patch_against_edit_packet_with_options(
                 let before = op
                     .before
                     .as_deref()
-                    .ok_or_else(|| "ops delete missing before (should be validated)".to_string())?;
-
-                let occ = op.occurrence;
+                    .ok_or_else(|| op_failed(i, op, "ops delete missing before (should be validated)"))?;
 
                 let idx = find_block_index(&out.b, &op.block_id)
-                    .ok_or_else(|| format!("unknown block_id '{}'", op.block_id))?;
+                    .ok_or_else(|| op_failed(i, op, format!("unknown block_id '{}'", op.block_id)))?;
 
                 let current_text = out.b[idx].3.clone();
-                out.b[idx].3 = match occ {
+                if tolerant && let Some(edited) = tolerant_edit(op, &current_text, before, "") {
+                    out.b[idx].3 = edited;
+                    continue;
+                }
+                out.b[idx].3 = match effective_occurrence(i, op, &current_text, before)? {
                     Some(Occurrence::Legacy(DeleteOccurrence::All)) => {
                         let hay = normalize_nfc(&current_text);
                         let needle = normalize_nfc(before);
@@ -106,41 +135,39 @@ pub fn apply_patch_against_edit_packet_with_options(
                     Some(Occurrence::Legacy(DeleteOccurrence::First)) => delete_first(&current_text, before),
                     Some(Occurrence::Index(n)) => {
                         let n = usize::try_from(n)
-                            .map_err(|_| "occurrence index is too large for this platform")?;
+                            .map_err(|_| op_failed(i, op, "occurrence index is too large for this platform"))?;
                         
                         delete_nth_non_overlapping(&current_text, before, n).ok_or_else(|| {
-                        format!(
+                        op_failed(i, op, format!(
                             "delete occurrence out of range (block_id='{}', occurrence={n})",
                             op.block_id
-                        )})?
+                        ))})?
                     },
                     None => delete_first(&current_text, before),
                 };
             }
-            OpType::InsertAfter => {
+            OpType::InsertAfter | OpType::InsertBefore => {
+                let name = op.op.as_str();
                 let new_block_id = op
                     .new_block_id
                     .as_deref()
-                    .ok_or_else(|| {
-                        "ops insert_after missing new_block_id (should be validated)".to_string()
-                    })?;
+                    .ok_or_else(|| op_failed(i, op, format!("ops {name} missing new_block_id (should be validated)")))?;
                 let kind_code = op
                     .kind_code
-                    .ok_or_else(|| {
-                        "ops insert_after missing kind_code (should be validated)".to_string()
-                    })?;
+                    .ok_or_else(|| op_failed(i, op, format!("ops {name} missing kind_code (should be validated)")))?;
                 let text = op
                     .text
                     .as_deref()
-                    .ok_or_else(|| "ops insert_after missing text (should be validated)".to_string())?;
+                    .ok_or_else(|| op_failed(i, op, format!("ops {name} missing text (should be validated)")))?;
 
                 let anchor_idx = find_block_index(&out.b, &op.block_id)
-                    .ok_or_else(|| format!("unknown block_id '{}'", op.block_id))?;
+                    .ok_or_else(|| op_failed(i, op, format!("unknown block_id '{}'", op.block_id)))?;
 
                 if out.b.iter().any(|t| t.0 == new_block_id) {
-                    return Err(format!(
-                        "insert_after new_block_id '{}' already exists",
-                        new_block_id
+                    return Err(op_failed(
+                        i,
+                        op,
+                        format!("{name} new_block_id '{new_block_id}' already exists"),
                     ));
                 }
 
@@ -152,7 +179,90 @@ pub fn apply_patch_against_edit_packet_with_options(
                     normalize_nfc(text),
                 );
 
-                out.b.insert(anchor_idx + 1, new_tuple);
+                let at = if op.op == OpType::InsertBefore { anchor_idx } else { anchor_idx + 1 };
+                out.b.insert(at, new_tuple);
+                // New blocks become siblings of their anchor.
+                if let Some(parent) = out.p.get(&op.block_id).cloned() {
+                    out.p.insert(new_block_id.to_string(), parent);
+                }
+            }
+
+            OpType::DeleteBlock => {
+                let idx = find_block_index(&out.b, &op.block_id)
+                    .ok_or_else(|| op_failed(i, op, format!("unknown block_id '{}'", op.block_id)))?;
+                out.b.remove(idx);
+                let parent = out.p.remove(&op.block_id);
+                reparent_children(&mut out.p, &op.block_id, parent.as_deref());
+            }
+
+            OpType::SplitBlock => {
+                let before = op
+                    .before
+                    .as_deref()
+                    .ok_or_else(|| op_failed(i, op, "ops split_block missing before (should be validated)"))?;
+                let new_block_id = op
+                    .new_block_id
+                    .as_deref()
+                    .ok_or_else(|| op_failed(i, op, "ops split_block missing new_block_id (should be validated)"))?;
+
+                let idx = find_block_index(&out.b, &op.block_id)
+                    .ok_or_else(|| op_failed(i, op, format!("unknown block_id '{}'", op.block_id)))?;
+                if out.b.iter().any(|b| b.0 == new_block_id) {
+                    return Err(op_failed(i, op, format!("split_block new_block_id '{new_block_id}' already exists")));
+                }
+                let split = if tolerant { tolerant_split(op, &out.b[idx].3, before) } else { None };
+                let (head, tail) = split.or_else(|| split_at_anchor(&out.b[idx].3, before)).ok_or_else(|| {
+                    op_failed(i, op, format!("split anchor not found in block '{}'", op.block_id))
+                })?;
+                let kind_code = op.kind_code.unwrap_or(out.b[idx].1);
+
+                // Placeholder hash, recomputed at the end.
+                let new_tuple: BlockTupleV1 = (new_block_id.to_string(), kind_code, String::new(), tail);
+                out.b[idx].3 = head;
+                out.b.insert(idx + 1, new_tuple);
+                if let Some(parent) = out.p.get(&op.block_id).cloned() {
+                    out.p.insert(new_block_id.to_string(), parent);
+                }
+            }
+
+            OpType::MergeBlock => {
+                let next_block_id = op
+                    .next_block_id
+                    .as_deref()
+                    .ok_or_else(|| op_failed(i, op, "ops merge_block missing next_block_id (should be validated)"))?;
+
+                let next_idx = find_block_index(&out.b, next_block_id)
+                    .ok_or_else(|| op_failed(i, op, format!("unknown next_block_id '{next_block_id}'")))?;
+                let next = out.b.remove(next_idx);
+                let idx = find_block_index(&out.b, &op.block_id)
+                    .ok_or_else(|| op_failed(i, op, format!("unknown block_id '{}'", op.block_id)))?;
+                out.b[idx].3 = join_texts(&out.b[idx].3, &next.3);
+                out.p.remove(next_block_id);
+                reparent_children(&mut out.p, next_block_id, Some(&op.block_id));
+            }
+
+            OpType::Reclassify => {
+                let kind_code = op
+                    .kind_code
+                    .ok_or_else(|| op_failed(i, op, "ops reclassify missing kind_code (should be validated)"))?;
+
+                let idx = find_block_index(&out.b, &op.block_id)
+                    .ok_or_else(|| op_failed(i, op, format!("unknown block_id '{}'", op.block_id)))?;
+                out.b[idx].1 = kind_code;
+            }
+
+            OpType::Move => {
+                let anchor = op
+                    .after_block_id
+                    .as_deref()
+                    .ok_or_else(|| op_failed(i, op, "ops move missing after_block_id (should be validated)"))?;
+
+                let idx = find_block_index(&out.b, &op.block_id)
+                    .ok_or_else(|| op_failed(i, op, format!("unknown block_id '{}'", op.block_id)))?;
+                let block = out.b.remove(idx);
+                let anchor_idx = find_block_index(&out.b, anchor)
+                    .ok_or_else(|| op_failed(i, op, format!("unknown after_block_id '{anchor}'")))?;
+                out.b.insert(anchor_idx + 1, block);
             }
 
             OpType::Suggest => {
@@ -170,50 +280,74 @@ pub fn apply_patch_against_edit_packet_with_options(
 /// Apply a patch against a full Document and return an updated Document.
 ///
 /// This is the CLI/workflow-friendly variant used when downstream systems
-/// need an updated Document JSON for renderers.
+/// need an updated Document JSON for renderers (such as `bdir-render`).
 ///
 /// Semantics match `apply_patch_against_edit_packet`.
 ///
 /// Safety:
 /// - Calls `validate_patch()` first.
 /// - Recomputes block `text_hash` values and `page_hash` after applying.
-pub fn apply_patch_against_document(doc: &Document, patch: &PatchV1) -> Result<Document, String> {
+pub fn apply_patch_against_document(doc: &Document, patch: &PatchV1) -> Result<Document, ApplyError> {
+    apply_patch_against_document_with_options(doc, patch, ValidateOptions::default())
+}
+
+/// Apply a patch against a Document using custom validation options.
+pub fn apply_patch_against_document_with_options(
+    doc: &Document,
+    patch: &PatchV1,
+    opts: ValidateOptions,
+) -> Result<Document, ApplyError> {
     // Validate first (stable error messages come from validator).
-    validate_patch(doc, patch)?;
+    let tolerant = opts.match_mode == MatchMode::Tolerant;
+    let assigned = opts.assign_new_block_ids.map(|strategy| assign_new_block_ids(doc, patch, strategy));
+    let patch = assigned.as_ref().unwrap_or(patch);
+    // Ids are already assigned.
+    let opts = ValidateOptions { assign_new_block_ids: None, ..opts };
+    validate_patch_with_diagnostics(doc, patch, opts)?;
+    apply_ops_to_document(doc, patch, tolerant)
+}
 
+/// Apply already-validated ops to a Document.
+///
+/// With `tolerant`, ops whose `before` only matches after folding edit the mapped range.
+fn apply_ops_to_document(doc: &Document, patch: &PatchV1, tolerant: bool) -> Result<Document, ApplyError> {
     let mut out = doc.clone();
 
-    for op in &patch.ops {
+    for (i, op) in patch.ops.iter().enumerate() {
         match op.op {
             OpType::Replace => {
                 let before = op
                     .before
                     .as_deref()
-                    .ok_or_else(|| "ops replace missing before (should be validated)".to_string())?;
+                    .ok_or_else(|| op_failed(i, op, "ops replace missing before (should be validated)"))?;
                 let after = op
                     .after
                     .as_deref()
-                    .ok_or_else(|| "ops replace missing after (should be validated)".to_string())?;
+                    .ok_or_else(|| op_failed(i, op, "ops replace missing after (should be validated)"))?;
 
                 let idx = find_doc_block_index(&out.blocks, &op.block_id)
-                    .ok_or_else(|| format!("unknown block_id '{}'", op.block_id))?;
+                    .ok_or_else(|| op_failed(i, op, format!("unknown block_id '{}'", op.block_id)))?;
 
                 let current_text = out.blocks[idx].text.clone();
-                out.blocks[idx].text = match op.occurrence {
+                if tolerant && let Some(edited) = tolerant_edit(op, &current_text, before, after) {
+                    out.blocks[idx].text = edited;
+                    continue;
+                }
+                out.blocks[idx].text = match effective_occurrence(i, op, &current_text, before)? {
                     Some(Occurrence::Index(n)) => { 
                         let n = usize::try_from(n)
-                            .map_err(|_| "occurrence index is too large for this platform")?;
+                            .map_err(|_| op_failed(i, op, "occurrence index is too large for this platform"))?;
 
                         replace_nth_non_overlapping(&current_text, before, after, n)
                         .ok_or_else(|| {
-                            format!(
+                            op_failed(i, op, format!(
                                 "replace occurrence out of range (block_id='{}', occurrence={n})",
                                 op.block_id
-                            )
+                            ))
                         })? 
                     },
                     Some(Occurrence::Legacy(_)) => {
-                        return Err("replace occurrence must be an integer (legacy string values are delete-only)".to_string());
+                        return Err(op_failed(i, op, "replace occurrence must be an integer (legacy string values are delete-only)"));
                     }
                     None => replace_first(&current_text, before, after),
                 };
@@ -223,15 +357,17 @@ pub fn apply_patch_against_document(doc: &Document, patch: &PatchV1) -> Result<D
                 let before = op
                     .before
                     .as_deref()
-                    .ok_or_else(|| "ops delete missing before (should be validated)".to_string())?;
-
-                let occ = op.occurrence;
+                    .ok_or_else(|| op_failed(i, op, "ops delete missing before (should be validated)"))?;
 
                 let idx = find_doc_block_index(&out.blocks, &op.block_id)
-                    .ok_or_else(|| format!("unknown block_id '{}'", op.block_id))?;
+                    .ok_or_else(|| op_failed(i, op, format!("unknown block_id '{}'", op.block_id)))?;
 
                 let current_text = out.blocks[idx].text.clone();
-                out.blocks[idx].text = match occ {
+                if tolerant && let Some(edited) = tolerant_edit(op, &current_text, before, "") {
+                    out.blocks[idx].text = edited;
+                    continue;
+                }
+                out.blocks[idx].text = match effective_occurrence(i, op, &current_text, before)? {
                     Some(Occurrence::Legacy(DeleteOccurrence::All)) => {
                         let hay = normalize_nfc(&current_text);
                         let needle = normalize_nfc(before);
@@ -240,52 +376,132 @@ pub fn apply_patch_against_document(doc: &Document, patch: &PatchV1) -> Result<D
                     Some(Occurrence::Legacy(DeleteOccurrence::First)) => delete_first(&current_text, before),
                     Some(Occurrence::Index(n)) => { 
                         let n = usize::try_from(n)
-                            .map_err(|_| "occurrence index is too large for this platform")?;
+                            .map_err(|_| op_failed(i, op, "occurrence index is too large for this platform"))?;
 
                         delete_nth_non_overlapping(&current_text, before, n).ok_or_else(|| {
-                        format!(
+                        op_failed(i, op, format!(
                             "delete occurrence out of range (block_id='{}', occurrence={n})",
                             op.block_id
-                        )
+                        ))
                     })?
                 },
                     None => delete_first(&current_text, before),
                 };
             }
 
-            OpType::InsertAfter => {
+            OpType::InsertAfter | OpType::InsertBefore => {
+                let name = op.op.as_str();
                 let new_block_id = op
                     .new_block_id
                     .as_deref()
-                    .ok_or_else(|| {
-                        "ops insert_after missing new_block_id (should be validated)".to_string()
-                    })?;
-                let kind_code = op.kind_code.ok_or_else(|| {
-                    "ops insert_after missing kind_code (should be validated)".to_string()
-                })?;
+                    .ok_or_else(|| op_failed(i, op, format!("ops {name} missing new_block_id (should be validated)")))?;
+                let kind_code = op.kind_code.ok_or_else(|| op_failed(i, op, format!("ops {name} missing kind_code (should be validated)")))?;
                 let text = op
                     .text
                     .as_deref()
-                    .ok_or_else(|| "ops insert_after missing text (should be validated)".to_string())?;
+                    .ok_or_else(|| op_failed(i, op, format!("ops {name} missing text (should be validated)")))?;
 
                 let anchor_idx = find_doc_block_index(&out.blocks, &op.block_id)
-                    .ok_or_else(|| format!("unknown block_id '{}'", op.block_id))?;
+                    .ok_or_else(|| op_failed(i, op, format!("unknown block_id '{}'", op.block_id)))?;
 
                 if out.blocks.iter().any(|b| b.id == new_block_id) {
-                    return Err(format!(
-                        "insert_after new_block_id '{}' already exists",
-                        new_block_id
+                    return Err(op_failed(
+                        i,
+                        op,
+                        format!("{name} new_block_id '{new_block_id}' already exists"),
                     ));
                 }
 
+                // New blocks become siblings of their anchor.
                 let new_block = Block {
                     id: new_block_id.to_string(),
                     kind_code,
                     text_hash: String::new(),
                     text: normalize_nfc(text),
+                    parent: out.blocks[anchor_idx].parent.clone(),
                 };
 
-                out.blocks.insert(anchor_idx + 1, new_block);
+                let at = if op.op == OpType::InsertBefore { anchor_idx } else { anchor_idx + 1 };
+                out.blocks.insert(at, new_block);
+            }
+
+            OpType::DeleteBlock => {
+                let idx = find_doc_block_index(&out.blocks, &op.block_id)
+                    .ok_or_else(|| op_failed(i, op, format!("unknown block_id '{}'", op.block_id)))?;
+                let removed = out.blocks.remove(idx);
+                reparent_doc_children(&mut out.blocks, &removed.id, removed.parent.as_ref());
+            }
+
+            OpType::SplitBlock => {
+                let before = op
+                    .before
+                    .as_deref()
+                    .ok_or_else(|| op_failed(i, op, "ops split_block missing before (should be validated)"))?;
+                let new_block_id = op
+                    .new_block_id
+                    .as_deref()
+                    .ok_or_else(|| op_failed(i, op, "ops split_block missing new_block_id (should be validated)"))?;
+
+                let idx = find_doc_block_index(&out.blocks, &op.block_id)
+                    .ok_or_else(|| op_failed(i, op, format!("unknown block_id '{}'", op.block_id)))?;
+                if out.blocks.iter().any(|b| b.id == new_block_id) {
+                    return Err(op_failed(i, op, format!("split_block new_block_id '{new_block_id}' already exists")));
+                }
+                let split = if tolerant { tolerant_split(op, &out.blocks[idx].text, before) } else { None };
+                let (head, tail) = split.or_else(|| split_at_anchor(&out.blocks[idx].text, before)).ok_or_else(|| {
+                    op_failed(i, op, format!("split anchor not found in block '{}'", op.block_id))
+                })?;
+                let kind_code = op.kind_code.unwrap_or(out.blocks[idx].kind_code);
+
+                // Placeholder hash, recomputed at the end.
+                let new_block = Block {
+                    id: new_block_id.to_string(),
+                    kind_code,
+                    text_hash: String::new(),
+                    text: tail,
+                    parent: out.blocks[idx].parent.clone(),
+                };
+                out.blocks[idx].text = head;
+                out.blocks.insert(idx + 1, new_block);
+            }
+
+            OpType::MergeBlock => {
+                let next_block_id = op
+                    .next_block_id
+                    .as_deref()
+                    .ok_or_else(|| op_failed(i, op, "ops merge_block missing next_block_id (should be validated)"))?;
+
+                let next_idx = find_doc_block_index(&out.blocks, next_block_id)
+                    .ok_or_else(|| op_failed(i, op, format!("unknown next_block_id '{next_block_id}'")))?;
+                let next = out.blocks.remove(next_idx);
+                let idx = find_doc_block_index(&out.blocks, &op.block_id)
+                    .ok_or_else(|| op_failed(i, op, format!("unknown block_id '{}'", op.block_id)))?;
+                out.blocks[idx].text = join_texts(&out.blocks[idx].text, &next.text);
+                reparent_doc_children(&mut out.blocks, &next.id, Some(&op.block_id));
+            }
+
+            OpType::Reclassify => {
+                let kind_code = op
+                    .kind_code
+                    .ok_or_else(|| op_failed(i, op, "ops reclassify missing kind_code (should be validated)"))?;
+
+                let idx = find_doc_block_index(&out.blocks, &op.block_id)
+                    .ok_or_else(|| op_failed(i, op, format!("unknown block_id '{}'", op.block_id)))?;
+                out.blocks[idx].kind_code = kind_code;
+            }
+
+            OpType::Move => {
+                let anchor = op
+                    .after_block_id
+                    .as_deref()
+                    .ok_or_else(|| op_failed(i, op, "ops move missing after_block_id (should be validated)"))?;
+
+                let idx = find_doc_block_index(&out.blocks, &op.block_id)
+                    .ok_or_else(|| op_failed(i, op, format!("unknown block_id '{}'", op.block_id)))?;
+                let block = out.blocks.remove(idx);
+                let anchor_idx = find_doc_block_index(&out.blocks, anchor)
+                    .ok_or_else(|| op_failed(i, op, format!("unknown after_block_id '{anchor}'")))?;
+                out.blocks.insert(anchor_idx + 1, block);
             }
 
             OpType::Suggest => {
@@ -300,14 +516,57 @@ pub fn apply_patch_against_document(doc: &Document, patch: &PatchV1) -> Result<D
     Ok(out)
 }
 
-/// Apply a patch against a Document using custom validation options.
-pub fn apply_patch_against_document_with_options(
-    doc: &Document,
-    patch: &PatchV1,
-    opts: ValidateOptions,
-) -> Result<Document, String> {
-    validate_patch_with_options(doc, patch, opts)?;
-    apply_patch_against_document(doc, patch)
+/// Occurrence selector a `replace` / `delete` op resolves to in `text`.
+///
+/// Context anchors are turned into the index of the single match they select;
+/// ops without context keep their declared `occurrence`.
+fn effective_occurrence(
+    i: usize,
+    op: &PatchOpV1,
+    text: &str,
+    before: &str,
+) -> Result<Option<Occurrence>, ApplyError> {
+    if !op.has_context() {
+        return Ok(op.occurrence);
+    }
+    match context_matches(text, before, op.context_before.as_deref(), op.context_after.as_deref())[..] {
+        [n] => u32::try_from(n)
+            .map(|n| Some(Occurrence::Index(n)))
+            .map_err(|_| op_failed(i, op, "context match index is too large")),
+        _ => Err(op_failed(
+            i,
+            op,
+            format!("context does not select exactly one match in block '{}' (should be validated)", op.block_id),
+        )),
+    }
+}
+
+/// Point the children of a removed block `from` at `to` (the removed block's own
+/// parent, or the block it was merged into), so sections stay connected.
+fn reparent_children(parents: &mut BTreeMap<String, String>, from: &str, to: Option<&str>) {
+    let children: Vec<String> = parents.iter().filter(|(_, p)| *p == from).map(|(c, _)| c.clone()).collect();
+    for child in children {
+        match to {
+            Some(to) => parents.insert(child, to.to_string()),
+            None => parents.remove(&child),
+        };
+    }
+}
+
+/// Document counterpart of `reparent_children`.
+fn reparent_doc_children(blocks: &mut [Block], from: &str, to: Option<&BlockId>) {
+    for b in blocks.iter_mut().filter(|b| b.parent.as_deref() == Some(from)) {
+        b.parent = to.cloned();
+    }
+}
+
+fn op_failed(op_index: usize, op: &PatchOpV1, message: impl Into<String>) -> ApplyError {
+    ApplyError::OpFailed {
+        op_index,
+        op: op.op,
+        block_id: op.block_id.clone(),
+        message: message.into(),
+    }
 }
 
 fn find_block_index(blocks: &[BlockTupleV1], block_id: &str) -> Option<usize> {
@@ -348,10 +607,6 @@ fn delete_first(haystack: &str, needle: &str) -> String {
     replace_first(haystack, needle, "")
 }
 
-/// Deterministic inserted id: "<anchor>_ins", or "<anchor>_ins2", "_ins3", ...
-
-/// Deterministic inserted id: "<anchor>_ins", or "<anchor>_ins2", "_ins3", ...
-
 /// Recompute block text hashes and packet hash `h`.
 ///
 /// Packet hash input is identical to the Document hash payload:
@@ -390,7 +645,7 @@ pub fn apply_patch_against_edit_packet_with_telemetry(
     packet: &EditPacketV1,
     patch: &PatchV1,
     opts: ValidateOptions,
-) -> (Result<EditPacketV1, String>, PatchTelemetry) {
+) -> (Result<EditPacketV1, ApplyError>, PatchTelemetry) {
     use std::time::Instant;
 
     let start = Instant::now();
@@ -425,7 +680,8 @@ pub fn apply_patch_against_edit_packet_with_telemetry(
         },
         input_chars,
         output_chars,
-        error_code: res.as_ref().err().map(|_| "apply_failed".to_string()),
+        tolerant_match_ops: PatchTelemetry::tolerant_ops(&tolerant_match_diagnostics_against_edit_packet(packet, patch, &opts)),
+        error_code: res.as_ref().err().map(|e| format!("{:?}", e.code()).to_lowercase()),
     };
 
     (res, tel)
@@ -438,7 +694,7 @@ pub fn apply_patch_against_document_with_telemetry(
     doc: &Document,
     patch: &PatchV1,
     opts: ValidateOptions,
-) -> (Result<Document, String>, PatchTelemetry) {
+) -> (Result<Document, ApplyError>, PatchTelemetry) {
     use std::time::Instant;
 
     let start = Instant::now();
@@ -473,7 +729,8 @@ pub fn apply_patch_against_document_with_telemetry(
         },
         input_chars,
         output_chars,
-        error_code: res.as_ref().err().map(|_| "apply_failed".to_string()),
+        tolerant_match_ops: PatchTelemetry::tolerant_ops(&tolerant_match_diagnostics(doc, patch, &opts)),
+        error_code: res.as_ref().err().map(|e| format!("{:?}", e.code()).to_lowercase()),
     };
 
     (res, tel)
@@ -0,0 +1,89 @@
+//! Engine-assigned ids for inserted blocks.
+//!
+//! `insert_after`, `insert_before` and `split_block` create a block, and the wire format
+//! requires the patch author to name it via `new_block_id`. Producers that cannot pick
+//! collision-free ids (typically models) may omit the field and let the engine fill it
+//! in with a `bdir_core::ids` strategy:
+//!
+//! - every id already in the Document, and every `new_block_id` the patch does set, is
+//!   reserved first, so generated ids never collide with them;
+//! - the kind prefix comes from the op's `kind_code` (for `split_block`, falling back to
+//!   the split block's kind); the parent is the anchor block's parent, as on apply;
+//! - content-addressed ids hash the inserted text (for `split_block`, the `before`
+//!   anchor) keyed by the op name and anchor block id.
+//!
+//! Ids are generated in `ops` order, so the same Document and patch always yield the
+//! same ids.
+
+use std::collections::HashMap;
+
+use bdir_codebook::id_prefix;
+use bdir_core::ids::{IdGenerator, IdRequest, IdStrategy};
+use bdir_core::model::Document;
+use bdir_editpacket::EditPacketV1;
+
+use crate::schema::{OpType, PatchV1};
+use crate::validate::packet_document;
+
+/// Return `patch` with every missing `new_block_id` filled in using `strategy`.
+///
+/// Ops that already carry a `new_block_id`, and ops whose anchor block does not exist,
+/// are left unchanged (the validator reports the latter).
+pub fn assign_new_block_ids(doc: &Document, patch: &PatchV1, strategy: IdStrategy) -> PatchV1 {
+    let mut out = patch.clone();
+    let creates_block = |op: OpType| matches!(op, OpType::InsertAfter | OpType::InsertBefore | OpType::SplitBlock);
+    if !out.ops.iter().any(|op| creates_block(op.op) && op.new_block_id.is_none()) {
+        return out;
+    }
+
+    let mut ids = IdGenerator::new(strategy);
+    // Anchor lookup: (kind_code, parent) per block id, including blocks created earlier
+    // in the patch.
+    let mut blocks: HashMap<String, (u16, Option<String>)> = HashMap::with_capacity(doc.blocks.len());
+    for b in &doc.blocks {
+        ids.reserve(&b.id);
+        blocks.insert(b.id.clone(), (b.kind_code, b.parent.clone()));
+    }
+    for id in out.ops.iter().filter_map(|op| op.new_block_id.as_deref()) {
+        ids.reserve(id);
+    }
+
+    for op in out.ops.iter_mut().filter(|op| creates_block(op.op)) {
+        let Some((anchor_kind, parent)) = blocks.get(&op.block_id).cloned() else {
+            continue;
+        };
+        let kind_code = match op.op {
+            OpType::SplitBlock => op.kind_code.unwrap_or(anchor_kind),
+            _ => match op.kind_code {
+                Some(kind_code) => kind_code,
+                None => continue,
+            },
+        };
+        if op.new_block_id.is_none() {
+            let text = match op.op {
+                OpType::SplitBlock => op.before.as_deref(),
+                _ => op.text.as_deref(),
+            };
+            let key = format!("{}:{}", op.op.as_str(), op.block_id);
+            op.new_block_id = Some(ids.next_id(&IdRequest {
+                prefix: id_prefix(kind_code),
+                text: text.unwrap_or(""),
+                parent: parent.as_deref(),
+                key: Some(&key),
+            }));
+        }
+        if let Some(id) = &op.new_block_id {
+            blocks.insert(id.clone(), (kind_code, parent));
+        }
+    }
+    out
+}
+
+/// Like `assign_new_block_ids`, reserving the ids and parents of an Edit Packet.
+pub fn assign_new_block_ids_against_edit_packet(
+    packet: &EditPacketV1,
+    patch: &PatchV1,
+    strategy: IdStrategy,
+) -> PatchV1 {
+    assign_new_block_ids(&packet_document(packet), patch, strategy)
+}
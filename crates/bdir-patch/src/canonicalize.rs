@@ -29,9 +29,12 @@ impl Default for CanonicalizeOptions {
 /// Canonicalize patch operation ordering without any document context.
 ///
 /// Ordering:
-/// 1) `block_id` (lexicographic)
-/// 2) operation type (delete, replace, insert_after, suggest)
-/// 3) operation-specific fields (`before`, `after`, insert_after fields, `message`, `occurrence`)
+/// 0) `move` ops first, in their original relative order (a later move may depend on an earlier one)
+/// 1) `block_id` (lexicographic; `merge_block` sorts under its `next_block_id`)
+/// 2) operation type (insert_before, delete, replace, reclassify, split_block, insert_after,
+///    suggest, merge_block, delete_block)
+/// 3) operation-specific fields (`before`, `after`, insert fields, `message`, `occurrence`,
+///    context anchors, `text_hash`)
 /// 4) original index (tie-breaker for deterministic output)
 pub fn canonicalize_patch_ops(patch: &mut PatchV1) {
     canonicalize_ops_inner(&mut patch.ops, None);
@@ -51,10 +54,21 @@ pub fn canonicalize_patch_ops_against_edit_packet(packet: &EditPacketV1, patch:
 
 fn op_rank(op: OpType) -> i32 {
     match op {
-        OpType::Delete => 0,
-        OpType::Replace => 1,
-        OpType::InsertAfter => 2,
-        OpType::Suggest => 3,
+        // Positionally ahead of the anchor block's own edits.
+        OpType::InsertBefore => 0,
+        OpType::Delete => 1,
+        OpType::Replace => 2,
+        OpType::Reclassify => 3,
+        // After the block's text edits, which were validated against the unsplit text.
+        OpType::SplitBlock => 4,
+        OpType::InsertAfter => 5,
+        OpType::Suggest => 6,
+        // Sorted under `next_block_id`, after that block's own edits.
+        OpType::MergeBlock => 7,
+        // Last within a block: ops are applied in order and nothing may follow a removal.
+        OpType::DeleteBlock => 8,
+        // Moves are ordered by `move_order` before any of this applies.
+        OpType::Move => 9,
     }
 }
 
@@ -76,15 +90,25 @@ fn canonicalize_ops_inner(ops: &mut Vec<PatchOpV1>, order: Option<&HashMap<&str,
         .iter()
         .enumerate()
         .map(|(i, op)| {
+            // A merge removes `next_block_id`, so it must follow that block's own edits.
+            let block_id = match op.op {
+                OpType::MergeBlock => op.next_block_id.as_deref().unwrap_or(&op.block_id),
+                _ => op.block_id.as_str(),
+            };
             let block_pos = order
-                .and_then(|m| m.get(op.block_id.as_str()).copied())
+                .and_then(|m| m.get(block_id).copied())
                 .unwrap_or(i64::MAX);
 
+            // Moves keep their relative order and run before edits; the validator
+            // rejects inserts whose anchor a later move relocates, so this is safe.
+            let move_order = if op.op == OpType::Move { i as i64 } else { i64::MAX };
+
             (
                 i,
                 CanonicalKey {
+                    move_order,
                     block_pos,
-                    block_id: op.block_id.clone(),
+                    block_id: block_id.to_string(),
                     op_rank: op_rank(op.op),
                     before: op.before.clone().unwrap_or_default(),
                     after: op.after.clone().unwrap_or_default(),
@@ -93,6 +117,9 @@ fn canonicalize_ops_inner(ops: &mut Vec<PatchOpV1>, order: Option<&HashMap<&str,
                     insert_text: op.text.clone().unwrap_or_default(),
                     message: op.message.clone().unwrap_or_default(),
                     occurrence_rank: occurrence_rank(op.occurrence),
+                    context_before: op.context_before.clone().unwrap_or_default(),
+                    context_after: op.context_after.clone().unwrap_or_default(),
+                    text_hash: op.text_hash.clone().unwrap_or_default(),
                 },
             )
         })
@@ -111,6 +138,7 @@ fn canonicalize_ops_inner(ops: &mut Vec<PatchOpV1>, order: Option<&HashMap<&str,
 
 #[derive(Debug, Clone, PartialEq, Eq)]
 struct CanonicalKey {
+    move_order: i64,
     block_pos: i64,
     block_id: String,
     op_rank: i32,
@@ -121,12 +149,16 @@ struct CanonicalKey {
     insert_text: String,
     message: String,
     occurrence_rank: i64,
+    context_before: String,
+    context_after: String,
+    text_hash: String,
 }
 
 impl Ord for CanonicalKey {
     fn cmp(&self, other: &Self) -> std::cmp::Ordering {
-        self.block_pos
-            .cmp(&other.block_pos)
+        self.move_order
+            .cmp(&other.move_order)
+            .then_with(|| self.block_pos.cmp(&other.block_pos))
             .then_with(|| self.block_id.cmp(&other.block_id))
             .then_with(|| self.op_rank.cmp(&other.op_rank))
             .then_with(|| self.before.cmp(&other.before))
@@ -136,6 +168,9 @@ impl Ord for CanonicalKey {
             .then_with(|| self.insert_text.cmp(&other.insert_text))
             .then_with(|| self.message.cmp(&other.message))
             .then_with(|| self.occurrence_rank.cmp(&other.occurrence_rank))
+            .then_with(|| self.context_before.cmp(&other.context_before))
+            .then_with(|| self.context_after.cmp(&other.context_after))
+            .then_with(|| self.text_hash.cmp(&other.text_hash))
     }
 }
 
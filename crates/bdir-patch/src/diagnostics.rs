@@ -13,9 +13,28 @@ pub enum DiagnosticCode {
     UnsupportedEditPacketVersion,
     PatchPageHashMismatch,
     PatchPageHashMissing,
+    /// A page or block hash is shorter than the minimum truncated prefix length (RFC-0001 §5.1.4).
+    HashTooShort,
+    /// A targeted block's `text_hash` does not match the hash of its text.
+    BlockHashMismatch,
+    /// An op targets a block that an earlier `delete_block` op in the same patch removes.
+    TargetsDeletedBlock,
+    /// `move` ops in the same patch form a cycle (including moving a block after itself).
+    MoveCycle,
+    /// An insert depends on block positions that a later `move` op changes.
+    MoveConflict,
+    /// `merge_block` names a `next_block_id` that does not directly follow `block_id`.
+    BlocksNotAdjacent,
+    /// `split_block` / `merge_block` combine with other ops in a way canonical ordering
+    /// cannot preserve (e.g. editing a block after splitting it).
+    StructuralConflict,
+    /// An op could not be applied even though validation accepted it.
+    ApplyOpFailed,
     /// Patch hash algorithm (`ha`) does not match the target document/packet algorithm.
     HashAlgorithmMismatch,
-    /// insert_after `new_block_id` conflicts with an existing block id.
+    /// The target document/packet (or patch `ha`) declares a hash algorithm that is not registered.
+    UnsupportedHashAlgorithm,
+    /// `new_block_id` conflicts with an existing block id or one an earlier op creates.
     DuplicateBlockId,
     UnknownBlockId,
     MissingField,
@@ -27,6 +46,16 @@ pub enum DiagnosticCode {
     BeforeAmbiguous,
     /// `occurrence` was provided but is invalid or out of range.
     OccurrenceOutOfRange,
+    /// `context_before` / `context_after` select none of the `before` matches.
+    ContextNotFound,
+    /// `context_before` / `context_after` still leave more than one `before` match.
+    ContextAmbiguous,
+    /// Informational: `before` matched only after tolerant (whitespace/typography) folding.
+    TolerantMatch,
+    /// `ValidateOptions::section` names a block that does not exist in the target.
+    SectionNotFound,
+    /// An op references a block outside the section `ValidateOptions::section` restricts it to.
+    OutOfSection,
     /// The target block's kindCode is not allowed under strict kindCode policy enforcement.
     KindCodeDisallowed,
     /// The edit packet's kindCode is outside RFC-0001 v1 canonical importance ranges.
@@ -53,12 +82,30 @@ pub struct ValidationDiagnostic {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub block_id: Option<String>,
     pub message: String,
+    /// `before_not_found` only: the nearest substring of the target block, when one is
+    /// close enough to be a plausible typo (curly quotes, doubled spaces, casing).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub closest_match: Option<ClosestMatch>,
+}
+
+/// Nearest candidate in a block for a `before` substring that does not occur in it.
+///
+/// Computed over NFC text with char-level edit distance (insertions, deletions and
+/// substitutions each cost 1).
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ClosestMatch {
+    /// The candidate substring of the block text.
+    pub text: String,
+    /// Char offset of the candidate in the block's NFC text.
+    pub offset: usize,
+    /// Edit distance between `before` and the candidate.
+    pub distance: usize,
 }
 
 /// Structured error wrapper for validation failures.
 ///
-/// The validator is currently fail-fast and returns a single diagnostic, but the
-/// container supports multiple diagnostics to allow future expansion.
+/// The validator is fail-fast by default and returns a single diagnostic. With
+/// `ValidateOptions::collect_all_diagnostics` it returns every diagnostic it finds.
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct ValidationError {
     pub diagnostics: Vec<ValidationDiagnostic>,
@@ -89,3 +136,99 @@ impl std::fmt::Display for ValidationError {
 }
 
 impl std::error::Error for ValidationError {}
+
+/// Structured error for patch application.
+///
+/// Validation failures keep the validator's diagnostics intact. The remaining variants
+/// cover failures that can only surface while ops are being applied.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ApplyError {
+    /// The patch was rejected by the validator.
+    Validation(ValidationError),
+    /// The target declares a hash algorithm that is not registered, so hashes cannot be recomputed.
+    UnsupportedHashAlgorithm {
+        algorithm: String,
+        supported: Vec<String>,
+    },
+    /// An op failed during application even though validation passed.
+    ///
+    /// This indicates a validator/applicator invariant violation.
+    OpFailed {
+        op_index: usize,
+        op: OpType,
+        block_id: String,
+        message: String,
+    },
+}
+
+impl ApplyError {
+    /// Diagnostics for this error, in the same shape the validator produces.
+    ///
+    /// Validation failures return the validator's diagnostics unchanged; apply-phase
+    /// failures map to a single diagnostic.
+    pub fn diagnostics(&self) -> ValidationError {
+        match self {
+            ApplyError::Validation(e) => e.clone(),
+            ApplyError::UnsupportedHashAlgorithm { .. } => ValidationError::single(ValidationDiagnostic {
+                code: DiagnosticCode::UnsupportedHashAlgorithm,
+                path: Some("ha".to_string()),
+                op_index: None,
+                op: None,
+                block_id: None,
+                message: self.to_string(),
+                closest_match: None,
+            }),
+            ApplyError::OpFailed { op_index, op, block_id, message } => ValidationError::single(ValidationDiagnostic {
+                code: DiagnosticCode::ApplyOpFailed,
+                path: Some(format!("ops[{op_index}]")),
+                op_index: Some(*op_index),
+                op: Some(*op),
+                block_id: Some(block_id.clone()),
+                message: message.clone(),
+                closest_match: None,
+            }),
+        }
+    }
+
+    /// Machine-readable code of the primary diagnostic.
+    pub fn code(&self) -> DiagnosticCode {
+        match self {
+            ApplyError::Validation(e) => e
+                .diagnostics
+                .first()
+                .map(|d| d.code)
+                .unwrap_or(DiagnosticCode::ApplyOpFailed),
+            ApplyError::UnsupportedHashAlgorithm { .. } => DiagnosticCode::UnsupportedHashAlgorithm,
+            ApplyError::OpFailed { .. } => DiagnosticCode::ApplyOpFailed,
+        }
+    }
+}
+
+impl From<ValidationError> for ApplyError {
+    fn from(e: ValidationError) -> Self {
+        ApplyError::Validation(e)
+    }
+}
+
+impl std::fmt::Display for ApplyError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ApplyError::Validation(e) => write!(f, "{e}"),
+            ApplyError::UnsupportedHashAlgorithm { algorithm, supported } => write!(
+                f,
+                "unsupported hash algorithm '{algorithm}' (supported: {})",
+                supported.join(", ")
+            ),
+            ApplyError::OpFailed { message, .. } => write!(f, "{message}"),
+        }
+    }
+}
+
+impl std::error::Error for ApplyError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            ApplyError::Validation(e) => Some(e),
+            _ => None,
+        }
+    }
+}
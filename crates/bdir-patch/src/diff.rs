@@ -0,0 +1,562 @@
+//! Document diff: express the difference between two revisions as a patch.
+//!
+//! Blocks are aligned by id. Text changes inside a block are found with a word-level
+//! diff and emitted as small, uniquely anchored `replace` / `delete` ops; blocks that
+//! only exist in the updated revision become `insert_after` ops, blocks that were
+//! dropped become `delete_block` ops, reordered blocks become `move` ops and blocks
+//! whose kind changed become `reclassify` ops.
+
+use std::collections::{HashMap, HashSet};
+
+use bdir_core::hash::{hash_canon_hex, normalize_nfc};
+use bdir_core::model::{Block, Document};
+
+use crate::anchor::{anchored_replace, byte_offset, is_unique_at};
+use crate::apply::apply_patch_against_document_with_options;
+use crate::diagnostics::ApplyError;
+use crate::schema::{PatchOpV1, PatchV1};
+use crate::validate::ValidateOptions;
+
+/// Token-pair budget for the word-level LCS table; larger blocks are diffed as one hunk.
+const MAX_LCS_CELLS: usize = 1 << 20;
+
+/// Error returned when two documents cannot be diffed into a patch.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DiffError {
+    /// The generated patch does not apply to the original document.
+    Apply(ApplyError),
+    /// The change cannot be expressed with the available patch ops.
+    Unrepresentable { block_id: String, message: String },
+}
+
+impl std::fmt::Display for DiffError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            DiffError::Apply(e) => write!(f, "{e}"),
+            DiffError::Unrepresentable { message, .. } => write!(f, "{message}"),
+        }
+    }
+}
+
+impl std::error::Error for DiffError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            DiffError::Apply(e) => Some(e),
+            DiffError::Unrepresentable { .. } => None,
+        }
+    }
+}
+
+/// Produce a patch that turns `original` into `updated`.
+pub fn diff_documents(original: &Document, updated: &Document) -> Result<PatchV1, DiffError> {
+    diff_documents_with_options(original, updated, ValidateOptions::default())
+}
+
+/// Produce a diff patch using custom validation options.
+///
+/// `opts` is used to check the generated patch, so its `before` strings honour the
+/// same `min_before_len` and policy settings.
+///
+/// Semantics:
+/// - blocks are aligned by id; text is compared in NFC (RFC-0001 §2.2)
+/// - each word-level hunk in a changed block becomes one op, widened by whole tokens
+///   until `before` is unique in the block and at least `min_before_len` chars; a hunk
+///   that only removes text and needs no context becomes a `delete`
+/// - blocks that only exist in `updated` become `insert_after` ops anchored on the
+///   nearest preceding block present in both revisions, or `insert_before` the next
+///   such block when they lead the document or their parent only matches that block's
+///   (inserted blocks take their anchor's parent)
+/// - blocks that only exist in `original` become `delete_block` ops
+/// - blocks whose `kind_code` changed get a `reclassify` op
+/// - blocks present in both revisions but in a different order become `move` ops,
+///   emitted first; the longest run of blocks already in order stays put
+/// - other ops follow `original` document order and the patch is bound (`h`/`ha`) to `original`
+/// - identical documents produce a patch with no ops
+///
+/// Safety: the patch is applied to `original` before it is returned and must reproduce
+/// `updated` exactly, parents included. A block whose word-level ops do not round-trip
+/// falls back to a single anchored `replace`; if that still fails, or a parent change
+/// cannot be expressed with patch ops, `Unrepresentable` is returned.
+pub fn diff_documents_with_options(
+    original: &Document,
+    updated: &Document,
+    opts: ValidateOptions,
+) -> Result<PatchV1, DiffError> {
+    let orig_ids: HashSet<&str> = original.blocks.iter().map(|b| b.id.as_str()).collect();
+    let mut seen: HashSet<&str> = HashSet::new();
+    if let Some(b) = updated.blocks.iter().find(|b| !seen.insert(b.id.as_str())) {
+        return Err(unrepresentable(
+            &b.id,
+            format!("block id '{}' appears more than once in the updated document", b.id),
+        ));
+    }
+
+    // Reorders become `move` ops and kind changes become `reclassify` ops.
+    let kept: Vec<&Block> = updated.blocks.iter().filter(|b| orig_ids.contains(b.id.as_str())).collect();
+    let kept_by_id: HashMap<&str, &Block> = kept.iter().map(|b| (b.id.as_str(), *b)).collect();
+    let reclassified: HashMap<&str, u16> = original
+        .blocks
+        .iter()
+        .filter_map(|orig| {
+            let upd = kept_by_id.get(orig.id.as_str())?;
+            (orig.kind_code != upd.kind_code).then_some((orig.id.as_str(), upd.kind_code))
+        })
+        .collect();
+    let survivor_ids: Vec<&str> =
+        original.blocks.iter().map(|b| b.id.as_str()).filter(|id| kept_by_id.contains_key(id)).collect();
+    let kept_ids: Vec<&str> = kept.iter().map(|b| b.id.as_str()).collect();
+    let moves = reorder_ops(&survivor_ids, &kept_ids);
+
+    // New blocks grouped by the kept block they are inserted next to, in `updated` order.
+    let anchors: HashMap<&str, Option<&str>> = kept.iter().map(|b| (b.id.as_str(), b.parent.as_deref())).collect();
+    let inserts = anchor_inserts(&updated.blocks, &anchors).map_err(|first| {
+        unrepresentable(
+            &first.id,
+            format!("block '{}' cannot be anchored: no block of the original document survives", first.id),
+        )
+    })?;
+
+    // Per original block: `None` when it was removed, otherwise its text ops.
+    let mut block_ops: Vec<Option<Vec<PatchOpV1>>> = Vec::with_capacity(original.blocks.len());
+    for orig in &original.blocks {
+        let Some(upd) = kept_by_id.get(orig.id.as_str()) else {
+            block_ops.push(None);
+            continue;
+        };
+        let cur = normalize_nfc(&orig.text);
+        let tgt = normalize_nfc(&upd.text);
+        if cur == tgt {
+            block_ops.push(Some(Vec::new()));
+            continue;
+        }
+        if cur.is_empty() {
+            return Err(unrepresentable(
+                &orig.id,
+                format!("block '{}' is empty in the original; a replace op cannot anchor on empty text", orig.id),
+            ));
+        }
+        block_ops.push(Some(word_diff_ops(&orig.id, &cur, &tgt, opts.min_before_len)));
+    }
+
+    let check_opts = ValidateOptions { expected_page_hash: None, ..opts.clone() };
+    let mut fell_back: HashSet<usize> = HashSet::new();
+    loop {
+        let patch = PatchV1 {
+            v: 1,
+            h: Some(original.page_hash.clone()),
+            ha: Some(original.hash_algorithm.clone()),
+            ops: moves.iter().cloned().chain(assemble_ops(original, &block_ops, &inserts, &reclassified)).collect(),
+        };
+        // Fail closed: the patch must reproduce `updated` exactly.
+        let applied = if patch.ops.is_empty() {
+            original.clone()
+        } else {
+            apply_patch_against_document_with_options(original, &patch, check_opts.clone()).map_err(DiffError::Apply)?
+        };
+
+        let mut retry = false;
+        for (i, orig) in original.blocks.iter().enumerate() {
+            let (Some(got), Some(upd)) = (
+                applied.blocks.iter().find(|b| b.id == orig.id),
+                updated.blocks.iter().find(|b| b.id == orig.id),
+            ) else {
+                continue;
+            };
+            if normalize_nfc(&got.text) == normalize_nfc(&upd.text) {
+                continue;
+            }
+            if !fell_back.insert(i) {
+                return Err(unrepresentable(
+                    &orig.id,
+                    format!("generated patch does not reproduce block '{}'", orig.id),
+                ));
+            }
+            block_ops[i] = Some(
+                anchored_replace(&orig.id, &orig.text, &upd.text, opts.min_before_len)
+                    .into_iter()
+                    .collect(),
+            );
+            retry = true;
+        }
+        if retry {
+            continue;
+        }
+
+        let (got, want) = (block_shape(&applied), block_shape(updated));
+        if let Some((got, want)) = parent_mismatch(&got, &want) {
+            return Err(unrepresentable(
+                want.0,
+                format!(
+                    "block '{}' cannot be reparented with patch ops (parent {} in the updated document, {} after the patch)",
+                    want.0,
+                    describe_parent(want.2),
+                    describe_parent(got.2)
+                ),
+            ));
+        }
+        if got != want {
+            return Err(unrepresentable(
+                "",
+                "generated patch does not reproduce the updated block order".to_string(),
+            ));
+        }
+        return Ok(patch);
+    }
+}
+
+fn unrepresentable(block_id: &str, message: String) -> DiffError {
+    DiffError::Unrepresentable { block_id: block_id.to_string(), message }
+}
+
+/// New blocks keyed by the kept block they are anchored on.
+#[derive(Default)]
+pub(crate) struct Inserts<'a> {
+    pub(crate) before: HashMap<&'a str, Vec<&'a Block>>,
+    pub(crate) after: HashMap<&'a str, Vec<&'a Block>>,
+}
+
+/// Anchor every block of `blocks` that is not a key of `anchors` on a neighbouring
+/// anchor block, keeping `blocks` order.
+///
+/// `anchors` maps each kept block to its parent where the inserts land. An inserted
+/// block takes its anchor's parent, so a run between two anchors goes `insert_after`
+/// the preceding one while the parents match and `insert_before` the following one from
+/// there on; runs ahead of every anchor go before the first one. Returns the first
+/// block of such a run when there is no anchor at all.
+pub(crate) fn anchor_inserts<'a>(
+    blocks: &'a [Block],
+    anchors: &HashMap<&str, Option<&str>>,
+) -> Result<Inserts<'a>, &'a Block> {
+    let mut inserts = Inserts::default();
+    let mut prev: Option<&str> = None;
+    let mut run: Vec<&Block> = Vec::new();
+    for b in blocks {
+        if !anchors.contains_key(b.id.as_str()) {
+            run.push(b);
+            continue;
+        }
+        let split = prev.map_or(0, |p| run.iter().take_while(|r| r.parent.as_deref() == anchors[p]).count());
+        let tail = run.split_off(split);
+        if let Some(p) = prev.filter(|_| !run.is_empty()) {
+            inserts.after.insert(p, std::mem::take(&mut run));
+        }
+        if !tail.is_empty() {
+            inserts.before.insert(b.id.as_str(), tail);
+        }
+        prev = Some(b.id.as_str());
+    }
+    match (prev, run.first()) {
+        (None, Some(first)) => Err(first),
+        (Some(p), Some(_)) => {
+            inserts.after.insert(p, run);
+            Ok(inserts)
+        }
+        _ => Ok(inserts),
+    }
+}
+
+/// `(id, kind_code, parent)` of a block.
+pub(crate) type BlockShape<'a> = (&'a str, u16, Option<&'a str>);
+
+/// Shape of every block, in order.
+pub(crate) fn block_shape(doc: &Document) -> Vec<BlockShape<'_>> {
+    doc.blocks.iter().map(|b| (b.id.as_str(), b.kind_code, b.parent.as_deref())).collect()
+}
+
+/// First block whose shapes differ only in the parent, when no block differs otherwise.
+pub(crate) fn parent_mismatch<'s, 'a>(
+    got: &'s [BlockShape<'a>],
+    want: &'s [BlockShape<'a>],
+) -> Option<(&'s BlockShape<'a>, &'s BlockShape<'a>)> {
+    let same_blocks = got.len() == want.len() && got.iter().zip(want).all(|(g, w)| (g.0, g.1) == (w.0, w.1));
+    if !same_blocks {
+        return None;
+    }
+    got.iter().zip(want).find(|(g, w)| g.2 != w.2)
+}
+
+/// `'id'` of a parent, or `none` for a root block.
+pub(crate) fn describe_parent(parent: Option<&str>) -> String {
+    parent.map_or_else(|| "none".to_string(), |p| format!("'{p}'"))
+}
+
+/// Interleave per-block ops with the blocks inserted around each block.
+///
+/// A block's `reclassify` op follows its text ops, mirroring canonical op order.
+/// Removed blocks get a `delete_block` bound to the hash of their current text.
+/// `insert_after` places the new block directly after its anchor, so a run of new
+/// blocks sharing one anchor is emitted in reverse to keep `updated` order;
+/// `insert_before` runs keep their order.
+fn assemble_ops(
+    original: &Document,
+    block_ops: &[Option<Vec<PatchOpV1>>],
+    inserts: &Inserts<'_>,
+    reclassified: &HashMap<&str, u16>,
+) -> Vec<PatchOpV1> {
+    let mut ops = Vec::new();
+    for (orig, text_ops) in original.blocks.iter().zip(block_ops) {
+        let Some(text_ops) = text_ops else {
+            let text_hash = hash_canon_hex(&original.hash_algorithm, &orig.text)
+                .unwrap_or_else(|| orig.text_hash.clone());
+            ops.push(PatchOpV1::delete_block(&orig.id, text_hash));
+            continue;
+        };
+        if let Some(run) = inserts.before.get(orig.id.as_str()) {
+            for b in run {
+                ops.push(PatchOpV1::insert_before(&orig.id, &b.id, b.kind_code, normalize_nfc(&b.text)));
+            }
+        }
+        ops.extend(text_ops.iter().cloned());
+        if let Some(&kind_code) = reclassified.get(orig.id.as_str()) {
+            ops.push(PatchOpV1::reclassify(&orig.id, kind_code));
+        }
+        if let Some(run) = inserts.after.get(orig.id.as_str()) {
+            for b in run.iter().rev() {
+                ops.push(PatchOpV1::insert_after(&orig.id, &b.id, b.kind_code, normalize_nfc(&b.text)));
+            }
+        }
+    }
+    ops
+}
+
+/// `move` ops that turn the block order `from` into `to` (the same ids, reordered).
+///
+/// The first block of `to` stays put (a move can only place a block after another),
+/// as does the longest run of later blocks already in order after it. Every other
+/// block is moved after its predecessor in `to`, in `to` order.
+pub(crate) fn reorder_ops(from: &[&str], to: &[&str]) -> Vec<PatchOpV1> {
+    let Some(&first) = to.first() else {
+        return Vec::new();
+    };
+    let pos: HashMap<&str, usize> = from.iter().enumerate().map(|(i, id)| (*id, i)).collect();
+    let first_pos = pos[first];
+
+    // Longest increasing subsequence of `from` positions over `to[1..]` (patience sort).
+    let candidates: Vec<usize> = (1..to.len()).filter(|&k| pos[to[k]] > first_pos).collect();
+    let mut tails: Vec<usize> = Vec::new();
+    let mut prev: Vec<Option<usize>> = vec![None; candidates.len()];
+    for (c, &k) in candidates.iter().enumerate() {
+        let p = pos[to[k]];
+        let at = tails.partition_point(|&t| pos[to[candidates[t]]] < p);
+        prev[c] = at.checked_sub(1).map(|a| tails[a]);
+        if at == tails.len() {
+            tails.push(c);
+        } else {
+            tails[at] = c;
+        }
+    }
+    let mut fixed: HashSet<usize> = HashSet::from([0]);
+    let mut cur = tails.last().copied();
+    while let Some(c) = cur {
+        fixed.insert(candidates[c]);
+        cur = prev[c];
+    }
+
+    (1..to.len())
+        .filter(|k| !fixed.contains(k))
+        .map(|k| PatchOpV1::move_after(to[k], to[k - 1]))
+        .collect()
+}
+
+// -----------------------------------------------------------------------------
+// Word-level diff
+// -----------------------------------------------------------------------------
+
+/// Token index ranges `[a0, a1)` in the current text and `[b0, b1)` in the target.
+#[derive(Debug, Clone, Copy)]
+struct Hunk {
+    a0: usize,
+    a1: usize,
+    b0: usize,
+    b1: usize,
+}
+
+/// Emit one op per word-level hunk of an NFC `current` -> `target` change.
+fn word_diff_ops(block_id: &str, current: &str, target: &str, min_before_len: usize) -> Vec<PatchOpV1> {
+    let cur: Vec<char> = current.chars().collect();
+    let tgt: Vec<char> = target.chars().collect();
+    let ta = tokenize(&cur);
+    let tb = tokenize(&tgt);
+    let cur_s = current;
+
+    let before_of = |w: &Hunk| -> String {
+        let (s, e) = char_span(&ta, w.a0, w.a1, cur.len());
+        cur[s..e].iter().collect()
+    };
+    let anchored = |w: &Hunk| -> bool {
+        let before = before_of(w);
+        let (s, _) = char_span(&ta, w.a0, w.a1, cur.len());
+        before.chars().count() >= min_before_len
+            && !before.trim().is_empty()
+            && is_unique_at(cur_s, &before, byte_offset(&cur, s))
+    };
+    let is_space = |t: &[(usize, usize)], chars: &[char], k: usize| chars[t[k].0].is_whitespace();
+
+    // `pending` is a stack: the next hunk in text order is at the end.
+    let mut pending: Vec<Hunk> = diff_hunks(&cur, &ta, &tgt, &tb);
+    pending.reverse();
+    let mut done: Vec<(Hunk, Hunk)> = Vec::new();
+
+    while let Some(mut core) = pending.pop() {
+        loop {
+            let left_bound = done.last().map_or(0, |(_, w)| w.a1);
+            let right_bound = pending.last().map_or(ta.len(), |h| h.a0);
+            let mut w = core;
+            let grown = loop {
+                if anchored(&w) || (w.a0 == 0 && w.a1 == ta.len()) {
+                    break true;
+                }
+                let can_left = w.a0 > left_bound;
+                let can_right = w.a1 < right_bound;
+                if !can_left && !can_right {
+                    break false;
+                }
+                // Grow by whole words: never stop on a whitespace token when the next
+                // token is still available.
+                if can_left {
+                    w.a0 -= 1;
+                    w.b0 -= 1;
+                    if w.a0 > left_bound && is_space(&ta, &cur, w.a0) {
+                        w.a0 -= 1;
+                        w.b0 -= 1;
+                    }
+                }
+                if can_right {
+                    w.a1 += 1;
+                    w.b1 += 1;
+                    if w.a1 < right_bound && is_space(&ta, &cur, w.a1 - 1) {
+                        w.a1 += 1;
+                        w.b1 += 1;
+                    }
+                }
+            };
+            if grown {
+                done.push((core, w));
+                break;
+            }
+
+            // The window ran into a neighbouring hunk: fold it in and grow again.
+            if let Some(next) = pending.pop() {
+                core = Hunk { a1: next.a1, b1: next.b1, ..core };
+            } else if let Some((prev, _)) = done.pop() {
+                core = Hunk { a0: prev.a0, b0: prev.b0, ..core };
+            } else {
+                done.push((core, w));
+                break;
+            }
+        }
+    }
+
+    done.iter()
+        .map(|(_, w)| {
+            let before = before_of(w);
+            let (s, e) = char_span(&tb, w.b0, w.b1, tgt.len());
+            let after: String = tgt[s..e].iter().collect();
+            if after.is_empty() {
+                PatchOpV1::delete(block_id, before)
+            } else {
+                PatchOpV1::replace(block_id, before, after)
+            }
+        })
+        .collect()
+}
+
+/// Split text into word, whitespace and single-punctuation tokens (char ranges).
+fn tokenize(chars: &[char]) -> Vec<(usize, usize)> {
+    fn class(c: char) -> u8 {
+        if c.is_whitespace() {
+            0
+        } else if c.is_alphanumeric() || c == '_' {
+            1
+        } else {
+            2
+        }
+    }
+
+    let mut tokens = Vec::new();
+    let mut start = 0;
+    for i in 1..=chars.len() {
+        let split = i == chars.len() || class(chars[i]) != class(chars[start]) || class(chars[start]) == 2;
+        if split {
+            tokens.push((start, i));
+            start = i;
+        }
+    }
+    tokens
+}
+
+fn char_span(tokens: &[(usize, usize)], lo: usize, hi: usize, len: usize) -> (usize, usize) {
+    if lo == hi {
+        let pos = tokens.get(lo).map_or(len, |t| t.0);
+        return (pos, pos);
+    }
+    (tokens[lo].0, tokens[hi - 1].1)
+}
+
+/// Token-level LCS hunks; hunks separated only by whitespace are merged.
+fn diff_hunks(cur: &[char], ta: &[(usize, usize)], tgt: &[char], tb: &[(usize, usize)]) -> Vec<Hunk> {
+    let eq = |i: usize, j: usize| cur[ta[i].0..ta[i].1] == tgt[tb[j].0..tb[j].1];
+
+    let max_common = ta.len().min(tb.len());
+    let prefix = (0..max_common).take_while(|&k| eq(k, k)).count();
+    let suffix = (0..max_common - prefix)
+        .take_while(|&k| eq(ta.len() - 1 - k, tb.len() - 1 - k))
+        .count();
+    let (na, nb) = (ta.len() - prefix - suffix, tb.len() - prefix - suffix);
+
+    let mut hunks = Vec::new();
+    if na == 0 && nb == 0 {
+        return hunks;
+    }
+    if na == 0 || nb == 0 || na.saturating_mul(nb) > MAX_LCS_CELLS {
+        hunks.push(Hunk { a0: prefix, a1: prefix + na, b0: prefix, b1: prefix + nb });
+        return hunks;
+    }
+
+    // dp[i][j] = LCS length of the middle token suffixes starting at (i, j).
+    let width = nb + 1;
+    let mut dp = vec![0u32; (na + 1) * width];
+    for i in (0..na).rev() {
+        for j in (0..nb).rev() {
+            dp[i * width + j] = if eq(prefix + i, prefix + j) {
+                dp[(i + 1) * width + j + 1] + 1
+            } else {
+                dp[(i + 1) * width + j].max(dp[i * width + j + 1])
+            };
+        }
+    }
+
+    let (mut i, mut j) = (0, 0);
+    let mut open: Option<Hunk> = None;
+    while i < na || j < nb {
+        if i < na && j < nb && eq(prefix + i, prefix + j) {
+            hunks.extend(open.take());
+            i += 1;
+            j += 1;
+            continue;
+        }
+        let h = open.get_or_insert(Hunk { a0: prefix + i, a1: prefix + i, b0: prefix + j, b1: prefix + j });
+        if j == nb || (i < na && dp[(i + 1) * width + j] >= dp[i * width + j + 1]) {
+            i += 1;
+            h.a1 = prefix + i;
+        } else {
+            j += 1;
+            h.b1 = prefix + j;
+        }
+    }
+    hunks.extend(open.take());
+
+    let mut merged: Vec<Hunk> = Vec::with_capacity(hunks.len());
+    for h in hunks {
+        if let Some(last) = merged.last_mut() {
+            let gap_is_space = (last.a1..h.a0).all(|k| cur[ta[k].0].is_whitespace());
+            if gap_is_space {
+                last.a1 = h.a1;
+                last.b1 = h.b1;
+                continue;
+            }
+        }
+        merged.push(h);
+    }
+    merged
+}
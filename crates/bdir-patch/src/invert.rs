@@ -0,0 +1,213 @@
+//! Inverse patch generation (undo).
+//!
+//! Given the original document and a patch that applies to it, produce a patch that
+//! restores the original text when applied to the updated document. The inverse is
+//! bound to the updated page hash so it cannot be applied anywhere else.
+
+use std::collections::{HashMap, HashSet};
+
+use bdir_core::hash::normalize_nfc;
+use bdir_core::model::Document;
+
+use crate::anchor::anchored_replace;
+use crate::apply::apply_patch_against_document_with_options;
+use crate::diagnostics::ApplyError;
+use crate::diff::{anchor_inserts, block_shape, describe_parent, parent_mismatch, reorder_ops};
+use crate::schema::{OpType, PatchOpV1, PatchV1};
+use crate::validate::ValidateOptions;
+
+/// Error returned when an inverse patch cannot be produced.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum InvertError {
+    /// The forward patch does not apply to the original document.
+    Apply(ApplyError),
+    /// The forward patch does not change any block text, so there is nothing to undo.
+    NoChanges,
+    /// The forward patch cannot be undone with the available ops.
+    NotInvertible {
+        op_index: Option<usize>,
+        block_id: String,
+        message: String,
+    },
+}
+
+impl std::fmt::Display for InvertError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            InvertError::Apply(e) => write!(f, "{e}"),
+            InvertError::NoChanges => write!(f, "patch makes no changes; nothing to invert"),
+            InvertError::NotInvertible { message, .. } => write!(f, "{message}"),
+        }
+    }
+}
+
+impl std::error::Error for InvertError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            InvertError::Apply(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+/// Produce a patch that undoes `patch` once it has been applied to `original`.
+pub fn invert_patch(original: &Document, patch: &PatchV1) -> Result<PatchV1, InvertError> {
+    invert_patch_with_options(original, patch, ValidateOptions::default())
+}
+
+/// Produce an inverse patch using custom validation options.
+///
+/// `opts` is used both to apply the forward patch and to check the inverse, so the
+/// inverse honours the same `min_before_len` and policy settings.
+///
+/// Semantics:
+/// - every block whose text changed gets one `replace` op, anchored on the smallest
+///   unique window around the change, in document order
+/// - blocks removed by `delete_block` are re-created with `insert_after` on the nearest
+///   preceding surviving block, or `insert_before` the next survivor when none precedes
+///   or only that one has the block's original parent (restored blocks take their
+///   anchor's parent); blocks added by `insert_after` / `insert_before` get a
+///   `delete_block`
+/// - blocks relocated by `move` are moved back first, so the original order is restored
+/// - blocks whose `kind_code` changed get a `reclassify` op back to the original kind
+/// - `suggest` ops are non-mutating and produce nothing
+/// - the inverse is bound (`h`/`ha`) to the updated document
+///
+/// Safety: the inverse is applied to the updated document before it is returned and
+/// must reproduce the original blocks (ids, kind codes, parents, NFC text) exactly;
+/// otherwise `NotInvertible` is returned. Parents that no op can restore (children
+/// reparented by `delete_block` / `merge_block`) make the patch not invertible.
+pub fn invert_patch_with_options(
+    original: &Document,
+    patch: &PatchV1,
+    opts: ValidateOptions,
+) -> Result<PatchV1, InvertError> {
+    let updated = apply_patch_against_document_with_options(original, patch, opts.clone())
+        .map_err(InvertError::Apply)?;
+
+    let original_ids: HashSet<&str> = original.blocks.iter().map(|b| b.id.as_str()).collect();
+    let updated_ids: HashSet<&str> = updated.blocks.iter().map(|b| b.id.as_str()).collect();
+
+    // Blocks removed by `delete_block` / `merge_block`, grouped by the surviving block
+    // they are restored next to (which gives them its parent in the updated document).
+    let anchors: HashMap<&str, Option<&str>> = updated
+        .blocks
+        .iter()
+        .filter(|b| original_ids.contains(b.id.as_str()))
+        .map(|b| (b.id.as_str(), b.parent.as_deref()))
+        .collect();
+    let restores = anchor_inserts(&original.blocks, &anchors).map_err(|first| {
+        let op_index = patch
+            .ops
+            .iter()
+            .position(|op| op.op == OpType::DeleteBlock && op.block_id == first.id);
+        InvertError::NotInvertible {
+            op_index,
+            block_id: first.id.clone(),
+            message: format!(
+                "{}(delete_block) cannot be inverted: no block of the original document survives to anchor block '{}'",
+                op_index.map(|i| format!("ops[{i}] ")).unwrap_or_default(),
+                first.id
+            ),
+        }
+    })?;
+
+    // Undo reorders first so restored blocks land next to their original neighbours.
+    let survivors: Vec<&str> =
+        original.blocks.iter().map(|b| b.id.as_str()).filter(|id| updated_ids.contains(id)).collect();
+    let current: Vec<&str> =
+        updated.blocks.iter().map(|b| b.id.as_str()).filter(|id| original_ids.contains(id)).collect();
+    let mut ops = reorder_ops(&current, &survivors);
+    for orig in &original.blocks {
+        let Some(cur) = updated.blocks.iter().find(|b| b.id == orig.id) else {
+            continue;
+        };
+        // `insert_before` keeps successive inserts in order ahead of the anchor.
+        for b in restores.before.get(orig.id.as_str()).into_iter().flatten() {
+            ops.push(PatchOpV1::insert_before(&orig.id, &b.id, b.kind_code, normalize_nfc(&b.text)));
+        }
+        if normalize_nfc(&cur.text) != normalize_nfc(&orig.text) {
+            let op = anchored_replace(&orig.id, &cur.text, &orig.text, opts.min_before_len).ok_or_else(|| {
+                InvertError::NotInvertible {
+                    op_index: None,
+                    block_id: orig.id.clone(),
+                    message: format!(
+                        "block '{}' is empty after the patch; a replace op cannot anchor on empty text",
+                        orig.id
+                    ),
+                }
+            })?;
+            ops.push(op);
+        }
+        if cur.kind_code != orig.kind_code {
+            ops.push(PatchOpV1::reclassify(&orig.id, orig.kind_code));
+        }
+        // `insert_after` places each block directly after the anchor, so restore in reverse.
+        if let Some(run) = restores.after.get(orig.id.as_str()) {
+            for b in run.iter().rev() {
+                ops.push(PatchOpV1::insert_after(&orig.id, &b.id, b.kind_code, normalize_nfc(&b.text)));
+            }
+        }
+    }
+
+    // Blocks added by the forward patch are removed again, bound to their current text.
+    for b in updated.blocks.iter().filter(|b| !original_ids.contains(b.id.as_str())) {
+        ops.push(PatchOpV1::delete_block(&b.id, &b.text_hash));
+    }
+
+    if ops.is_empty() {
+        return Err(InvertError::NoChanges);
+    }
+
+    let inverse = PatchV1 {
+        v: 1,
+        h: Some(updated.page_hash.clone()),
+        ha: Some(updated.hash_algorithm.clone()),
+        ops,
+    };
+
+    // Fail closed: the inverse must round-trip to the original text.
+    let check_opts = ValidateOptions { expected_page_hash: None, ..opts };
+    let restored = apply_patch_against_document_with_options(&updated, &inverse, check_opts).map_err(|e| {
+        let diag = e.diagnostics().diagnostics.into_iter().next();
+        InvertError::NotInvertible {
+            op_index: diag.as_ref().and_then(|d| d.op_index),
+            block_id: diag.and_then(|d| d.block_id).unwrap_or_default(),
+            message: format!("inverse patch does not apply to the updated document: {e}"),
+        }
+    })?;
+    let (want_shape, got_shape) = (block_shape(original), block_shape(&restored));
+    if let Some((got, want)) = parent_mismatch(&got_shape, &want_shape) {
+        return Err(InvertError::NotInvertible {
+            op_index: None,
+            block_id: want.0.to_string(),
+            message: format!(
+                "inverse patch cannot restore the parent of block '{}' (was {}, would be {})",
+                want.0,
+                describe_parent(want.2),
+                describe_parent(got.2)
+            ),
+        });
+    }
+    let shape = |d: &Document| -> Vec<(String, u16, Option<String>, String)> {
+        d.blocks.iter().map(|b| (b.id.clone(), b.kind_code, b.parent.clone(), normalize_nfc(&b.text))).collect()
+    };
+    let (want, got) = (shape(original), shape(&restored));
+    if want != got {
+        let block_id = want
+            .iter()
+            .zip(&got)
+            .find(|(w, g)| w != g)
+            .map(|(w, _)| w)
+            .or_else(|| want.get(got.len()).or(got.get(want.len())))
+            .map(|b| b.0.clone())
+            .unwrap_or_default();
+        return Err(InvertError::NotInvertible {
+            op_index: None,
+            message: format!("inverse patch does not restore block '{block_id}'"),
+            block_id,
+        });
+    }
+
+    Ok(inverse)
+}
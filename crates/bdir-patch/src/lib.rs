@@ -7,12 +7,19 @@ Do NOT depend on this crate directly.
 Use `bdir-io` instead.
 "#]
 
+mod anchor;
 pub mod apply;
+pub mod assign_ids;
 pub mod canonicalize;
 pub mod diagnostics;
+pub mod diff;
+pub mod invert;
+pub mod merge;
+pub mod rebase;
 pub mod schema;
 pub mod validate;
 pub mod telemetry;
+mod tolerant;
 
 pub use bdir_editpacket::schema::{EditPacketV1, BlockTupleV1};
 pub use apply::{
@@ -23,9 +30,27 @@ pub use apply::{
     apply_patch_against_edit_packet_with_telemetry,
     apply_patch_against_document_with_telemetry,
 };
-pub use diagnostics::{DiagnosticCode, ValidationDiagnostic, ValidationError};
+pub use assign_ids::{assign_new_block_ids, assign_new_block_ids_against_edit_packet};
+pub use diagnostics::{ApplyError, ClosestMatch, DiagnosticCode, ValidationDiagnostic, ValidationError};
+pub use diff::{diff_documents, diff_documents_with_options, DiffError};
+pub use invert::{invert_patch, invert_patch_with_options, InvertError};
+pub use merge::{
+    merge_patches,
+    merge_patches_with_options,
+    MergeConflict,
+    MergeConflictKind,
+    MergeOpRef,
+    MergeReport,
+};
+pub use rebase::{
+    rebase_patch,
+    rebase_patch_with_options,
+    RebaseConflict,
+    RebaseConflictReason,
+    RebaseReport,
+};
 pub use telemetry::PatchTelemetry;
-pub use schema::{OpType, PatchOpV1, PatchV1};
+pub use schema::{OpType, PatchOpV1, PatchV1, PatchV2, VersionedPatch, SUPPORTED_PATCH_VERSIONS};
 pub use canonicalize::{
     canonicalize_patch_ops,
     canonicalize_patch_ops_against_edit_packet,
@@ -33,6 +58,7 @@ pub use canonicalize::{
 };
 pub use validate::{
     KindCodePolicy,
+    MatchMode,
     ValidateOptions,
     validate_patch,
     validate_patch_with_options,
@@ -42,4 +68,6 @@ pub use validate::{
     validate_patch_against_edit_packet_with_diagnostics,
     validate_patch_with_telemetry,
     validate_patch_against_edit_packet_with_telemetry,
+    tolerant_match_diagnostics,
+    tolerant_match_diagnostics_against_edit_packet,
 };
@@ -0,0 +1,311 @@
+//! Merging patches produced independently against the same Edit Packet.
+//!
+//! Several models (or prompts) may propose patches for one packet. Merging keeps every
+//! op that does not conflict with an op from another patch, drops exact duplicates,
+//! and reports the rest so a reviewer (or a repair round) can resolve them.
+
+use bdir_core::hash::normalize_nfc;
+use bdir_editpacket::EditPacketV1;
+use serde::{Deserialize, Serialize};
+
+use crate::anchor::context_matches;
+use crate::canonicalize::canonicalize_patch_ops_against_edit_packet;
+use crate::schema::{DeleteOccurrence, Occurrence, OpType, PatchOpV1, PatchV1};
+use crate::validate::{validate_patch_against_edit_packet_with_diagnostics, ValidateOptions};
+
+/// Kind of merge conflict.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MergeConflictKind {
+    /// An input patch does not validate against the packet; none of its ops are merged.
+    InvalidPatch,
+    /// Two `replace`/`delete` ops touch overlapping `before` ranges in the same block.
+    OverlappingBefore,
+    /// Two ops target the exact same range but produce different text.
+    ContradictoryReplacement,
+    /// Two different `insert_after` / `insert_before` / `split_block` ops use the same `new_block_id`.
+    DuplicateNewBlockId,
+    /// One op removes a block (`delete_block`) that another op edits or inserts after.
+    DeletedBlockEdited,
+    /// Two different `move` ops relocate the same block.
+    ConflictingMove,
+    /// Two `reclassify` ops give the same block different kind codes.
+    ConflictingReclassify,
+    /// The merged op fails validation against the packet.
+    Invalid,
+}
+
+/// Reference to an op in the merge input.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub struct MergeOpRef {
+    /// Index into the input patch list.
+    pub patch_index: usize,
+    /// Index into that patch's `ops` (absent for whole-patch conflicts).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub op_index: Option<usize>,
+}
+
+/// A conflict between merge inputs.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MergeConflict {
+    pub kind: MergeConflictKind,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub block_id: Option<String>,
+    /// Ops involved, in input order.
+    pub ops: Vec<MergeOpRef>,
+    pub message: String,
+}
+
+/// Result of a merge: the combined patch plus every conflict found.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MergeReport {
+    /// Merged patch, bound to the packet and canonicalized against its block order.
+    /// Ops involved in any conflict are omitted.
+    pub patch: PatchV1,
+    /// Conflicts, ordered by the first op involved.
+    pub conflicts: Vec<MergeConflict>,
+}
+
+impl MergeReport {
+    pub fn is_clean(&self) -> bool {
+        self.conflicts.is_empty()
+    }
+}
+
+/// Merge patches against an Edit Packet with default validation options.
+pub fn merge_patches(packet: &EditPacketV1, patches: &[PatchV1]) -> MergeReport {
+    merge_patches_with_options(packet, patches, ValidateOptions::default())
+}
+
+/// Merge patches against an Edit Packet.
+///
+/// Each input patch is validated against `packet` first. Ops from valid patches are
+/// then compared pairwise within each block:
+/// - identical ops are merged into one
+/// - `replace`/`delete` ops whose matched `before` ranges overlap conflict
+///   (`ContradictoryReplacement` when the ranges are identical)
+/// - `insert_after` / `insert_before` / `split_block` ops that reuse a `new_block_id` conflict
+/// - a `split_block` anchor overlapping another op's `before` range conflicts like an edit
+/// - `delete_block` conflicts with any other mutating op on (or anchored at) the same block
+/// - two `move` ops that send the same block to different places conflict
+/// - two `reclassify` ops that give the same block different kind codes conflict
+/// - `suggest` ops never conflict
+///
+/// Ops involved in a conflict are left out of the merged patch entirely; no input
+/// "wins" silently.
+pub fn merge_patches_with_options(
+    packet: &EditPacketV1,
+    patches: &[PatchV1],
+    opts: ValidateOptions,
+) -> MergeReport {
+    let mut conflicts = Vec::new();
+    let mut entries: Vec<Entry> = Vec::new();
+
+    for (pi, patch) in patches.iter().enumerate() {
+        if let Err(e) = validate_patch_against_edit_packet_with_diagnostics(packet, patch, opts.clone()) {
+            conflicts.push(MergeConflict {
+                kind: MergeConflictKind::InvalidPatch,
+                block_id: None,
+                ops: vec![MergeOpRef { patch_index: pi, op_index: None }],
+                message: format!("patches[{pi}] is invalid: {}", e.legacy_message()),
+            });
+            continue;
+        }
+        for (oi, op) in patch.ops.iter().enumerate() {
+            let at = MergeOpRef { patch_index: pi, op_index: Some(oi) };
+            let ranges = match_ranges(packet, op);
+            entries.push(Entry { at, op: op.clone(), ranges, duplicate: false, conflicted: false });
+        }
+    }
+
+    for b in 0..entries.len() {
+        for a in 0..b {
+            if entries[a].duplicate || entries[b].duplicate {
+                continue;
+            }
+            if entries[a].op == entries[b].op {
+                entries[b].duplicate = true;
+                continue;
+            }
+            if let Some((kind, block_id, message)) = conflict_between(&entries[a], &entries[b]) {
+                entries[a].conflicted = true;
+                entries[b].conflicted = true;
+                conflicts.push(MergeConflict {
+                    kind,
+                    block_id: Some(block_id),
+                    ops: vec![entries[a].at, entries[b].at],
+                    message,
+                });
+            }
+        }
+    }
+
+    let mut kept: Vec<&Entry> = entries.iter().filter(|e| !e.duplicate && !e.conflicted).collect();
+
+    // Emitting v1 is lossless while patch v2 only narrows the v1 wire format (no new ops or
+    // fields), so every op from a v2 input is also a valid v1 op.
+    let mut merged = PatchV1 {
+        v: 1,
+        h: Some(packet.h.clone()),
+        ha: Some(packet.ha.clone()),
+        ops: kept.iter().map(|e| e.op.clone()).collect(),
+    };
+
+    // Ops that are individually valid can still fail once combined (e.g. an inserted
+    // block id reused by another patch's anchor). Report them instead of emitting an
+    // invalid patch.
+    if !merged.ops.is_empty() {
+        let check_opts = ValidateOptions { collect_all_diagnostics: true, ..opts };
+        if let Err(e) = validate_patch_against_edit_packet_with_diagnostics(packet, &merged, check_opts) {
+            let mut invalid = vec![false; kept.len()];
+            for d in &e.diagnostics {
+                let Some(j) = d.op_index else { continue };
+                if !invalid[j] {
+                    invalid[j] = true;
+                    conflicts.push(MergeConflict {
+                        kind: MergeConflictKind::Invalid,
+                        block_id: Some(kept[j].op.block_id.clone()),
+                        ops: vec![kept[j].at],
+                        message: format!("merged op fails validation: {}", d.message),
+                    });
+                }
+            }
+            let mut flags = invalid.into_iter();
+            kept.retain(|_| !flags.next().unwrap_or(false));
+            merged.ops = kept.iter().map(|e| e.op.clone()).collect();
+        }
+    }
+
+    canonicalize_patch_ops_against_edit_packet(packet, &mut merged);
+    conflicts.sort_by(|a, b| a.ops.cmp(&b.ops));
+
+    MergeReport { patch: merged, conflicts }
+}
+
+struct Entry {
+    at: MergeOpRef,
+    op: PatchOpV1,
+    /// Byte ranges (in the NFC block text) touched by `replace`/`delete`.
+    ranges: Vec<(usize, usize)>,
+    duplicate: bool,
+    conflicted: bool,
+}
+
+fn conflict_between(a: &Entry, b: &Entry) -> Option<(MergeConflictKind, String, String)> {
+    let ra = describe(a.at);
+    let rb = describe(b.at);
+
+    let inserts = |op: OpType| matches!(op, OpType::InsertAfter | OpType::InsertBefore | OpType::SplitBlock);
+    if inserts(a.op.op)
+        && inserts(b.op.op)
+        && a.op.new_block_id.is_some()
+        && a.op.new_block_id == b.op.new_block_id
+    {
+        let id = a.op.new_block_id.clone().unwrap_or_default();
+        return Some((
+            MergeConflictKind::DuplicateNewBlockId,
+            a.op.block_id.clone(),
+            format!("{ra} and {rb} both insert new_block_id '{id}' with different content"),
+        ));
+    }
+
+    if a.op.block_id == b.op.block_id && a.op.op == OpType::Move && b.op.op == OpType::Move {
+        return Some((
+            MergeConflictKind::ConflictingMove,
+            a.op.block_id.clone(),
+            format!("{ra} and {rb} move block '{}' to different places", a.op.block_id),
+        ));
+    }
+
+    if a.op.block_id == b.op.block_id && a.op.op == OpType::Reclassify && b.op.op == OpType::Reclassify {
+        return Some((
+            MergeConflictKind::ConflictingReclassify,
+            a.op.block_id.clone(),
+            format!(
+                "{ra} and {rb} reclassify block '{}' differently (kind_code {} vs {})",
+                a.op.block_id,
+                a.op.kind_code.unwrap_or_default(),
+                b.op.kind_code.unwrap_or_default()
+            ),
+        ));
+    }
+
+    if a.op.block_id == b.op.block_id
+        && (a.op.op == OpType::DeleteBlock) != (b.op.op == OpType::DeleteBlock)
+        && a.op.op != OpType::Suggest
+        && b.op.op != OpType::Suggest
+    {
+        let (del, other) = if a.op.op == OpType::DeleteBlock { (&ra, &rb) } else { (&rb, &ra) };
+        return Some((
+            MergeConflictKind::DeletedBlockEdited,
+            a.op.block_id.clone(),
+            format!("{del} deletes block '{}', which {other} also changes", a.op.block_id),
+        ));
+    }
+
+    if a.op.block_id != b.op.block_id || a.ranges.is_empty() || b.ranges.is_empty() {
+        return None;
+    }
+
+    let overlaps = a
+        .ranges
+        .iter()
+        .any(|&(s1, e1)| b.ranges.iter().any(|&(s2, e2)| s1 < e2 && s2 < e1));
+    if !overlaps {
+        return None;
+    }
+
+    let block_id = a.op.block_id.clone();
+    if a.ranges == b.ranges {
+        Some((
+            MergeConflictKind::ContradictoryReplacement,
+            block_id.clone(),
+            format!("{ra} and {rb} rewrite the same text in block '{block_id}' differently"),
+        ))
+    } else {
+        Some((
+            MergeConflictKind::OverlappingBefore,
+            block_id.clone(),
+            format!("{ra} and {rb} edit overlapping ranges in block '{block_id}'"),
+        ))
+    }
+}
+
+fn describe(at: MergeOpRef) -> String {
+    match at.op_index {
+        Some(oi) => format!("patches[{}].ops[{oi}]", at.patch_index),
+        None => format!("patches[{}]", at.patch_index),
+    }
+}
+
+/// Byte ranges of the `before` matches a `replace`/`delete`/`split_block` op will touch.
+fn match_ranges(packet: &EditPacketV1, op: &PatchOpV1) -> Vec<(usize, usize)> {
+    if !matches!(op.op, OpType::Replace | OpType::Delete | OpType::SplitBlock) {
+        return Vec::new();
+    }
+    let Some(block) = packet.b.iter().find(|t| t.0 == op.block_id) else {
+        return Vec::new();
+    };
+    let text = normalize_nfc(&block.3);
+    let before = normalize_nfc(op.before.as_deref().unwrap_or_default());
+    if before.is_empty() {
+        return Vec::new();
+    }
+
+    let all: Vec<(usize, usize)> = text
+        .match_indices(before.as_str())
+        .map(|(s, m)| (s, s + m.len()))
+        .collect();
+
+    if op.has_context() {
+        return context_matches(&text, &before, op.context_before.as_deref(), op.context_after.as_deref())
+            .into_iter()
+            .filter_map(|n| all.get(n - 1).copied())
+            .collect();
+    }
+    match op.occurrence {
+        Some(Occurrence::Legacy(DeleteOccurrence::All)) => all,
+        Some(Occurrence::Index(n)) => all.get((n as usize).saturating_sub(1)).copied().into_iter().collect(),
+        Some(Occurrence::Legacy(DeleteOccurrence::First)) | None => all.first().copied().into_iter().collect(),
+    }
+}
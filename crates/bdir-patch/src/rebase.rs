@@ -0,0 +1,275 @@
+//! Three-way patch rebase.
+//!
+//! Patches are bound to a page hash, so any upstream edit invalidates an in-flight
+//! patch. Rebasing carries a patch authored against a *base* document over to a *new*
+//! revision of that document: ops whose anchors survived are kept, the rest are
+//! reported as conflicts, and the result is bound to the new page hash.
+
+use bdir_core::hash::normalize_nfc;
+use bdir_core::model::{Block, Document};
+use serde::{Deserialize, Serialize};
+
+use crate::anchor::{context_matches, count_non_overlapping};
+use crate::diagnostics::ValidationError;
+use crate::schema::{DeleteOccurrence, Occurrence, OpType, PatchOpV1, PatchV1};
+use crate::validate::{validate_patch_with_diagnostics, ValidateOptions};
+
+/// Why an op could not be carried over to the new document.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RebaseConflictReason {
+    /// The target block (or a `move` anchor / `merge_block` neighbour) no longer exists.
+    BlockRemoved,
+    /// The target block changed and `before` (with its context anchors) no longer occurs in it.
+    BeforeNotFound,
+    /// The target block changed and `before` (with its context anchors) now occurs more than once.
+    BeforeAmbiguous,
+    /// The target block changed and the number of `before` matches moved, so `occurrence` is stale.
+    OccurrenceShifted,
+    /// The inserted block's `new_block_id` now collides with an existing block.
+    NewBlockIdTaken,
+    /// `delete_block` targets a block whose text changed (its `text_hash` is stale), or
+    /// `reclassify` targets a block whose `kind_code` changed.
+    BlockChanged,
+    /// The re-anchored op fails validation against the new document.
+    Invalid,
+}
+
+/// A single op that could not be rebased.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RebaseConflict {
+    /// Index of the op in the input patch.
+    pub op_index: usize,
+    pub op: OpType,
+    pub block_id: String,
+    pub reason: RebaseConflictReason,
+    pub message: String,
+}
+
+/// Result of a rebase: the carried-over patch plus every op that conflicted.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RebaseReport {
+    /// Rebased patch bound (`h`/`ha`) to the new document. Conflicting ops are omitted.
+    pub patch: PatchV1,
+    /// Conflicting ops, in input `ops` order.
+    pub conflicts: Vec<RebaseConflict>,
+}
+
+impl RebaseReport {
+    pub fn is_clean(&self) -> bool {
+        self.conflicts.is_empty()
+    }
+}
+
+/// Rebase `patch` (bound to `base`) onto `new` with default validation options.
+pub fn rebase_patch(base: &Document, new: &Document, patch: &PatchV1) -> Result<RebaseReport, ValidationError> {
+    rebase_patch_with_options(base, new, patch, ValidateOptions::default())
+}
+
+/// Rebase `patch` (bound to `base`) onto `new`.
+///
+/// The patch must validate against `base`; otherwise its diagnostics are returned.
+///
+/// Per op:
+/// - the target block must still exist in `new`
+/// - if the block text is unchanged (NFC), the op is kept as-is
+/// - otherwise `replace`/`delete` are kept only when `before` still matches uniquely
+///   (or, with `occurrence`, when the match count is unchanged)
+/// - `insert_after` is kept when its `new_block_id` is still free
+/// - `suggest` is kept whenever its block exists
+///
+/// Kept ops are finally validated against `new`; any that fail are reported as
+/// `Invalid` conflicts rather than silently dropped.
+pub fn rebase_patch_with_options(
+    base: &Document,
+    new: &Document,
+    patch: &PatchV1,
+    opts: ValidateOptions,
+) -> Result<RebaseReport, ValidationError> {
+    validate_patch_with_diagnostics(base, patch, opts.clone())?;
+
+    let mut kept: Vec<(usize, PatchOpV1)> = Vec::new();
+    let mut conflicts = Vec::new();
+
+    for (i, op) in patch.ops.iter().enumerate() {
+        match rebase_op(base, new, op) {
+            Ok(()) => kept.push((i, op.clone())),
+            Err((reason, message)) => conflicts.push(RebaseConflict {
+                op_index: i,
+                op: op.op,
+                block_id: op.block_id.clone(),
+                reason,
+                message: format!("ops[{i}] {message}"),
+            }),
+        }
+    }
+
+    let mut rebased = PatchV1 {
+        v: patch.v,
+        h: Some(new.page_hash.clone()),
+        ha: Some(new.hash_algorithm.clone()),
+        ops: kept.iter().map(|(_, op)| op.clone()).collect(),
+    };
+
+    if !rebased.ops.is_empty() {
+        let check_opts = ValidateOptions {
+            expected_page_hash: None,
+            collect_all_diagnostics: true,
+            ..opts
+        };
+        if let Err(e) = validate_patch_with_diagnostics(new, &rebased, check_opts) {
+            let mut invalid = vec![false; kept.len()];
+            for d in &e.diagnostics {
+                // Page-level failures are not attributable to a single op.
+                let Some(j) = d.op_index else {
+                    return Err(e);
+                };
+                if !invalid[j] {
+                    invalid[j] = true;
+                    let (i, op) = &kept[j];
+                    conflicts.push(RebaseConflict {
+                        op_index: *i,
+                        op: op.op,
+                        block_id: op.block_id.clone(),
+                        reason: RebaseConflictReason::Invalid,
+                        message: format!("ops[{i}] fails validation against the new document: {}", d.message),
+                    });
+                }
+            }
+            rebased.ops = kept
+                .into_iter()
+                .zip(invalid)
+                .filter(|(_, bad)| !bad)
+                .map(|((_, op), _)| op)
+                .collect();
+            conflicts.sort_by_key(|c| c.op_index);
+        }
+    }
+
+    Ok(RebaseReport { patch: rebased, conflicts })
+}
+
+fn find_block<'a>(doc: &'a Document, id: &str) -> Option<&'a Block> {
+    doc.blocks.iter().find(|b| b.id == id)
+}
+
+fn rebase_op(base: &Document, new: &Document, op: &PatchOpV1) -> Result<(), (RebaseConflictReason, String)> {
+    let Some(new_block) = find_block(new, &op.block_id) else {
+        return Err((
+            RebaseConflictReason::BlockRemoved,
+            format!("targets block '{}', which no longer exists", op.block_id),
+        ));
+    };
+    let base_block = find_block(base, &op.block_id).expect("validated against base");
+
+    if matches!(op.op, OpType::InsertAfter | OpType::InsertBefore | OpType::SplitBlock)
+        && let Some(new_block_id) = op.new_block_id.as_deref()
+        && find_block(new, new_block_id).is_some()
+    {
+        return Err((
+            RebaseConflictReason::NewBlockIdTaken,
+            format!("({}) new_block_id '{new_block_id}' now exists in the new document", op.op.as_str()),
+        ));
+    }
+
+    for (field, other) in [("after_block_id", &op.after_block_id), ("next_block_id", &op.next_block_id)] {
+        if let Some(other) = other.as_deref()
+            && find_block(new, other).is_none()
+        {
+            return Err((
+                RebaseConflictReason::BlockRemoved,
+                format!("({}) {field} '{other}' no longer exists", op.op.as_str()),
+            ));
+        }
+    }
+
+    if op.op == OpType::Reclassify && base_block.kind_code != new_block.kind_code {
+        return Err((
+            RebaseConflictReason::BlockChanged,
+            format!(
+                "(reclassify) kind_code of block '{}' changed since the base revision ({} -> {})",
+                op.block_id, base_block.kind_code, new_block.kind_code
+            ),
+        ));
+    }
+
+    let unchanged = normalize_nfc(&base_block.text) == normalize_nfc(&new_block.text);
+    if op.op == OpType::DeleteBlock && !unchanged {
+        return Err((
+            RebaseConflictReason::BlockChanged,
+            format!("(delete_block) block '{}' changed since the base revision", op.block_id),
+        ));
+    }
+    if unchanged || !matches!(op.op, OpType::Replace | OpType::Delete | OpType::SplitBlock) {
+        return Ok(());
+    }
+
+    let before = normalize_nfc(op.before.as_deref().unwrap_or_default());
+    let base_matches = count_non_overlapping(&normalize_nfc(&base_block.text), &before);
+    let new_matches = count_non_overlapping(&normalize_nfc(&new_block.text), &before);
+
+    if new_matches == 0 {
+        return Err((
+            RebaseConflictReason::BeforeNotFound,
+            format!("before substring no longer occurs in changed block '{}'", op.block_id),
+        ));
+    }
+    // Context anchors re-select their match in the new text, so shifted counts are fine.
+    if op.has_context() {
+        let selected = context_matches(
+            &new_block.text,
+            &before,
+            op.context_before.as_deref(),
+            op.context_after.as_deref(),
+        );
+        return match selected.len() {
+            1 => Ok(()),
+            0 => Err((
+                RebaseConflictReason::BeforeNotFound,
+                format!("before substring no longer occurs with its context in changed block '{}'", op.block_id),
+            )),
+            n => Err((
+                RebaseConflictReason::BeforeAmbiguous,
+                format!("context is ambiguous in changed block '{}' ({n} matches of before have it)", op.block_id),
+            )),
+        };
+    }
+    match op.occurrence {
+        Some(Occurrence::Legacy(DeleteOccurrence::All)) => Ok(()),
+        Some(Occurrence::Index(_)) if new_matches != base_matches => Err((
+            RebaseConflictReason::OccurrenceShifted,
+            format!(
+                "before substring matches {new_matches} times in changed block '{}' (was {base_matches}); occurrence is stale",
+                op.block_id
+            ),
+        )),
+        // Same count, but matches can appear ahead of the target while others vanish
+        // after it: keep the index only if it still selects the same text position.
+        Some(Occurrence::Index(n))
+            if new_matches > 1 && nth_match(&base_block.text, &before, n) != nth_match(&new_block.text, &before, n) =>
+        {
+            Err((
+                RebaseConflictReason::OccurrenceShifted,
+                format!(
+                    "occurrence {n} of before substring moved in changed block '{}'; occurrence is stale",
+                    op.block_id
+                ),
+            ))
+        }
+        Some(Occurrence::Index(_)) => Ok(()),
+        Some(Occurrence::Legacy(DeleteOccurrence::First)) | None if new_matches > 1 => Err((
+            RebaseConflictReason::BeforeAmbiguous,
+            format!(
+                "before substring is ambiguous in changed block '{}' (matches {new_matches} times)",
+                op.block_id
+            ),
+        )),
+        _ => Ok(()),
+    }
+}
+
+/// Byte offset (in NFC) of the `n`th (1-based) non-overlapping match of `needle` in `text`.
+fn nth_match(text: &str, needle: &str, n: u32) -> Option<usize> {
+    let n = usize::try_from(n).ok()?.checked_sub(1)?;
+    normalize_nfc(text).match_indices(needle).nth(n).map(|(pos, _)| pos)
+}
@@ -1,6 +1,14 @@
-use serde::{Deserialize, Serialize};
+use serde::{Deserialize, Deserializer, Serialize};
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+/// Patch wire versions this engine understands.
+pub const SUPPORTED_PATCH_VERSIONS: &[u8] = &[1, 2];
+
+/// Patch in RFC-0001 v1 wire format.
+///
+/// This is also the engine's in-memory patch model: validation, application and the
+/// patch tools all operate on it. `v` is carried through, so a v2 patch converted with
+/// `VersionedPatch::into_patch` keeps `v: 2`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct PatchV1 {
     pub v: u8,
 
@@ -23,13 +31,181 @@ pub struct PatchV1 {
     pub ops: Vec<PatchOpV1>,
 }
 
+/// Patch in v2 wire format (`spec/schemas/patch.v2.schema.json`).
+///
+/// v2 carries the same operation set as v1 with canonical spellings only: the
+/// camelCase and `content` aliases and `occurrence: "first"` (use `1`) that v1 accepts
+/// are rejected when deserializing. Every v1 patch can be upgraded losslessly with
+/// `PatchV2::from`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct PatchV2 {
+    pub v: u8,
+
+    /// Page-level hash binding (same semantics as v1).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub h: Option<String>,
+
+    /// Hash algorithm identifier for `h` (same semantics as v1).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ha: Option<String>,
+
+    pub ops: Vec<PatchOpV1>,
+}
+
+impl From<PatchV1> for PatchV2 {
+    /// Lossless upgrade: the binding and ops are kept; `occurrence: "first"` becomes
+    /// `1`, which selects the same match.
+    fn from(patch: PatchV1) -> Self {
+        let ops = patch
+            .ops
+            .into_iter()
+            .map(|op| match op.occurrence {
+                Some(Occurrence::Legacy(DeleteOccurrence::First)) => {
+                    PatchOpV1 { occurrence: Some(Occurrence::Index(1)), ..op }
+                }
+                _ => op,
+            })
+            .collect();
+        Self { v: 2, h: patch.h, ha: patch.ha, ops }
+    }
+}
+
+/// Op field spellings `PatchOpV1` accepts for v1 compatibility, with their canonical
+/// names. Patch v2 rejects them.
+const V1_ONLY_OP_FIELDS: &[(&str, &str)] = &[
+    ("blockId", "block_id"),
+    ("contextBefore", "context_before"),
+    ("contextAfter", "context_after"),
+    ("newBlockId", "new_block_id"),
+    ("kindCode", "kind_code"),
+    ("content", "text"),
+    ("textHash", "text_hash"),
+    ("afterBlockId", "after_block_id"),
+    ("nextBlockId", "next_block_id"),
+];
+
+impl<'de> Deserialize<'de> for PatchV2 {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        use serde::de::Error;
+
+        #[derive(Deserialize)]
+        struct Wire {
+            v: u8,
+            h: Option<String>,
+            ha: Option<String>,
+            ops: Vec<PatchOpV1>,
+        }
+
+        let value = serde_json::Value::deserialize(deserializer)?;
+        let ops = value.get("ops").and_then(serde_json::Value::as_array).map_or(&[][..], Vec::as_slice);
+        for (i, op) in ops.iter().enumerate() {
+            if let Some((alias, canonical)) = V1_ONLY_OP_FIELDS.iter().find(|(alias, _)| op.get(alias).is_some()) {
+                return Err(D::Error::custom(format!(
+                    "ops[{i}]: `{alias}` is not valid in patch v2; use `{canonical}`"
+                )));
+            }
+        }
+
+        let wire: Wire = serde_json::from_value(value).map_err(D::Error::custom)?;
+        if wire.v != 2 {
+            return Err(D::Error::custom(format!("patch v2 requires `v` = 2, got {}", wire.v)));
+        }
+        if let Some(i) = wire
+            .ops
+            .iter()
+            .position(|op| op.occurrence == Some(Occurrence::Legacy(DeleteOccurrence::First)))
+        {
+            return Err(D::Error::custom(format!(
+                "ops[{i}]: occurrence \"first\" is not valid in patch v2; use 1"
+            )));
+        }
+        Ok(PatchV2 { v: wire.v, h: wire.h, ha: wire.ha, ops: wire.ops })
+    }
+}
+
+/// A patch in any supported wire version, dispatched on `v` when deserializing.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(untagged)]
+pub enum VersionedPatch {
+    V1(PatchV1),
+    V2(PatchV2),
+}
+
+impl VersionedPatch {
+    /// Wire version (`v`) of the patch.
+    pub fn version(&self) -> u8 {
+        match self {
+            VersionedPatch::V1(p) => p.v,
+            VersionedPatch::V2(p) => p.v,
+        }
+    }
+
+    /// Upgrade to the latest wire version (lossless).
+    pub fn upgrade(self) -> PatchV2 {
+        match self {
+            VersionedPatch::V1(p) => p.into(),
+            VersionedPatch::V2(p) => p,
+        }
+    }
+
+    /// The engine's patch model for validation and application; `v` is preserved.
+    pub fn into_patch(self) -> PatchV1 {
+        match self {
+            VersionedPatch::V1(p) => p,
+            VersionedPatch::V2(p) => PatchV1 { v: p.v, h: p.h, ha: p.ha, ops: p.ops },
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for VersionedPatch {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        use serde::de::Error;
+
+        let value = serde_json::Value::deserialize(deserializer)?;
+        let v = value
+            .get("v")
+            .ok_or_else(|| D::Error::missing_field("v"))?
+            .as_u64()
+            .ok_or_else(|| D::Error::custom("patch version `v` must be an unsigned integer"))?;
+        match v {
+            1 => serde_json::from_value(value).map(VersionedPatch::V1).map_err(D::Error::custom),
+            2 => serde_json::from_value(value).map(VersionedPatch::V2).map_err(D::Error::custom),
+            other => Err(D::Error::custom(format!("unsupported patch version {other}"))),
+        }
+    }
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(rename_all = "snake_case")]
 pub enum OpType {
     Replace,
     Delete,
     InsertAfter,
+    InsertBefore,
     Suggest,
+    DeleteBlock,
+    Move,
+    Reclassify,
+    SplitBlock,
+    MergeBlock,
+}
+
+impl OpType {
+    /// Wire name of the op (`"insert_after"`, `"delete_block"`, ...).
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            OpType::Replace => "replace",
+            OpType::Delete => "delete",
+            OpType::InsertAfter => "insert_after",
+            OpType::InsertBefore => "insert_before",
+            OpType::Suggest => "suggest",
+            OpType::DeleteBlock => "delete_block",
+            OpType::Move => "move",
+            OpType::Reclassify => "reclassify",
+            OpType::SplitBlock => "split_block",
+            OpType::MergeBlock => "merge_block",
+        }
+    }
 }
 
 /// Explicit delete semantics.
@@ -68,7 +244,7 @@ pub enum Occurrence {
 /// - Canonical JSON field is `block_id` (snake_case).
 /// - For backwards compatibility, `blockId` (camelCase) is accepted on input.
 ///
-/// `insert_after` (RFC-0001 v1.0.2) fields:
+/// `insert_after` (RFC-0001 v1.0.2) and `insert_before` fields:
 /// - `new_block_id` (REQUIRED)
 /// - `kind_code` (REQUIRED)
 /// - `text` (REQUIRED)
@@ -77,7 +253,34 @@ pub enum Occurrence {
 /// - Older engine versions used `content` instead of `text` and auto-derived
 ///   `new_block_id`/`kind_code`. Those spellings are accepted on input but are
 ///   rejected by validation unless the required RFC fields are present.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+///
+/// `replace` / `delete` context anchors:
+/// - `context_before` / `context_after` (OPTIONAL): text immediately around the
+///   `before` match; together they must select exactly one match. An alternative
+///   to `occurrence`, which they cannot be combined with.
+///
+/// `delete_block` fields:
+/// - `text_hash` (REQUIRED): hash of the block's current text; the op is rejected
+///   if the block changed since the patch was authored.
+///
+/// `move` fields:
+/// - `after_block_id` (REQUIRED): the block is relocated directly after this anchor,
+///   keeping its id, kind_code and text.
+///
+/// `reclassify` fields:
+/// - `kind_code` (REQUIRED): new kind classification for the block; its id and text
+///   are unchanged.
+///
+/// `split_block` fields:
+/// - `before` (REQUIRED): unique anchor in the block; the text from the anchor onward
+///   moves into a new block placed directly after it
+/// - `new_block_id` (REQUIRED): identifier for the new block
+/// - `kind_code` (OPTIONAL): kind for the new block; defaults to the split block's kind
+///
+/// `merge_block` fields:
+/// - `next_block_id` (REQUIRED): the block directly after `block_id`; its text is
+///   appended to `block_id` (separated by one space) and the block is removed.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct PatchOpV1 {
     pub op: OpType,
 
@@ -96,20 +299,46 @@ pub struct PatchOpV1 {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub occurrence: Option<Occurrence>,
 
-    /// `insert_after` only: identifier for the inserted block.
+    /// `replace` / `delete` only: text that must end immediately before the `before` match.
+    ///
+    /// Context anchors select a single match without counting occurrences; they are
+    /// mutually exclusive with `occurrence`.
+    #[serde(skip_serializing_if = "Option::is_none", rename = "context_before", alias = "contextBefore")]
+    pub context_before: Option<String>,
+
+    /// `replace` / `delete` only: text that must start immediately after the `before` match.
+    #[serde(skip_serializing_if = "Option::is_none", rename = "context_after", alias = "contextAfter")]
+    pub context_after: Option<String>,
+
+    /// `insert_after` / `insert_before` / `split_block`: identifier for the new block.
     #[serde(skip_serializing_if = "Option::is_none", rename = "new_block_id", alias = "newBlockId")]
     pub new_block_id: Option<String>,
 
-    /// `insert_after` only: kind classification for the inserted block.
+    /// `insert_after` / `insert_before` / `split_block`: kind classification for the new block.
+    /// `reclassify`: new kind classification for the target block.
     #[serde(skip_serializing_if = "Option::is_none", rename = "kind_code", alias = "kindCode")]
     pub kind_code: Option<u16>,
 
-    /// `insert_after` only: canonical text for the inserted block.
+    /// `insert_after` / `insert_before` only: canonical text for the inserted block.
     ///
     /// Backwards compatibility: accepts legacy `content` field as an alias.
     #[serde(skip_serializing_if = "Option::is_none", alias = "content")]
     pub text: Option<String>,
 
+    /// `delete_block` only: expected hash of the block's current text.
+    ///
+    /// May be a truncated prefix of at least 8 hex chars (RFC-0001 §5.1.4).
+    #[serde(skip_serializing_if = "Option::is_none", rename = "text_hash", alias = "textHash")]
+    pub text_hash: Option<String>,
+
+    /// `move` only: anchor block the moved block is placed after.
+    #[serde(skip_serializing_if = "Option::is_none", rename = "after_block_id", alias = "afterBlockId")]
+    pub after_block_id: Option<String>,
+
+    /// `merge_block` only: the following block that is joined into `block_id`.
+    #[serde(skip_serializing_if = "Option::is_none", rename = "next_block_id", alias = "nextBlockId")]
+    pub next_block_id: Option<String>,
+
     /// `suggest` only: advisory message.
     #[serde(skip_serializing_if = "Option::is_none")]
     pub message: Option<String>,
@@ -118,3 +347,133 @@ pub struct PatchOpV1 {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub severity: Option<String>,
 }
+
+impl PatchOpV1 {
+    /// Build a `replace` op with no optional fields set.
+    pub fn replace(block_id: impl Into<String>, before: impl Into<String>, after: impl Into<String>) -> Self {
+        Self {
+            op: OpType::Replace,
+            block_id: block_id.into(),
+            before: Some(before.into()),
+            after: Some(after.into()),
+            occurrence: None,
+            context_before: None,
+            context_after: None,
+            new_block_id: None,
+            kind_code: None,
+            text: None,
+            text_hash: None,
+            after_block_id: None,
+            next_block_id: None,
+            message: None,
+            severity: None,
+        }
+    }
+
+    /// Build a `delete` op (first occurrence) with no optional fields set.
+    pub fn delete(block_id: impl Into<String>, before: impl Into<String>) -> Self {
+        Self {
+            op: OpType::Delete,
+            before: Some(before.into()),
+            after: None,
+            ..Self::replace(block_id, "", "")
+        }
+    }
+
+    /// Build an `insert_after` op that adds block `new_block_id` after `block_id`.
+    pub fn insert_after(
+        block_id: impl Into<String>,
+        new_block_id: impl Into<String>,
+        kind_code: u16,
+        text: impl Into<String>,
+    ) -> Self {
+        Self {
+            op: OpType::InsertAfter,
+            before: None,
+            after: None,
+            new_block_id: Some(new_block_id.into()),
+            kind_code: Some(kind_code),
+            text: Some(text.into()),
+            ..Self::replace(block_id, "", "")
+        }
+    }
+
+    /// Build an `insert_before` op that adds block `new_block_id` before `block_id`.
+    ///
+    /// Anchoring on the first block inserts at the start of the document.
+    pub fn insert_before(
+        block_id: impl Into<String>,
+        new_block_id: impl Into<String>,
+        kind_code: u16,
+        text: impl Into<String>,
+    ) -> Self {
+        Self {
+            op: OpType::InsertBefore,
+            ..Self::insert_after(block_id, new_block_id, kind_code, text)
+        }
+    }
+
+    /// Build a `delete_block` op that removes block `block_id` if its text still hashes to `text_hash`.
+    pub fn delete_block(block_id: impl Into<String>, text_hash: impl Into<String>) -> Self {
+        Self {
+            op: OpType::DeleteBlock,
+            before: None,
+            after: None,
+            text_hash: Some(text_hash.into()),
+            ..Self::replace(block_id, "", "")
+        }
+    }
+
+    /// Build a `move` op that relocates block `block_id` directly after `after_block_id`.
+    pub fn move_after(block_id: impl Into<String>, after_block_id: impl Into<String>) -> Self {
+        Self {
+            op: OpType::Move,
+            before: None,
+            after: None,
+            after_block_id: Some(after_block_id.into()),
+            ..Self::replace(block_id, "", "")
+        }
+    }
+
+    /// Build a `reclassify` op that changes the `kind_code` of block `block_id`.
+    pub fn reclassify(block_id: impl Into<String>, kind_code: u16) -> Self {
+        Self {
+            op: OpType::Reclassify,
+            before: None,
+            after: None,
+            kind_code: Some(kind_code),
+            ..Self::replace(block_id, "", "")
+        }
+    }
+
+    /// Build a `split_block` op that moves the text of `block_id` from `before` onward
+    /// into a new block `new_block_id` (same kind) placed directly after it.
+    pub fn split_block(
+        block_id: impl Into<String>,
+        before: impl Into<String>,
+        new_block_id: impl Into<String>,
+    ) -> Self {
+        Self {
+            op: OpType::SplitBlock,
+            after: None,
+            new_block_id: Some(new_block_id.into()),
+            ..Self::replace(block_id, before, "")
+        }
+    }
+
+    /// Build a `merge_block` op that joins `next_block_id` into `block_id`.
+    pub fn merge_block(block_id: impl Into<String>, next_block_id: impl Into<String>) -> Self {
+        Self {
+            op: OpType::MergeBlock,
+            before: None,
+            after: None,
+            next_block_id: Some(next_block_id.into()),
+            ..Self::replace(block_id, "", "")
+        }
+    }
+
+    /// Whether the op carries a `context_before` or `context_after` anchor.
+    pub fn has_context(&self) -> bool {
+        self.context_before.is_some() || self.context_after.is_some()
+    }
+}
@@ -1,3 +1,4 @@
+use crate::diagnostics::ValidationDiagnostic;
 use crate::schema::OpType;
 use serde::{Deserialize, Serialize};
 use std::collections::BTreeMap;
@@ -56,6 +57,10 @@ pub struct PatchTelemetry {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub output_chars: Option<usize>,
 
+    /// Indices of ops whose `before` only matched under tolerant matching.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tolerant_match_ops: Vec<usize>,
+
     /// Optional machine-readable error code (when failed).
     #[serde(skip_serializing_if = "Option::is_none")]
     pub error_code: Option<String>,
@@ -76,6 +81,11 @@ impl PatchTelemetry {
         ranges.iter().map(|(lo, hi)| format!("{lo}-{hi}")).collect()
     }
 
+    /// Op indices of `tolerant_match` diagnostics, for `tolerant_match_ops`.
+    pub fn tolerant_ops(diagnostics: &[ValidationDiagnostic]) -> Vec<usize> {
+        diagnostics.iter().filter_map(|d| d.op_index).collect()
+    }
+
     pub fn op_type_key(op: OpType) -> String {
         format!("{:?}", op).to_lowercase()
     }
@@ -0,0 +1,137 @@
+//! Opt-in whitespace and typography tolerant matching (`MatchMode::Tolerant`).
+//!
+//! Exact matching (RFC-0001 §8.3) is always tried first. Only when `before` does not
+//! occur verbatim are both texts folded: whitespace runs (including NBSP) become one
+//! space, smart quotes become ASCII quotes and dashes become `-`. Matches found in the
+//! folded text are mapped back to byte ranges of the original NFC text, so edits land
+//! on the real characters.
+
+use bdir_core::hash::normalize_nfc;
+
+use crate::anchor::{context_matches, count_non_overlapping};
+use crate::schema::{DeleteOccurrence, Occurrence, PatchOpV1};
+
+/// Folded text plus, per folded byte, the original byte range it came from.
+struct Folded {
+    text: String,
+    start_of: Vec<usize>,
+    end_of: Vec<usize>,
+}
+
+impl Folded {
+    /// Original byte range covered by the folded byte range `start..end` (non-empty).
+    fn original_range(&self, start: usize, end: usize) -> (usize, usize) {
+        (self.start_of[start], self.end_of[end - 1])
+    }
+}
+
+/// Typographic equivalent of `c`, or `None` when `c` is whitespace.
+fn fold_char(c: char) -> Option<char> {
+    match c {
+        c if c.is_whitespace() => None,
+        '\u{2018}' | '\u{2019}' | '\u{201A}' | '\u{201B}' | '\u{2032}' => Some('\''),
+        '\u{201C}' | '\u{201D}' | '\u{201E}' | '\u{201F}' | '\u{2033}' | '\u{00AB}' | '\u{00BB}' => Some('"'),
+        '\u{2010}' | '\u{2011}' | '\u{2012}' | '\u{2013}' | '\u{2014}' | '\u{2015}' | '\u{2212}' => Some('-'),
+        c => Some(c),
+    }
+}
+
+/// Fold NFC `text`, keeping the mapping back to its bytes.
+fn fold_mapped(text: &str) -> Folded {
+    let mut folded = Folded { text: String::with_capacity(text.len()), start_of: Vec::new(), end_of: Vec::new() };
+    let mut chars = text.char_indices().peekable();
+    while let Some((start, c)) = chars.next() {
+        let mut end = start + c.len_utf8();
+        let out = match fold_char(c) {
+            Some(out) => out,
+            None => {
+                while let Some(&(next, n)) = chars.peek() {
+                    if !n.is_whitespace() {
+                        break;
+                    }
+                    end = next + n.len_utf8();
+                    chars.next();
+                }
+                ' '
+            }
+        };
+        folded.text.push(out);
+        for _ in 0..out.len_utf8() {
+            folded.start_of.push(start);
+            folded.end_of.push(end);
+        }
+    }
+    folded
+}
+
+/// Fold `text` (NFC first) for comparison.
+pub(crate) fn fold(text: &str) -> String {
+    fold_mapped(&normalize_nfc(text)).text
+}
+
+/// True when `before` only occurs in `text` after folding, i.e. tolerant matching is
+/// what makes the op applicable.
+pub(crate) fn relies_on_folding(text: &str, before: &str) -> bool {
+    count_non_overlapping(&normalize_nfc(text), &normalize_nfc(before)) == 0
+        && count_non_overlapping(&fold(text), &fold(before)) > 0
+}
+
+/// Byte ranges in the NFC form of `text` of the folded matches of `before` that the
+/// op selects, using the same rules as exact matching: context anchors (folded too),
+/// then `occurrence`, otherwise the first match.
+fn selected_ranges(op: &PatchOpV1, text: &str, before: &str) -> Vec<(usize, usize)> {
+    let folded = fold_mapped(text);
+    let before = fold(before);
+    if before.is_empty() {
+        return Vec::new();
+    }
+    let all: Vec<(usize, usize)> = folded
+        .text
+        .match_indices(before.as_str())
+        .map(|(s, m)| folded.original_range(s, s + m.len()))
+        .collect();
+
+    if op.has_context() {
+        let context_before = op.context_before.as_deref().map(fold);
+        let context_after = op.context_after.as_deref().map(fold);
+        return context_matches(&folded.text, &before, context_before.as_deref(), context_after.as_deref())
+            .into_iter()
+            .filter_map(|n| all.get(n - 1).copied())
+            .collect();
+    }
+    match op.occurrence {
+        Some(Occurrence::Legacy(DeleteOccurrence::All)) => all,
+        Some(Occurrence::Index(n)) => all.get((n as usize).saturating_sub(1)).copied().into_iter().collect(),
+        Some(Occurrence::Legacy(DeleteOccurrence::First)) | None => all.first().copied().into_iter().collect(),
+    }
+}
+
+/// Apply a `replace` (or, with an empty `replacement`, a `delete`) by tolerant matching.
+///
+/// Returns `None` when `before` occurs verbatim (exact matching applies) or when no
+/// folded match is selected.
+pub(crate) fn tolerant_edit(op: &PatchOpV1, text: &str, before: &str, replacement: &str) -> Option<String> {
+    if !relies_on_folding(text, before) {
+        return None;
+    }
+    let mut out = normalize_nfc(text);
+    let ranges = selected_ranges(op, &out, before);
+    if ranges.is_empty() {
+        return None;
+    }
+    let replacement = normalize_nfc(replacement);
+    for &(start, end) in ranges.iter().rev() {
+        out.replace_range(start..end, &replacement);
+    }
+    Some(out)
+}
+
+/// Split `text` at the tolerant match of `before`, like `split_at_anchor`.
+pub(crate) fn tolerant_split(op: &PatchOpV1, text: &str, before: &str) -> Option<(String, String)> {
+    if !relies_on_folding(text, before) {
+        return None;
+    }
+    let text = normalize_nfc(text);
+    let (start, _) = selected_ranges(op, &text, before).first().copied()?;
+    Some((text[..start].trim_end().to_string(), text[start..].to_string()))
+}
@@ -1,11 +1,26 @@
+use std::borrow::Cow;
+use std::collections::{HashMap, HashSet};
+
+use bdir_codebook::{is_boilerplate, is_core};
+use bdir_core::ids::IdStrategy;
 use bdir_core::model::Document;
-use bdir_core::hash::normalize_nfc;
+use bdir_core::hash::{
+    MIN_HASH_PREFIX_LEN,
+    hash_canon_hex,
+    hash_prefix_matches,
+    lookup_hash_algorithm,
+    normalize_nfc,
+    supported_hash_algorithms,
+};
 
 use crate::{
     EditPacketV1,
+    assign_ids::assign_new_block_ids,
+    anchor::{closest_match, context_matches, count_non_overlapping, split_at_anchor},
     PatchTelemetry,
     diagnostics::{DiagnosticCode, ValidationDiagnostic, ValidationError},
-    schema::{DeleteOccurrence, Occurrence, OpType, PatchV1},
+    schema::{DeleteOccurrence, Occurrence, OpType, PatchOpV1, PatchV1, SUPPORTED_PATCH_VERSIONS},
+    tolerant::{fold, relies_on_folding},
 };
 
 /// kindCode enforcement policy.
@@ -24,6 +39,12 @@ pub struct KindCodePolicy {
     /// This preserves the ability to attach non-mutating guidance to boilerplate/UI
     /// blocks while still blocking mutations.
     pub allow_suggest_any: bool,
+
+    /// If true, `reclassify` may promote a boilerplate block (20–39) into core (0–19).
+    ///
+    /// Promotion moves navigation and other boilerplate into the content a model is
+    /// allowed to edit, so strict mode forbids it unless explicitly enabled.
+    pub allow_boilerplate_promotion: bool,
 }
 
 impl Default for KindCodePolicy {
@@ -33,6 +54,7 @@ impl Default for KindCodePolicy {
             // 0–19 is Core (0–9) + Medium (10–19).
             allow_ranges: vec![(0, 19)],
             allow_suggest_any: true,
+            allow_boilerplate_promotion: false,
         }
     }
 }
@@ -81,6 +103,52 @@ pub struct ValidateOptions {
     /// When true, validators MUST reject patches that omit `h` or `ha`, even if an
     /// out-of-band `expected_page_hash` is available.
     pub strict_page_hash_binding: bool,
+
+    /// Recompute the canonical hash of every block an op reads (`block_id`, plus a
+    /// merge's `next_block_id` and a move's `after_block_id`) and compare it to `text_hash`.
+    ///
+    /// When true, validators MUST reject ops that read a block carrying a stale or
+    /// tampered `text_hash`. Truncated prefixes (RFC-0001 §5.1.4) are accepted.
+    pub verify_block_hashes: bool,
+
+    /// Keep validating past the first failure and return every diagnostic.
+    ///
+    /// Diagnostics are ordered deterministically: page-level binding first, then at
+    /// most one diagnostic per failing op, in `ops` order.
+    pub collect_all_diagnostics: bool,
+
+    /// How `before` (and context anchors) are located in block text.
+    ///
+    /// Defaults to `MatchMode::Exact` (RFC-0001 §8.3).
+    pub match_mode: MatchMode,
+
+    /// Restrict the patch to one section: the block with this id and every block nested
+    /// under it (`Block::parent`, or the Edit Packet's `p` table).
+    ///
+    /// When set, validators MUST reject ops that reference blocks outside the section,
+    /// including `insert_before` the section's root.
+    pub section: Option<String>,
+
+    /// Let the engine assign ids to inserted and split-off blocks.
+    ///
+    /// When set, `insert_after`, `insert_before` and `split_block` ops may omit
+    /// `new_block_id`; validation and apply fill it in deterministically with this
+    /// strategy (see `assign_new_block_ids`). Non-standard: the wire format requires
+    /// the field.
+    pub assign_new_block_ids: Option<IdStrategy>,
+}
+
+/// How `before` substrings are located in block text.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum MatchMode {
+    /// Verbatim (NFC) substring matching, as required by RFC-0001 §8.3.
+    #[default]
+    Exact,
+    /// Non-standard, opt-in: when `before` does not occur verbatim, retry with
+    /// whitespace runs (including NBSP), smart quotes and dashes normalized. The match
+    /// is mapped back to the original text, and every op that needed it is reported
+    /// (`tolerant_match` diagnostics, `PatchTelemetry::tolerant_match_ops`).
+    Tolerant,
 }
 
 impl Default for ValidateOptions {
@@ -92,6 +160,11 @@ impl Default for ValidateOptions {
             kind_code_policy: KindCodePolicy::default(),
             expected_page_hash: None,
             strict_page_hash_binding: false,
+            verify_block_hashes: false,
+            collect_all_diagnostics: false,
+            match_mode: MatchMode::Exact,
+            section: None,
+            assign_new_block_ids: None,
         }
     }
 }
@@ -139,6 +212,34 @@ fn enforce_kind_code(
     ))
 }
 
+/// Strict-mode gate on `reclassify`: boilerplate may not be promoted into core unless
+/// the policy allows it.
+fn enforce_reclassify(
+    i: usize,
+    op: &PatchOpV1,
+    from: u16,
+    to: u16,
+    opts: &ValidateOptions,
+) -> Result<(), ValidationError> {
+    if !opts.strict_kind_code
+        || opts.kind_code_policy.allow_boilerplate_promotion
+        || !(is_boilerplate(from) && is_core(to))
+    {
+        return Ok(());
+    }
+
+    Err(err_op(
+        DiagnosticCode::KindCodeDisallowed,
+        i,
+        op.op,
+        Some(op.block_id.clone()),
+        Some(format!("ops[{i}].kind_code")),
+        format!(
+            "ops[{i}] (reclassify) promotes boilerplate kindCode {from} to core kindCode {to}, which is disallowed under strict kindCode policy (allow_boilerplate_promotion=false)"
+        ),
+    ))
+}
+
 /// Validate a patch against a document. Strict and fail-fast.
 ///
 /// Rules:
@@ -166,7 +267,415 @@ pub fn validate_patch_with_diagnostics(
     patch: &PatchV1,
     opts: ValidateOptions,
 ) -> Result<(), ValidationError> {
-    if patch.v != 1 {
+    let assigned = opts.assign_new_block_ids.map(|strategy| assign_new_block_ids(doc, patch, strategy));
+    let patch = assigned.as_ref().unwrap_or(patch);
+    let doc_algo = doc.hash_algorithm.trim().to_lowercase();
+    let mut diagnostics = Vec::new();
+
+    // Fail-fast by default. In collect-all mode every failure is recorded and validation
+    // continues, so diagnostics come back in a deterministic order: page-level binding
+    // first, then one diagnostic per failing op in `ops` order.
+    if let Err(e) = validate_binding(doc, patch, &opts, &doc_algo) {
+        if !opts.collect_all_diagnostics {
+            return Err(e);
+        }
+        diagnostics.extend(e.diagnostics);
+    }
+
+    // An unknown section root is reported once; the ops are then checked without it.
+    let scope = SectionScope::new(doc, &opts).or_else(|e| {
+        if !opts.collect_all_diagnostics {
+            return Err(e);
+        }
+        diagnostics.extend(e.diagnostics);
+        Ok(SectionScope::unrestricted())
+    })?;
+
+    // Ops are applied in order, so nothing may target a block after it was removed
+    // (by `delete_block`, or as the `next_block_id` of a `merge_block`).
+    let mut removed_by: HashMap<&str, (usize, OpType)> = HashMap::new();
+    let links = OpLinks::new(&patch.ops);
+    for (i, op) in patch.ops.iter().enumerate() {
+        let targets = [("block_id", Some(op.block_id.as_str())), ("next_block_id", op.next_block_id.as_deref())];
+        let removed = targets
+            .into_iter()
+            .find_map(|(path, id)| id.and_then(|id| removed_by.get(id).map(|r| (path, id, *r))));
+        let result = match removed {
+            Some((path, id, (j, by))) => Err(err_op(
+                DiagnosticCode::TargetsDeletedBlock,
+                i,
+                op.op,
+                Some(op.block_id.clone()),
+                Some(format!("ops[{i}].{path}")),
+                format!("ops[{i}] targets block '{id}', which {}", removal(j, by)),
+            )),
+            None => scope
+                .check(i, op)
+                .and_then(|()| validate_op(doc, i, op, &opts, &doc_algo))
+                .and_then(|()| links.check(i, op)),
+        };
+        if let Some(id) = removed_block(op) {
+            removed_by.entry(id).or_insert((i, op.op));
+        }
+        if let Err(e) = result {
+            if !opts.collect_all_diagnostics {
+                return Err(e);
+            }
+            diagnostics.extend(e.diagnostics);
+        }
+    }
+
+    if diagnostics.is_empty() {
+        Ok(())
+    } else {
+        Err(ValidationError { diagnostics })
+    }
+}
+
+/// Blocks an op may reference under `ValidateOptions::section`.
+struct SectionScope<'a> {
+    /// Section root; `None` when the patch is not restricted.
+    root: Option<&'a str>,
+    ids: HashSet<&'a str>,
+}
+
+impl<'a> SectionScope<'a> {
+    fn new(doc: &'a Document, opts: &'a ValidateOptions) -> Result<Self, ValidationError> {
+        let Some(root) = opts.section.as_deref() else {
+            return Ok(Self::unrestricted());
+        };
+        let ids = doc.section_ids(root).ok_or_else(|| {
+            err_root(
+                DiagnosticCode::SectionNotFound,
+                "section",
+                format!("section root '{root}' is not a block of the target"),
+            )
+        })?;
+        Ok(Self { root: Some(root), ids: ids.into_iter().collect() })
+    }
+
+    fn unrestricted() -> Self {
+        Self { root: None, ids: HashSet::new() }
+    }
+
+    /// Reject references outside the section.
+    fn check(&self, i: usize, op: &PatchOpV1) -> Result<(), ValidationError> {
+        let Some(root) = self.root else {
+            return Ok(());
+        };
+        let refs = [
+            ("block_id", Some(op.block_id.as_str())),
+            ("after_block_id", op.after_block_id.as_deref()),
+            ("next_block_id", op.next_block_id.as_deref()),
+        ];
+        for (field, id) in refs {
+            let Some(id) = id else { continue };
+            let before_root = op.op == OpType::InsertBefore && id == root;
+            if before_root || !self.ids.contains(id) {
+                let reason = if before_root { "would insert before" } else { "references a block outside" };
+                return Err(err_op(
+                    DiagnosticCode::OutOfSection,
+                    i,
+                    op.op,
+                    Some(op.block_id.clone()),
+                    Some(format!("ops[{i}].{field}")),
+                    format!("ops[{i}] ({}) {reason} section '{root}' ('{id}')", op.op.as_str()),
+                ));
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Block removed from the page by `op`, if any.
+fn removed_block(op: &PatchOpV1) -> Option<&str> {
+    match op.op {
+        OpType::DeleteBlock => Some(op.block_id.as_str()),
+        OpType::MergeBlock => op.next_block_id.as_deref(),
+        _ => None,
+    }
+}
+
+/// Block added to the page by `op`, if any.
+fn created_block(op: &PatchOpV1) -> Option<&str> {
+    match op.op {
+        OpType::InsertAfter | OpType::InsertBefore | OpType::SplitBlock => op.new_block_id.as_deref(),
+        _ => None,
+    }
+}
+
+/// "ops[j] deletes" / "ops[j] merges away", for removal diagnostics.
+fn removal(j: usize, by: OpType) -> String {
+    match by {
+        OpType::MergeBlock => format!("ops[{j}] merges away"),
+        _ => format!("ops[{j}] deletes"),
+    }
+}
+
+/// Cross-op relations that single-op validation cannot see: block removals, moves,
+/// splits and merges.
+struct OpLinks<'a> {
+    /// First removing op (`delete_block`, or `merge_block` for its `next_block_id`) per block.
+    removes: HashMap<&'a str, (usize, OpType)>,
+    /// `move` ops per moved block: (op index, anchor).
+    moves: HashMap<&'a str, Vec<(usize, &'a str)>>,
+    /// `split_block` op indices per split block.
+    splits: HashMap<&'a str, Vec<usize>>,
+    /// `merge_block` op indices per block involved (either side of the merge).
+    merges: HashMap<&'a str, Vec<usize>>,
+    /// First op creating each `new_block_id` (inserts and splits).
+    creates: HashMap<&'a str, usize>,
+}
+
+impl<'a> OpLinks<'a> {
+    fn new(ops: &'a [PatchOpV1]) -> Self {
+        let mut removes = HashMap::new();
+        let mut moves: HashMap<&str, Vec<(usize, &str)>> = HashMap::new();
+        let mut splits: HashMap<&str, Vec<usize>> = HashMap::new();
+        let mut merges: HashMap<&str, Vec<usize>> = HashMap::new();
+        let mut creates = HashMap::new();
+        for (i, op) in ops.iter().enumerate() {
+            if let Some(id) = removed_block(op) {
+                removes.entry(id).or_insert((i, op.op));
+            }
+            if let Some(id) = created_block(op) {
+                creates.entry(id).or_insert(i);
+            }
+            match op.op {
+                OpType::Move => {
+                    if let Some(anchor) = op.after_block_id.as_deref() {
+                        moves.entry(op.block_id.as_str()).or_default().push((i, anchor));
+                    }
+                }
+                OpType::SplitBlock => splits.entry(op.block_id.as_str()).or_default().push(i),
+                OpType::MergeBlock => {
+                    merges.entry(op.block_id.as_str()).or_default().push(i);
+                    if let Some(next) = op.next_block_id.as_deref() {
+                        merges.entry(next).or_default().push(i);
+                    }
+                }
+                _ => {}
+            }
+        }
+        Self { removes, moves, splits, merges, creates }
+    }
+
+    fn check(&self, i: usize, op: &PatchOpV1) -> Result<(), ValidationError> {
+        self.check_structure(i, op)?;
+        if let Some(id) = created_block(op)
+            && let Some(&j) = self.creates.get(id).filter(|&&j| j < i)
+        {
+            return Err(err_op(
+                DiagnosticCode::DuplicateBlockId,
+                i,
+                op.op,
+                Some(op.block_id.clone()),
+                Some(format!("ops[{i}].new_block_id")),
+                format!("ops[{i}] ({}) new_block_id '{id}' is already created by ops[{j}]", op.op.as_str()),
+            ));
+        }
+        match op.op {
+            OpType::Move => self.check_move(i, op),
+            // Canonical order applies moves first, so an insert must not depend on a
+            // later move: neither on its anchor being relocated, nor (for insert_after)
+            // on landing between its anchor and a block moved after that anchor.
+            OpType::InsertAfter | OpType::InsertBefore => {
+                let moved = self
+                    .moves
+                    .get(op.block_id.as_str())
+                    .and_then(|ms| ms.iter().find(|(j, _)| *j > i))
+                    .map(|(j, _)| format!("which ops[{j}] moves later"));
+                let shared = (op.op == OpType::InsertAfter)
+                    .then(|| {
+                        self.moves
+                            .values()
+                            .flatten()
+                            .filter(|(j, anchor)| *j > i && *anchor == op.block_id)
+                            .map(|(j, _)| *j)
+                            .min()
+                    })
+                    .flatten()
+                    .map(|j| format!("which ops[{j}] later uses as a move anchor"));
+                match moved.or(shared) {
+                    Some(why) => Err(err_op(
+                        DiagnosticCode::MoveConflict,
+                        i,
+                        op.op,
+                        Some(op.block_id.clone()),
+                        Some(format!("ops[{i}].block_id")),
+                        format!("ops[{i}] ({}) anchors on block '{}', {why}", op.op.as_str(), op.block_id),
+                    )),
+                    None => Ok(()),
+                }
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// Splits and merges rewrite block boundaries, and canonical ordering may move ops
+    /// across them, so their combinations are restricted:
+    /// - a split block may not be edited, split or merged by later ops
+    /// - one patch may not both split and merge the same block
+    /// - a block merged away may not absorb another block (merge chains need separate patches)
+    fn check_structure(&self, i: usize, op: &PatchOpV1) -> Result<(), ValidationError> {
+        let conflict = |message: String| {
+            Err(err_op(
+                DiagnosticCode::StructuralConflict,
+                i,
+                op.op,
+                Some(op.block_id.clone()),
+                Some(format!("ops[{i}].block_id")),
+                message,
+            ))
+        };
+        let name = op.op.as_str();
+        let earlier = |ops: Option<&Vec<usize>>| ops.and_then(|js| js.iter().copied().find(|&j| j < i));
+
+        if matches!(op.op, OpType::Replace | OpType::Delete | OpType::SplitBlock | OpType::MergeBlock)
+            && let Some(j) = earlier(self.splits.get(op.block_id.as_str()))
+        {
+            return conflict(format!(
+                "ops[{i}] ({name}) targets block '{}' after ops[{j}] splits it; edit the block before splitting it",
+                op.block_id
+            ));
+        }
+
+        match op.op {
+            OpType::SplitBlock => {
+                if let Some(j) = earlier(self.merges.get(op.block_id.as_str())) {
+                    return conflict(format!(
+                        "ops[{i}] (split_block) splits block '{}', which ops[{j}] merges; split and merge it in separate patches",
+                        op.block_id
+                    ));
+                }
+            }
+            OpType::MergeBlock => {
+                let next = op.next_block_id.as_deref().unwrap_or_default();
+                if let Some((id, j)) = [op.block_id.as_str(), next]
+                    .into_iter()
+                    .find_map(|id| earlier(self.splits.get(id)).map(|j| (id, j)))
+                {
+                    return conflict(format!(
+                        "ops[{i}] (merge_block) merges block '{id}', which ops[{j}] splits; split and merge it in separate patches"
+                    ));
+                }
+                if let Some(&(j, OpType::MergeBlock)) = self.removes.get(op.block_id.as_str()) {
+                    return conflict(format!(
+                        "ops[{i}] (merge_block) merges into block '{}', which ops[{j}] merges away; merge chains need separate patches",
+                        op.block_id
+                    ));
+                }
+            }
+            _ => {}
+        }
+        Ok(())
+    }
+
+    fn check_move(&self, i: usize, op: &PatchOpV1) -> Result<(), ValidationError> {
+        let block = op.block_id.as_str();
+        let anchor = op.after_block_id.as_deref().unwrap_or_default();
+
+        for (id, role, path) in [(block, "moves", "block_id"), (anchor, "anchors on", "after_block_id")] {
+            if let Some(&(j, by)) = self.removes.get(id) {
+                return Err(err_op(
+                    DiagnosticCode::TargetsDeletedBlock,
+                    i,
+                    op.op,
+                    Some(op.block_id.clone()),
+                    Some(format!("ops[{i}].{path}")),
+                    format!("ops[{i}] (move) {role} block '{id}', which {}", removal(j, by)),
+                ));
+            }
+        }
+
+        // Follow each block's first move: block -> anchor -> anchor's anchor -> ...
+        let mut chain = vec![block, anchor];
+        let mut cur = anchor;
+        while cur != block && chain.len() <= self.moves.len() + 1 {
+            let Some(&(_, next)) = self.moves.get(cur).and_then(|ms| ms.first()) else {
+                return Ok(());
+            };
+            chain.push(next);
+            cur = next;
+        }
+        if cur != block {
+            return Ok(());
+        }
+        Err(err_op(
+            DiagnosticCode::MoveCycle,
+            i,
+            op.op,
+            Some(op.block_id.clone()),
+            Some(format!("ops[{i}].after_block_id")),
+            format!("ops[{i}] (move) forms a cycle: {}", chain.join(" -> ")),
+        ))
+    }
+}
+
+/// Block text and `before` to match against: verbatim, or both folded when the op only
+/// matches under `MatchMode::Tolerant`. The flag reports whether folding was used.
+fn match_texts<'a>(text: &'a str, before: &'a str, mode: MatchMode) -> (Cow<'a, str>, Cow<'a, str>, bool) {
+    if mode == MatchMode::Tolerant && relies_on_folding(text, before) {
+        (Cow::Owned(fold(text)), Cow::Owned(fold(before)), true)
+    } else {
+        (Cow::Borrowed(text), Cow::Borrowed(before), false)
+    }
+}
+
+/// Context anchors on `replace` / `delete`: they stand in for `occurrence` and must
+/// select exactly one match of `before` in the block text.
+///
+/// With `folded`, `text` and `before` are tolerant-folded and the anchors are folded too.
+fn check_context(i: usize, op: &PatchOpV1, text: &str, before: &str, folded: bool) -> Result<(), ValidationError> {
+    let name = op.op.as_str();
+    if op.occurrence.is_some() {
+        return Err(err_op(
+            DiagnosticCode::UnexpectedField,
+            i,
+            op.op,
+            Some(op.block_id.clone()),
+            Some(format!("ops[{i}].occurrence")),
+            format!("ops[{i}] ({name}) occurrence cannot be combined with context_before/context_after"),
+        ));
+    }
+    let path = if op.context_before.is_some() { "context_before" } else { "context_after" };
+    let anchor = |c: &Option<String>| c.as_deref().map(|c| if folded { fold(c) } else { c.to_string() });
+    let selected = context_matches(text, before, anchor(&op.context_before).as_deref(), anchor(&op.context_after).as_deref());
+    match selected.len() {
+        1 => Ok(()),
+        0 => Err(err_op(
+            DiagnosticCode::ContextNotFound,
+            i,
+            op.op,
+            Some(op.block_id.clone()),
+            Some(format!("ops[{i}].{path}")),
+            format!(
+                "ops[{i}] ({name}) no match of before in block '{}' has the given context",
+                op.block_id
+            ),
+        )),
+        n => Err(err_op(
+            DiagnosticCode::ContextAmbiguous,
+            i,
+            op.op,
+            Some(op.block_id.clone()),
+            Some(format!("ops[{i}].{path}")),
+            format!(
+                "ops[{i}] ({name}) context is ambiguous in block '{}' ({n} matches of before have it); widen context_before/context_after",
+                op.block_id
+            ),
+        )),
+    }
+}
+
+/// Page-level checks: patch version, hash algorithm, and page-hash binding.
+fn validate_binding(
+    doc: &Document,
+    patch: &PatchV1,
+    opts: &ValidateOptions,
+    doc_algo: &str,
+) -> Result<(), ValidationError> {
+    if !SUPPORTED_PATCH_VERSIONS.contains(&patch.v) {
         return Err(err_root(
             DiagnosticCode::UnsupportedPatchVersion,
             "v",
@@ -174,6 +683,20 @@ pub fn validate_patch_with_diagnostics(
         ));
     }
 
+    // RFC-0001 §5.1.2: receivers MUST treat documents with an unrecognized
+    // hash_algorithm as unsupported and MUST NOT apply patches derived from them.
+    if lookup_hash_algorithm(doc_algo).is_none() {
+        return Err(err_root(
+            DiagnosticCode::UnsupportedHashAlgorithm,
+            "ha",
+            format!(
+                "unsupported hash algorithm '{}' (supported: {})",
+                doc.hash_algorithm,
+                supported_hash_algorithms().join(", ")
+            ),
+        ));
+    }
+
     // Strict page-hash binding (safety hardening):
     // In strict mode, a patch MUST carry an explicit in-band binding (`h` + `ha`).
     if opts.strict_page_hash_binding {
@@ -193,13 +716,23 @@ pub fn validate_patch_with_diagnostics(
             ));
         }
     }
+    // RFC-0001 §5.1.4: hashes MAY be truncated prefixes, but never shorter than 8 hex chars.
+    if let Some(patch_h) = patch.h.as_deref() {
+        guard_hash_len("h", "patch.h", patch_h)?;
+    }
+    if let Some(expected_h) = opts.expected_page_hash.as_deref() {
+        guard_hash_len("h", "expected_page_hash", expected_h)?;
+    }
+
     // Safety binding: ensure the patch is only applied to the intended page version.
     //
     // A patch MUST be bound to a specific page hash either by including `h` in the patch,
     // or by the caller providing an explicit `expected_page_hash` out-of-band.
+    //
+    // Comparisons are prefix-aware: a truncated hash matches any hash it is a prefix of.
     let expected = match (patch.h.as_deref(), opts.expected_page_hash.as_deref()) {
         (Some(patch_h), Some(expected_h)) => {
-            if patch_h != expected_h {
+            if !hash_prefix_matches(patch_h, expected_h) {
                 return Err(err_root(
                     DiagnosticCode::PatchPageHashMismatch,
                     "h",
@@ -230,32 +763,32 @@ pub fn validate_patch_with_diagnostics(
     // Note: this check only applies when the patch includes an in-band page-hash binding (`h`).
     // If the caller supplies `expected_page_hash` out-of-band, the hash algorithm is implied by
     // the target document/packet and `patch.ha` is ignored.
-    if patch.h.is_some() {
-        if let Some(patch_algo_raw) = patch.ha.as_deref() {
-            let patch_algo = patch_algo_raw.trim().to_lowercase();
-            if patch_algo.is_empty() {
-                return Err(err_root(
-                    DiagnosticCode::MissingField,
-                    "ha",
-                    "patch ha is empty".to_string(),
-                ));
-            }
+    if patch.h.is_some()
+        && let Some(patch_algo_raw) = patch.ha.as_deref()
+    {
+        let patch_algo = patch_algo_raw.trim().to_lowercase();
+        if patch_algo.is_empty() {
+            return Err(err_root(
+                DiagnosticCode::MissingField,
+                "ha",
+                "patch ha is empty".to_string(),
+            ));
+        }
 
-            let doc_algo = doc.hash_algorithm.trim().to_lowercase();
-            if patch_algo != doc_algo {
-                return Err(err_root(
-                    DiagnosticCode::HashAlgorithmMismatch,
-                    "ha",
-                    format!(
-                        "patch hash algorithm mismatch (patch.ha='{}', doc.hash_algorithm='{}')",
-                        patch_algo_raw, doc.hash_algorithm
-                    ),
-                ));
-            }
+        if patch_algo != *doc_algo {
+            return Err(err_root(
+                DiagnosticCode::HashAlgorithmMismatch,
+                "ha",
+                format!(
+                    "patch hash algorithm mismatch (patch.ha='{}', doc.hash_algorithm='{}')",
+                    patch_algo_raw, doc.hash_algorithm
+                ),
+            ));
         }
     }
 
-    if doc.page_hash != expected {
+    guard_hash_len("h", "target page hash", &doc.page_hash)?;
+    if !hash_prefix_matches(&doc.page_hash, expected) {
         return Err(err_root(
             DiagnosticCode::PatchPageHashMismatch,
             "h",
@@ -266,400 +799,711 @@ pub fn validate_patch_with_diagnostics(
         ));
     }
 
-    for (i, op) in patch.ops.iter().enumerate() {
-        let block = doc
-            .blocks
-            .iter()
-            .find(|b| b.id == op.block_id)
-            .ok_or_else(|| {
+    Ok(())
+}
+
+/// Per-op checks: target block, kindCode policy, block hash, and op-specific fields.
+fn validate_op(
+    doc: &Document,
+    i: usize,
+    op: &PatchOpV1,
+    opts: &ValidateOptions,
+    doc_algo: &str,
+) -> Result<(), ValidationError> {
+    let block = doc
+        .blocks
+        .iter()
+        .find(|b| b.id == op.block_id)
+        .ok_or_else(|| {
+            err_op(
+                DiagnosticCode::UnknownBlockId,
+                i,
+                op.op,
+                Some(op.block_id.clone()),
+                Some(format!("ops[{i}].block_id")),
+                format!("ops[{i}] references unknown block_id '{}'", op.block_id),
+            )
+        })?;
+
+    // Optional strict safety gate: enforce kindCode policy.
+    enforce_kind_code(i, op.op, &op.block_id, block.kind_code, opts)?;
+
+    // Optional integrity gate: every block the op reads must carry a text_hash that
+    // matches its text (unknown ids are reported by the op's own checks).
+    if opts.verify_block_hashes {
+        verify_block_hash(i, op.op, "block_id", block, doc_algo)?;
+        let others = [("next_block_id", op.next_block_id.as_deref()), ("after_block_id", op.after_block_id.as_deref())];
+        for (field, id) in others {
+            if let Some(other) = id.and_then(|id| doc.blocks.iter().find(|b| b.id == id)) {
+                verify_block_hash(i, op.op, field, other, doc_algo)?;
+            }
+        }
+    }
+
+    if op.has_context() && !matches!(op.op, OpType::Replace | OpType::Delete) {
+        let field = if op.context_before.is_some() { "context_before" } else { "context_after" };
+        return Err(err_op(
+            DiagnosticCode::UnexpectedField,
+            i,
+            op.op,
+            Some(op.block_id.clone()),
+            Some(format!("ops[{i}].{field}")),
+            format!(
+                "ops[{i}] ({}) unexpected {field} (context is only valid for replace and delete)",
+                op.op.as_str()
+            ),
+        ));
+    }
+
+    match op.op {
+        OpType::Replace => {
+            let before = op.before.as_deref().ok_or_else(|| {
                 err_op(
-                    DiagnosticCode::UnknownBlockId,
+                    DiagnosticCode::MissingField,
                     i,
                     op.op,
                     Some(op.block_id.clone()),
-                    Some(format!("ops[{i}].block_id")),
-                    format!("ops[{i}] references unknown block_id '{}'", op.block_id),
+                    Some(format!("ops[{i}].before")),
+                    format!("ops[{i}] (replace) missing before"),
+                )
+            })?;
+            let _after = op.after.as_deref().ok_or_else(|| {
+                err_op(
+                    DiagnosticCode::MissingField,
+                    i,
+                    op.op,
+                    Some(op.block_id.clone()),
+                    Some(format!("ops[{i}].after")),
+                    format!("ops[{i}] (replace) missing after"),
                 )
             })?;
 
-        // Optional strict safety gate: enforce kindCode policy.
-        enforce_kind_code(i, op.op, &op.block_id, block.kind_code, &opts)?;
-
-        match op.op {
-            OpType::Replace => {
-                let before = op.before.as_deref().ok_or_else(|| {
-                    err_op(
-                        DiagnosticCode::MissingField,
-                        i,
-                        op.op,
-                        Some(op.block_id.clone()),
-                        Some(format!("ops[{i}].before")),
-                        format!("ops[{i}] (replace) missing before"),
-                    )
-                })?;
-                let _after = op.after.as_deref().ok_or_else(|| {
-                    err_op(
-                        DiagnosticCode::MissingField,
-                        i,
-                        op.op,
-                        Some(op.block_id.clone()),
-                        Some(format!("ops[{i}].after")),
-                        format!("ops[{i}] (replace) missing after"),
-                    )
-                })?;
-
-                guard_before_diag(i, op.op, &op.block_id, before, opts.min_before_len)?;
-                let matches = count_non_overlapping(&block.text, before);
-                if matches == 0 {
-                    return Err(err_op(
-                        DiagnosticCode::BeforeNotFound,
-                        i,
-                        op.op,
-                        Some(op.block_id.clone()),
-                        Some(format!("ops[{i}].before")),
-                        format!(
-                            "ops[{i}] (replace) before substring not found in block '{}'",
-                            op.block_id
-                        ),
-                    ));
-                }
+            guard_before_diag(i, op.op, &op.block_id, before, opts.min_before_len)?;
+            let (text, before, folded) = match_texts(&block.text, before, opts.match_mode);
+            let matches = count_non_overlapping(&normalize_nfc(&text), &normalize_nfc(&before));
+            if matches == 0 {
+                return Err(before_not_found(i, op, &text, &before));
+            }
 
-                // Ambiguity handling (RFC-0001 v1.0.2):
-                // - If multiple matches exist and `occurrence` is omitted, reject.
-                // - If `occurrence` is present, it must be a 1-indexed integer within range.
-                match op.occurrence {
-                    None => {
-                        if matches > 1 {
-                            return Err(err_op(
-                                DiagnosticCode::BeforeAmbiguous,
-                                i,
-                                op.op,
-                                Some(op.block_id.clone()),
-                                Some(format!("ops[{i}].before")),
-                                format!(
-                                    "ops[{i}] (replace) before substring is ambiguous in block '{}' (matches {matches} times); provide occurrence",
-                                    op.block_id
-                                ),
-                            ));
-                        }
-                    }
-                    Some(Occurrence::Index(n)) => {
-                        if n == 0 || (n as usize) > matches {
-                            return Err(err_op(
-                                DiagnosticCode::OccurrenceOutOfRange,
-                                i,
-                                op.op,
-                                Some(op.block_id.clone()),
-                                Some(format!("ops[{i}].occurrence")),
-                                format!(
-                                    "ops[{i}] (replace) occurrence out of range for block '{}' (occurrence={n}, matches={matches})",
-                                    op.block_id
-                                ),
-                            ));
-                        }
+            // Ambiguity handling (RFC-0001 v1.0.2):
+            // - If multiple matches exist and `occurrence` is omitted, reject.
+            // - If `occurrence` is present, it must be a 1-indexed integer within range.
+            // - Context anchors replace `occurrence` and must select exactly one match.
+            if op.has_context() {
+                check_context(i, op, &text, &before, folded)?;
+            }
+            match op.occurrence {
+                None => {
+                    if matches > 1 && !op.has_context() {
+                        return Err(err_op(
+                            DiagnosticCode::BeforeAmbiguous,
+                            i,
+                            op.op,
+                            Some(op.block_id.clone()),
+                            Some(format!("ops[{i}].before")),
+                            format!(
+                                "ops[{i}] (replace) before substring is ambiguous in block '{}' (matches {matches} times); provide occurrence",
+                                op.block_id
+                            ),
+                        ));
                     }
-                    Some(Occurrence::Legacy(_)) => {
+                }
+                Some(Occurrence::Index(n)) => {
+                    if n == 0 || (n as usize) > matches {
                         return Err(err_op(
-                            DiagnosticCode::UnexpectedField,
+                            DiagnosticCode::OccurrenceOutOfRange,
                             i,
                             op.op,
                             Some(op.block_id.clone()),
                             Some(format!("ops[{i}].occurrence")),
                             format!(
-                                "ops[{i}] (replace) invalid occurrence value (legacy string values are delete-only; use integer occurrence)",
+                                "ops[{i}] (replace) occurrence out of range for block '{}' (occurrence={n}, matches={matches})",
+                                op.block_id
                             ),
                         ));
                     }
                 }
-            }
-
-            OpType::Delete => {
-                let before = op.before.as_deref().ok_or_else(|| {
-                    err_op(
-                        DiagnosticCode::MissingField,
-                        i,
-                        op.op,
-                        Some(op.block_id.clone()),
-                        Some(format!("ops[{i}].before")),
-                        format!("ops[{i}] (delete) missing before"),
-                    )
-                })?;
-
-                let matches = count_non_overlapping(&block.text, before);
-
-                guard_before_diag(i, op.op, &op.block_id, before, opts.min_before_len)?;
-                if matches == 0 {
+                Some(Occurrence::Legacy(_)) => {
                     return Err(err_op(
-                        DiagnosticCode::BeforeNotFound,
+                        DiagnosticCode::UnexpectedField,
                         i,
                         op.op,
                         Some(op.block_id.clone()),
-                        Some(format!("ops[{i}].before")),
+                        Some(format!("ops[{i}].occurrence")),
                         format!(
-                            "ops[{i}] (delete) before substring not found in block '{}'",
-                            op.block_id
+                            "ops[{i}] (replace) invalid occurrence value (legacy string values are delete-only; use integer occurrence)",
                         ),
                     ));
                 }
+            }
+        }
 
-                match op.occurrence {
-                    None => {
-                        if matches > 1 {
-                            return Err(err_op(
-                                DiagnosticCode::BeforeAmbiguous,
-                                i,
-                                op.op,
-                                Some(op.block_id.clone()),
-                                Some(format!("ops[{i}].before")),
-                                format!(
-                                    "ops[{i}] (delete) before substring is ambiguous in block '{}' (matches {matches} times); provide occurrence",
-                                    op.block_id
-                                ),
-                            ));
-                        }
-                    }
-                    Some(Occurrence::Index(n)) => {
-                        if n == 0 || (n as usize) > matches {
-                            return Err(err_op(
-                                DiagnosticCode::OccurrenceOutOfRange,
-                                i,
-                                op.op,
-                                Some(op.block_id.clone()),
-                                Some(format!("ops[{i}].occurrence")),
-                                format!(
-                                    "ops[{i}] (delete) occurrence out of range for block '{}' (occurrence={n}, matches={matches})",
-                                    op.block_id
-                                ),
-                            ));
-                        }
-                    }
-                    // Legacy delete semantics are accepted for backwards compatibility.
-                    Some(Occurrence::Legacy(DeleteOccurrence::First)) => {}
-                    Some(Occurrence::Legacy(DeleteOccurrence::All)) => {}
-                }
+        OpType::Delete => {
+            let before = op.before.as_deref().ok_or_else(|| {
+                err_op(
+                    DiagnosticCode::MissingField,
+                    i,
+                    op.op,
+                    Some(op.block_id.clone()),
+                    Some(format!("ops[{i}].before")),
+                    format!("ops[{i}] (delete) missing before"),
+                )
+            })?;
+
+            guard_before_diag(i, op.op, &op.block_id, before, opts.min_before_len)?;
+            let (text, before, folded) = match_texts(&block.text, before, opts.match_mode);
+            let matches = count_non_overlapping(&normalize_nfc(&text), &normalize_nfc(&before));
+            if matches == 0 {
+                return Err(before_not_found(i, op, &text, &before));
             }
 
-            OpType::InsertAfter => {
-                if op.occurrence.is_some() {
-                    return Err(err_op(
-                        DiagnosticCode::UnexpectedField,
-                        i,
-                        op.op,
-                        Some(op.block_id.clone()),
-                        Some(format!("ops[{i}].occurrence")),
-                        format!(
-                            "ops[{i}] (insert_after) unexpected occurrence (only valid for delete)"
-                        ),
-                    ));
-                }
-                if op.before.is_some() {
-                    return Err(err_op(
-                        DiagnosticCode::UnexpectedField,
-                        i,
-                        op.op,
-                        Some(op.block_id.clone()),
-                        Some(format!("ops[{i}].before")),
-                        format!(
-                            "ops[{i}] (insert_after) unexpected before (insert_after must not include before/after)"
-                        ),
-                    ));
-                }
-                if op.after.is_some() {
-                    return Err(err_op(
-                        DiagnosticCode::UnexpectedField,
-                        i,
-                        op.op,
-                        Some(op.block_id.clone()),
-                        Some(format!("ops[{i}].after")),
-                        format!(
-                            "ops[{i}] (insert_after) unexpected after (insert_after must not include before/after)"
-                        ),
-                    ));
+            if op.has_context() {
+                check_context(i, op, &text, &before, folded)?;
+            }
+            match op.occurrence {
+                None => {
+                    if matches > 1 && !op.has_context() {
+                        return Err(err_op(
+                            DiagnosticCode::BeforeAmbiguous,
+                            i,
+                            op.op,
+                            Some(op.block_id.clone()),
+                            Some(format!("ops[{i}].before")),
+                            format!(
+                                "ops[{i}] (delete) before substring is ambiguous in block '{}' (matches {matches} times); provide occurrence",
+                                op.block_id
+                            ),
+                        ));
+                    }
                 }
-                if op.message.is_some() {
-                    return Err(err_op(
-                        DiagnosticCode::UnexpectedField,
-                        i,
-                        op.op,
-                        Some(op.block_id.clone()),
-                        Some(format!("ops[{i}].message")),
-                        format!(
-                            "ops[{i}] (insert_after) unexpected message (insert_after is mutating; use suggest instead)"
-                        ),
-                    ));
+                Some(Occurrence::Index(n)) => {
+                    if n == 0 || (n as usize) > matches {
+                        return Err(err_op(
+                            DiagnosticCode::OccurrenceOutOfRange,
+                            i,
+                            op.op,
+                            Some(op.block_id.clone()),
+                            Some(format!("ops[{i}].occurrence")),
+                            format!(
+                                "ops[{i}] (delete) occurrence out of range for block '{}' (occurrence={n}, matches={matches})",
+                                op.block_id
+                            ),
+                        ));
+                    }
                 }
+                // Legacy delete semantics are accepted for backwards compatibility.
+                Some(Occurrence::Legacy(DeleteOccurrence::First)) => {}
+                Some(Occurrence::Legacy(DeleteOccurrence::All)) => {}
+            }
+        }
 
-                let new_block_id = op.new_block_id.as_deref().ok_or_else(|| {
-                    err_op(
-                        DiagnosticCode::MissingField,
-                        i,
-                        op.op,
-                        Some(op.block_id.clone()),
-                        Some(format!("ops[{i}].new_block_id")),
-                        format!("ops[{i}] (insert_after) missing new_block_id"),
-                    )
-                })?;
-                if new_block_id.trim().is_empty() {
-                    return Err(err_op(
-                        DiagnosticCode::ContentEmpty,
-                        i,
-                        op.op,
-                        Some(op.block_id.clone()),
-                        Some(format!("ops[{i}].new_block_id")),
-                        format!("ops[{i}] (insert_after) new_block_id is empty"),
-                    ));
-                }
-                if doc.blocks.iter().any(|b| b.id == new_block_id) {
-                    return Err(err_op(
-                        DiagnosticCode::DuplicateBlockId,
-                        i,
-                        op.op,
-                        Some(op.block_id.clone()),
-                        Some(format!("ops[{i}].new_block_id")),
-                        format!(
-                            "ops[{i}] (insert_after) new_block_id '{}' already exists",
-                            new_block_id
-                        ),
-                    ));
-                }
+        OpType::InsertAfter | OpType::InsertBefore => {
+            let name = op.op.as_str();
+            if op.occurrence.is_some() {
+                return Err(err_op(
+                    DiagnosticCode::UnexpectedField,
+                    i,
+                    op.op,
+                    Some(op.block_id.clone()),
+                    Some(format!("ops[{i}].occurrence")),
+                    format!(
+                        "ops[{i}] ({name}) unexpected occurrence (only valid for delete)"
+                    ),
+                ));
+            }
+            if op.before.is_some() {
+                return Err(err_op(
+                    DiagnosticCode::UnexpectedField,
+                    i,
+                    op.op,
+                    Some(op.block_id.clone()),
+                    Some(format!("ops[{i}].before")),
+                    format!(
+                        "ops[{i}] ({name}) unexpected before ({name} must not include before/after)"
+                    ),
+                ));
+            }
+            if op.after.is_some() {
+                return Err(err_op(
+                    DiagnosticCode::UnexpectedField,
+                    i,
+                    op.op,
+                    Some(op.block_id.clone()),
+                    Some(format!("ops[{i}].after")),
+                    format!(
+                        "ops[{i}] ({name}) unexpected after ({name} must not include before/after)"
+                    ),
+                ));
+            }
+            if op.message.is_some() {
+                return Err(err_op(
+                    DiagnosticCode::UnexpectedField,
+                    i,
+                    op.op,
+                    Some(op.block_id.clone()),
+                    Some(format!("ops[{i}].message")),
+                    format!(
+                        "ops[{i}] ({name}) unexpected message ({name} is mutating; use suggest instead)"
+                    ),
+                ));
+            }
 
-                let _kind_code = op.kind_code.ok_or_else(|| {
-                    err_op(
-                        DiagnosticCode::MissingField,
-                        i,
-                        op.op,
-                        Some(op.block_id.clone()),
-                        Some(format!("ops[{i}].kind_code")),
-                        format!("ops[{i}] (insert_after) missing kind_code"),
-                    )
-                })?;
-
-                let text = op.text.as_deref().ok_or_else(|| {
-                    err_op(
-                        DiagnosticCode::MissingField,
-                        i,
-                        op.op,
-                        Some(op.block_id.clone()),
-                        Some(format!("ops[{i}].text")),
-                        format!("ops[{i}] (insert_after) missing text"),
-                    )
-                })?;
-                if text.trim().is_empty() {
-                    return Err(err_op(
-                        DiagnosticCode::ContentEmpty,
-                        i,
-                        op.op,
-                        Some(op.block_id.clone()),
-                        Some(format!("ops[{i}].text")),
-                        format!("ops[{i}] (insert_after) text is empty"),
-                    ));
-                }
+            let new_block_id = op.new_block_id.as_deref().ok_or_else(|| {
+                err_op(
+                    DiagnosticCode::MissingField,
+                    i,
+                    op.op,
+                    Some(op.block_id.clone()),
+                    Some(format!("ops[{i}].new_block_id")),
+                    format!("ops[{i}] ({name}) missing new_block_id"),
+                )
+            })?;
+            if new_block_id.trim().is_empty() {
+                return Err(err_op(
+                    DiagnosticCode::ContentEmpty,
+                    i,
+                    op.op,
+                    Some(op.block_id.clone()),
+                    Some(format!("ops[{i}].new_block_id")),
+                    format!("ops[{i}] ({name}) new_block_id is empty"),
+                ));
+            }
+            if doc.blocks.iter().any(|b| b.id == new_block_id) {
+                return Err(err_op(
+                    DiagnosticCode::DuplicateBlockId,
+                    i,
+                    op.op,
+                    Some(op.block_id.clone()),
+                    Some(format!("ops[{i}].new_block_id")),
+                    format!(
+                        "ops[{i}] ({name}) new_block_id '{}' already exists",
+                        new_block_id
+                    ),
+                ));
             }
 
-            OpType::Suggest => {
-                if op.occurrence.is_some() {
-                    return Err(err_op(
-                        DiagnosticCode::UnexpectedField,
-                        i,
-                        op.op,
-                        Some(op.block_id.clone()),
-                        Some(format!("ops[{i}].occurrence")),
-                        format!(
-                            "ops[{i}] (suggest) unexpected occurrence (only valid for delete)"
-                        ),
-                    ));
+            let _kind_code = op.kind_code.ok_or_else(|| {
+                err_op(
+                    DiagnosticCode::MissingField,
+                    i,
+                    op.op,
+                    Some(op.block_id.clone()),
+                    Some(format!("ops[{i}].kind_code")),
+                    format!("ops[{i}] ({name}) missing kind_code"),
+                )
+            })?;
+
+            let text = op.text.as_deref().ok_or_else(|| {
+                err_op(
+                    DiagnosticCode::MissingField,
+                    i,
+                    op.op,
+                    Some(op.block_id.clone()),
+                    Some(format!("ops[{i}].text")),
+                    format!("ops[{i}] ({name}) missing text"),
+                )
+            })?;
+            if text.trim().is_empty() {
+                return Err(err_op(
+                    DiagnosticCode::ContentEmpty,
+                    i,
+                    op.op,
+                    Some(op.block_id.clone()),
+                    Some(format!("ops[{i}].text")),
+                    format!("ops[{i}] ({name}) text is empty"),
+                ));
+            }
+        }
+
+        OpType::SplitBlock => {
+            let unexpected = [
+                ("after", op.after.is_some()),
+                ("occurrence", op.occurrence.is_some()),
+                ("text", op.text.is_some()),
+                ("text_hash", op.text_hash.is_some()),
+                ("after_block_id", op.after_block_id.is_some()),
+                ("next_block_id", op.next_block_id.is_some()),
+                ("message", op.message.is_some()),
+            ];
+            if let Some((field, _)) = unexpected.iter().find(|(_, present)| *present) {
+                return Err(err_op(
+                    DiagnosticCode::UnexpectedField,
+                    i,
+                    op.op,
+                    Some(op.block_id.clone()),
+                    Some(format!("ops[{i}].{field}")),
+                    format!("ops[{i}] (split_block) unexpected {field} (split_block keeps the block's text)"),
+                ));
+            }
+
+            let before = op.before.as_deref().ok_or_else(|| {
+                err_op(
+                    DiagnosticCode::MissingField,
+                    i,
+                    op.op,
+                    Some(op.block_id.clone()),
+                    Some(format!("ops[{i}].before")),
+                    format!("ops[{i}] (split_block) missing before"),
+                )
+            })?;
+            guard_before_diag(i, op.op, &op.block_id, before, opts.min_before_len)?;
+            let (text, before, _) = match_texts(&block.text, before, opts.match_mode);
+            match count_non_overlapping(&normalize_nfc(&text), &normalize_nfc(&before)) {
+                0 => {
+                    return Err(before_not_found(i, op, &text, &before));
                 }
-                if op.before.is_some() {
+                1 => {}
+                matches => {
                     return Err(err_op(
-                        DiagnosticCode::UnexpectedField,
+                        DiagnosticCode::BeforeAmbiguous,
                         i,
                         op.op,
                         Some(op.block_id.clone()),
                         Some(format!("ops[{i}].before")),
                         format!(
-                            "ops[{i}] (suggest) unexpected before (suggest must not include before/after)"
-                        ),
-                    ));
-                }
-                if op.after.is_some() {
-                    return Err(err_op(
-                        DiagnosticCode::UnexpectedField,
-                        i,
-                        op.op,
-                        Some(op.block_id.clone()),
-                        Some(format!("ops[{i}].after")),
-                        format!(
-                            "ops[{i}] (suggest) unexpected after (suggest must not include before/after)"
-                        ),
-                    ));
-                }
-                if op.text.is_some() || op.new_block_id.is_some() || op.kind_code.is_some() {
-                    return Err(err_op(
-                        DiagnosticCode::UnexpectedField,
-                        i,
-                        op.op,
-                        Some(op.block_id.clone()),
-                        Some(format!("ops[{i}].text")),
-                        format!(
-                            "ops[{i}] (suggest) unexpected insert_after fields (suggest is non-mutating; use insert_after instead)"
+                            "ops[{i}] (split_block) before substring is ambiguous in block '{}' (matches {matches} times); split anchors must be unique",
+                            op.block_id
                         ),
                     ));
                 }
-                let msg = op.message.as_deref().ok_or_else(|| {
-                    err_op(
-                        DiagnosticCode::MissingField,
-                        i,
-                        op.op,
-                        Some(op.block_id.clone()),
-                        Some(format!("ops[{i}].message")),
-                        format!("ops[{i}] (suggest) missing message"),
-                    )
-                })?;
-                if msg.trim().is_empty() {
-                    return Err(err_op(
-                        DiagnosticCode::MessageEmpty,
-                        i,
-                        op.op,
-                        Some(op.block_id.clone()),
-                        Some(format!("ops[{i}].message")),
-                        format!("ops[{i}] (suggest) message is empty"),
-                    ));
-                }
+            }
+            let (head, _) = split_at_anchor(&text, &before).unwrap_or_default();
+            if head.is_empty() {
+                return Err(err_op(
+                    DiagnosticCode::ContentEmpty,
+                    i,
+                    op.op,
+                    Some(op.block_id.clone()),
+                    Some(format!("ops[{i}].before")),
+                    format!(
+                        "ops[{i}] (split_block) would leave block '{}' empty; the anchor must not start the block",
+                        op.block_id
+                    ),
+                ));
+            }
+
+            let new_block_id = op.new_block_id.as_deref().ok_or_else(|| {
+                err_op(
+                    DiagnosticCode::MissingField,
+                    i,
+                    op.op,
+                    Some(op.block_id.clone()),
+                    Some(format!("ops[{i}].new_block_id")),
+                    format!("ops[{i}] (split_block) missing new_block_id"),
+                )
+            })?;
+            if new_block_id.trim().is_empty() {
+                return Err(err_op(
+                    DiagnosticCode::ContentEmpty,
+                    i,
+                    op.op,
+                    Some(op.block_id.clone()),
+                    Some(format!("ops[{i}].new_block_id")),
+                    format!("ops[{i}] (split_block) new_block_id is empty"),
+                ));
+            }
+            if doc.blocks.iter().any(|b| b.id == new_block_id) {
+                return Err(err_op(
+                    DiagnosticCode::DuplicateBlockId,
+                    i,
+                    op.op,
+                    Some(op.block_id.clone()),
+                    Some(format!("ops[{i}].new_block_id")),
+                    format!("ops[{i}] (split_block) new_block_id '{new_block_id}' already exists"),
+                ));
+            }
+            if let Some(kind_code) = op.kind_code {
+                enforce_reclassify(i, op, block.kind_code, kind_code, opts)?;
             }
         }
-    }
 
-    Ok(())
-}
+        OpType::MergeBlock => {
+            let unexpected = [
+                ("before", op.before.is_some()),
+                ("after", op.after.is_some()),
+                ("occurrence", op.occurrence.is_some()),
+                ("new_block_id", op.new_block_id.is_some()),
+                ("kind_code", op.kind_code.is_some()),
+                ("text", op.text.is_some()),
+                ("text_hash", op.text_hash.is_some()),
+                ("after_block_id", op.after_block_id.is_some()),
+                ("message", op.message.is_some()),
+            ];
+            if let Some((field, _)) = unexpected.iter().find(|(_, present)| *present) {
+                return Err(err_op(
+                    DiagnosticCode::UnexpectedField,
+                    i,
+                    op.op,
+                    Some(op.block_id.clone()),
+                    Some(format!("ops[{i}].{field}")),
+                    format!("ops[{i}] (merge_block) unexpected {field} (merge_block joins two whole blocks)"),
+                ));
+            }
 
-// -----------------------------------------------------------------------------
-// Helpers
-// -----------------------------------------------------------------------------
+            let next_id = op.next_block_id.as_deref().ok_or_else(|| {
+                err_op(
+                    DiagnosticCode::MissingField,
+                    i,
+                    op.op,
+                    Some(op.block_id.clone()),
+                    Some(format!("ops[{i}].next_block_id")),
+                    format!("ops[{i}] (merge_block) missing next_block_id"),
+                )
+            })?;
+            let Some(next) = doc.blocks.iter().find(|b| b.id == next_id) else {
+                return Err(err_op(
+                    DiagnosticCode::UnknownBlockId,
+                    i,
+                    op.op,
+                    Some(op.block_id.clone()),
+                    Some(format!("ops[{i}].next_block_id")),
+                    format!("ops[{i}] (merge_block) references unknown next_block_id '{next_id}'"),
+                ));
+            };
+            let pos = doc.blocks.iter().position(|b| b.id == op.block_id);
+            if pos.and_then(|p| doc.blocks.get(p + 1)).map(|b| b.id.as_str()) != Some(next_id) {
+                return Err(err_op(
+                    DiagnosticCode::BlocksNotAdjacent,
+                    i,
+                    op.op,
+                    Some(op.block_id.clone()),
+                    Some(format!("ops[{i}].next_block_id")),
+                    format!(
+                        "ops[{i}] (merge_block) next_block_id '{next_id}' does not directly follow block '{}'",
+                        op.block_id
+                    ),
+                ));
+            }
+            // The absorbed block is mutated too, so it is subject to the same policy.
+            enforce_kind_code(i, op.op, next_id, next.kind_code, opts)?;
+        }
 
-/// Count non-overlapping occurrences of `needle` in `haystack`.
-///
-/// This is used for ambiguity detection and occurrence range validation.
-fn count_non_overlapping(haystack: &str, needle: &str) -> usize {
-    // RFC-0001 §2.2: substring matching is performed over NFC-normalized strings.
-    let haystack = normalize_nfc(haystack);
-    let needle = normalize_nfc(needle);
+        OpType::Reclassify => {
+            let unexpected = [
+                ("before", op.before.is_some()),
+                ("after", op.after.is_some()),
+                ("occurrence", op.occurrence.is_some()),
+                ("new_block_id", op.new_block_id.is_some()),
+                ("text", op.text.is_some()),
+                ("text_hash", op.text_hash.is_some()),
+                ("after_block_id", op.after_block_id.is_some()),
+                ("next_block_id", op.next_block_id.is_some()),
+                ("message", op.message.is_some()),
+            ];
+            if let Some((field, _)) = unexpected.iter().find(|(_, present)| *present) {
+                return Err(err_op(
+                    DiagnosticCode::UnexpectedField,
+                    i,
+                    op.op,
+                    Some(op.block_id.clone()),
+                    Some(format!("ops[{i}].{field}")),
+                    format!("ops[{i}] (reclassify) unexpected {field} (reclassify only changes kind_code)"),
+                ));
+            }
 
-    if needle.is_empty() {
-        return 0;
-    }
+            let kind_code = op.kind_code.ok_or_else(|| {
+                err_op(
+                    DiagnosticCode::MissingField,
+                    i,
+                    op.op,
+                    Some(op.block_id.clone()),
+                    Some(format!("ops[{i}].kind_code")),
+                    format!("ops[{i}] (reclassify) missing kind_code"),
+                )
+            })?;
+            enforce_reclassify(i, op, block.kind_code, kind_code, opts)?;
+        }
+
+        OpType::Move => {
+            let unexpected = [
+                ("before", op.before.is_some()),
+                ("after", op.after.is_some()),
+                ("occurrence", op.occurrence.is_some()),
+                ("new_block_id", op.new_block_id.is_some()),
+                ("kind_code", op.kind_code.is_some()),
+                ("text", op.text.is_some()),
+                ("text_hash", op.text_hash.is_some()),
+                ("next_block_id", op.next_block_id.is_some()),
+                ("message", op.message.is_some()),
+            ];
+            if let Some((field, _)) = unexpected.iter().find(|(_, present)| *present) {
+                return Err(err_op(
+                    DiagnosticCode::UnexpectedField,
+                    i,
+                    op.op,
+                    Some(op.block_id.clone()),
+                    Some(format!("ops[{i}].{field}")),
+                    format!("ops[{i}] (move) unexpected {field} (move keeps the block's id, kind_code and text)"),
+                ));
+            }
 
-    let mut count = 0usize;
-    let mut start = 0usize;
-    while let Some(pos) = haystack[start..].find(&needle) {
-        count += 1;
-        start += pos + needle.len();
-        if start >= haystack.len() {
-            break;
+            let anchor = op.after_block_id.as_deref().ok_or_else(|| {
+                err_op(
+                    DiagnosticCode::MissingField,
+                    i,
+                    op.op,
+                    Some(op.block_id.clone()),
+                    Some(format!("ops[{i}].after_block_id")),
+                    format!("ops[{i}] (move) missing after_block_id"),
+                )
+            })?;
+            if !doc.blocks.iter().any(|b| b.id == anchor) {
+                return Err(err_op(
+                    DiagnosticCode::UnknownBlockId,
+                    i,
+                    op.op,
+                    Some(op.block_id.clone()),
+                    Some(format!("ops[{i}].after_block_id")),
+                    format!("ops[{i}] (move) references unknown after_block_id '{anchor}'"),
+                ));
+            }
+        }
+
+        OpType::DeleteBlock => {
+            let unexpected = [
+                ("before", op.before.is_some()),
+                ("after", op.after.is_some()),
+                ("occurrence", op.occurrence.is_some()),
+                ("new_block_id", op.new_block_id.is_some()),
+                ("kind_code", op.kind_code.is_some()),
+                ("text", op.text.is_some()),
+                ("after_block_id", op.after_block_id.is_some()),
+                ("next_block_id", op.next_block_id.is_some()),
+                ("message", op.message.is_some()),
+            ];
+            if let Some((field, _)) = unexpected.iter().find(|(_, present)| *present) {
+                return Err(err_op(
+                    DiagnosticCode::UnexpectedField,
+                    i,
+                    op.op,
+                    Some(op.block_id.clone()),
+                    Some(format!("ops[{i}].{field}")),
+                    format!("ops[{i}] (delete_block) unexpected {field} (delete_block removes the whole block)"),
+                ));
+            }
+
+            let expected = op.text_hash.as_deref().ok_or_else(|| {
+                err_op(
+                    DiagnosticCode::MissingField,
+                    i,
+                    op.op,
+                    Some(op.block_id.clone()),
+                    Some(format!("ops[{i}].text_hash")),
+                    format!("ops[{i}] (delete_block) missing text_hash"),
+                )
+            })?;
+            if expected.chars().count() < MIN_HASH_PREFIX_LEN {
+                return Err(err_op(
+                    DiagnosticCode::HashTooShort,
+                    i,
+                    op.op,
+                    Some(op.block_id.clone()),
+                    Some(format!("ops[{i}].text_hash")),
+                    format!(
+                        "ops[{i}] (delete_block) text_hash '{expected}' is too short (<{MIN_HASH_PREFIX_LEN} hex chars)"
+                    ),
+                ));
+            }
+
+            // Always checked against the recomputed hash, not the declared `text_hash`:
+            // removing a block must not rely on metadata that may be stale.
+            // An unregistered algorithm is already reported by the page-level checks.
+            if let Some(computed) = hash_canon_hex(doc_algo, &block.text)
+                && !hash_prefix_matches(expected, &computed)
+            {
+                return Err(err_op(
+                    DiagnosticCode::BlockHashMismatch,
+                    i,
+                    op.op,
+                    Some(op.block_id.clone()),
+                    Some(format!("ops[{i}].text_hash")),
+                    format!(
+                        "ops[{i}] (delete_block) text_hash '{expected}' does not match the current text of block '{}' (computed '{computed}')",
+                        op.block_id
+                    ),
+                ));
+            }
+        }
+
+        OpType::Suggest => {
+            if op.occurrence.is_some() {
+                return Err(err_op(
+                    DiagnosticCode::UnexpectedField,
+                    i,
+                    op.op,
+                    Some(op.block_id.clone()),
+                    Some(format!("ops[{i}].occurrence")),
+                    format!(
+                        "ops[{i}] (suggest) unexpected occurrence (only valid for delete)"
+                    ),
+                ));
+            }
+            if op.before.is_some() {
+                return Err(err_op(
+                    DiagnosticCode::UnexpectedField,
+                    i,
+                    op.op,
+                    Some(op.block_id.clone()),
+                    Some(format!("ops[{i}].before")),
+                    format!(
+                        "ops[{i}] (suggest) unexpected before (suggest must not include before/after)"
+                    ),
+                ));
+            }
+            if op.after.is_some() {
+                return Err(err_op(
+                    DiagnosticCode::UnexpectedField,
+                    i,
+                    op.op,
+                    Some(op.block_id.clone()),
+                    Some(format!("ops[{i}].after")),
+                    format!(
+                        "ops[{i}] (suggest) unexpected after (suggest must not include before/after)"
+                    ),
+                ));
+            }
+            if op.text.is_some() || op.new_block_id.is_some() || op.kind_code.is_some() {
+                return Err(err_op(
+                    DiagnosticCode::UnexpectedField,
+                    i,
+                    op.op,
+                    Some(op.block_id.clone()),
+                    Some(format!("ops[{i}].text")),
+                    format!(
+                        "ops[{i}] (suggest) unexpected insert fields (suggest is non-mutating; use insert_after or insert_before instead)"
+                    ),
+                ));
+            }
+            let msg = op.message.as_deref().ok_or_else(|| {
+                err_op(
+                    DiagnosticCode::MissingField,
+                    i,
+                    op.op,
+                    Some(op.block_id.clone()),
+                    Some(format!("ops[{i}].message")),
+                    format!("ops[{i}] (suggest) missing message"),
+                )
+            })?;
+            if msg.trim().is_empty() {
+                return Err(err_op(
+                    DiagnosticCode::MessageEmpty,
+                    i,
+                    op.op,
+                    Some(op.block_id.clone()),
+                    Some(format!("ops[{i}].message")),
+                    format!("ops[{i}] (suggest) message is empty"),
+                ));
+            }
         }
     }
-    count
+
+    Ok(())
 }
 
+// -----------------------------------------------------------------------------
+// Helpers
+// -----------------------------------------------------------------------------
+
 /// Validate `before` safety constraints and return a structured diagnostic on failure.
 fn guard_before_diag(
     op_index: usize,
@@ -700,6 +1544,75 @@ fn guard_before_diag(
 }
 
 
+fn verify_block_hash(
+    i: usize,
+    op: OpType,
+    field: &str,
+    block: &bdir_core::model::Block,
+    algo: &str,
+) -> Result<(), ValidationError> {
+    if block.text_hash.chars().count() < MIN_HASH_PREFIX_LEN {
+        return Err(err_op(
+            DiagnosticCode::HashTooShort,
+            i,
+            op,
+            Some(block.id.clone()),
+            Some(format!("ops[{i}].{field}")),
+            format!(
+                "ops[{i}] targets block '{}' whose text_hash '{}' is too short (<{MIN_HASH_PREFIX_LEN} hex chars)",
+                block.id, block.text_hash
+            ),
+        ));
+    }
+
+    // An unregistered algorithm is already reported by the page-level checks.
+    let Some(computed) = hash_canon_hex(algo, &block.text) else {
+        return Ok(());
+    };
+    if !hash_prefix_matches(&block.text_hash, &computed) {
+        return Err(err_op(
+            DiagnosticCode::BlockHashMismatch,
+            i,
+            op,
+            Some(block.id.clone()),
+            Some(format!("ops[{i}].{field}")),
+            format!(
+                "ops[{i}] targets block '{}' whose text_hash '{}' does not match its text (computed '{computed}')",
+                block.id, block.text_hash
+            ),
+        ));
+    }
+    Ok(())
+}
+
+/// Reject hash values shorter than the RFC-0001 §5.1.4 minimum prefix length.
+fn guard_hash_len(path: &str, what: &str, value: &str) -> Result<(), ValidationError> {
+    if value.chars().count() < MIN_HASH_PREFIX_LEN {
+        return Err(err_root(
+            DiagnosticCode::HashTooShort,
+            path,
+            format!(
+                "{what} '{value}' is too short (<{MIN_HASH_PREFIX_LEN} hex chars); truncated hashes must keep at least {MIN_HASH_PREFIX_LEN}"
+            ),
+        ));
+    }
+    Ok(())
+}
+
+/// `before_not_found` diagnostic, with the nearest candidate in the block attached.
+fn before_not_found(i: usize, op: &PatchOpV1, text: &str, before: &str) -> ValidationError {
+    let mut err = err_op(
+        DiagnosticCode::BeforeNotFound,
+        i,
+        op.op,
+        Some(op.block_id.clone()),
+        Some(format!("ops[{i}].before")),
+        format!("ops[{i}] ({}) before substring not found in block '{}'", op.op.as_str(), op.block_id),
+    );
+    err.diagnostics[0].closest_match = closest_match(text, before);
+    err
+}
+
 fn err_root(code: DiagnosticCode, path: &str, message: String) -> ValidationError {
     ValidationError::single(ValidationDiagnostic {
         code,
@@ -708,6 +1621,7 @@ fn err_root(code: DiagnosticCode, path: &str, message: String) -> ValidationErro
         op: None,
         block_id: None,
         message,
+        closest_match: None,
     })
 }
 
@@ -726,6 +1640,7 @@ fn err_op(
         op: Some(op),
         block_id,
         message,
+        closest_match: None,
     })
 }
 // -----------------------------------------------------------------------------
@@ -769,6 +1684,7 @@ pub fn validate_patch_against_edit_packet_with_telemetry(
         },
         input_chars,
         output_chars: None,
+        tolerant_match_ops: PatchTelemetry::tolerant_ops(&tolerant_match_diagnostics_against_edit_packet(packet, patch, &opts)),
         error_code: res
             .as_ref()
             .err()
@@ -820,6 +1736,7 @@ pub fn validate_patch_with_telemetry(
         },
         input_chars,
         output_chars: None,
+        tolerant_match_ops: PatchTelemetry::tolerant_ops(&tolerant_match_diagnostics(doc, patch, &opts)),
         error_code: res
             .as_ref()
             .err()
@@ -881,7 +1798,12 @@ pub fn validate_patch_against_edit_packet_with_diagnostics(
         opts.expected_page_hash = Some(packet.h.clone());
     }
 
-    let doc = Document {
+    validate_patch_with_diagnostics(&packet_document(packet), patch, opts)
+}
+
+/// View an Edit Packet as a Document (tuple fields and parents copied as-is).
+pub(crate) fn packet_document(packet: &EditPacketV1) -> Document {
+    Document {
         page_hash: packet.h.clone(),
         hash_algorithm: packet.ha.clone(),
         blocks: packet
@@ -892,9 +1814,50 @@ pub fn validate_patch_against_edit_packet_with_diagnostics(
                 kind_code: t.1,
                 text_hash: t.2.clone(),
                 text: t.3.clone(),
+                parent: packet.p.get(&t.0).cloned(),
             })
             .collect(),
-    };
+    }
+}
+
+/// Report every op whose `before` only matches under `MatchMode::Tolerant`.
+///
+/// Returns one `tolerant_match` diagnostic per such op, in `ops` order, so auditors can
+/// see which edits did not match verbatim. Empty unless `opts.match_mode` is tolerant.
+pub fn tolerant_match_diagnostics(doc: &Document, patch: &PatchV1, opts: &ValidateOptions) -> Vec<ValidationDiagnostic> {
+    if opts.match_mode != MatchMode::Tolerant {
+        return Vec::new();
+    }
+    patch
+        .ops
+        .iter()
+        .enumerate()
+        .filter(|(_, op)| matches!(op.op, OpType::Replace | OpType::Delete | OpType::SplitBlock))
+        .filter_map(|(i, op)| {
+            let block = doc.blocks.iter().find(|b| b.id == op.block_id)?;
+            let before = op.before.as_deref()?;
+            relies_on_folding(&block.text, before).then(|| ValidationDiagnostic {
+                code: DiagnosticCode::TolerantMatch,
+                path: Some(format!("ops[{i}].before")),
+                op_index: Some(i),
+                op: Some(op.op),
+                block_id: Some(op.block_id.clone()),
+                message: format!(
+                    "ops[{i}] ({}) before matched block '{}' only after whitespace/typography normalization",
+                    op.op.as_str(),
+                    op.block_id
+                ),
+                closest_match: None,
+            })
+        })
+        .collect()
+}
 
-    validate_patch_with_diagnostics(&doc, patch, opts)
+/// Edit Packet variant of `tolerant_match_diagnostics`.
+pub fn tolerant_match_diagnostics_against_edit_packet(
+    packet: &EditPacketV1,
+    patch: &PatchV1,
+    opts: &ValidateOptions,
+) -> Vec<ValidationDiagnostic> {
+    tolerant_match_diagnostics(&packet_document(packet), patch, opts)
 }
@@ -60,7 +60,7 @@ fn golden_replace_without_occurrence_is_rejected_when_ambiguous() {
 
     let patch = bind_patch_to_edit_packet(patch, &packet);
     let err = apply_patch_against_edit_packet(&packet, &patch).unwrap_err();
-    assert!(err.contains("ambiguous"));
+    assert!(err.to_string().contains("ambiguous"));
 }
 
 #[test]
@@ -94,7 +94,7 @@ fn golden_delete_without_occurrence_is_rejected_when_ambiguous() {
 
     let patch = bind_patch_to_edit_packet(patch, &packet);
     let err = apply_patch_against_edit_packet(&packet, &patch).unwrap_err();
-    assert!(err.contains("ambiguous"));
+    assert!(err.to_string().contains("ambiguous"));
 }
 
 #[test]
@@ -133,6 +133,84 @@ fn golden_suggest_is_non_mutating() {
     assert_eq!(out.b[1].3, "This is the second paragraph.");
 }
 
+#[test]
+fn golden_split_block_moves_text_from_anchor_into_new_block() {
+    let packet = baseline_packet();
+
+    let patch: PatchV1 = serde_json::from_value(json!({
+        "v": 1,
+        "ops": [
+            { "op": "split_block", "block_id": "p2", "before": "second paragraph.", "new_block_id": "p2_split" }
+        ]
+    })).unwrap();
+
+    let patch = bind_patch_to_edit_packet(patch, &packet);
+    let out = apply_patch_against_edit_packet(&packet, &patch).unwrap();
+
+    // The split block keeps the text before the anchor (trailing whitespace trimmed);
+    // the new block follows it with the same kind_code and the rest of the text.
+    assert_eq!(out.b.len(), 3);
+    assert_eq!(out.b[1].0, "p2");
+    assert_eq!(out.b[1].3, "This is the");
+    assert_eq!(out.b[2].0, "p2_split");
+    assert_eq!(out.b[2].1, 2);
+    assert_eq!(out.b[2].3, "second paragraph.");
+}
+
+#[test]
+fn golden_split_block_is_rejected_when_anchor_is_ambiguous() {
+    let packet = baseline_packet();
+
+    let patch: PatchV1 = serde_json::from_value(json!({
+        "v": 1,
+        "ops": [
+            { "op": "split_block", "block_id": "p1", "before": "This is teh", "new_block_id": "p1_split" }
+        ]
+    })).unwrap();
+
+    let patch = bind_patch_to_edit_packet(patch, &packet);
+    let err = apply_patch_against_edit_packet(&packet, &patch).unwrap_err();
+    assert!(err.to_string().contains("ambiguous"));
+}
+
+#[test]
+fn golden_merge_block_joins_next_block_into_first_with_one_space() {
+    let packet = baseline_packet();
+
+    let patch: PatchV1 = serde_json::from_value(json!({
+        "v": 1,
+        "ops": [
+            { "op": "merge_block", "block_id": "p1", "next_block_id": "p2" }
+        ]
+    })).unwrap();
+
+    let patch = bind_patch_to_edit_packet(patch, &packet);
+    let out = apply_patch_against_edit_packet(&packet, &patch).unwrap();
+
+    assert_eq!(out.b.len(), 1);
+    assert_eq!(out.b[0].0, "p1");
+    assert_eq!(
+        out.b[0].3,
+        "This is teh first paragraph. This is teh first paragraph. This is the second paragraph."
+    );
+}
+
+#[test]
+fn golden_merge_block_is_rejected_when_blocks_are_not_adjacent() {
+    let packet = baseline_packet();
+
+    let patch: PatchV1 = serde_json::from_value(json!({
+        "v": 1,
+        "ops": [
+            { "op": "merge_block", "block_id": "p2", "next_block_id": "p1" }
+        ]
+    })).unwrap();
+
+    let patch = bind_patch_to_edit_packet(patch, &packet);
+    let err = apply_patch_against_edit_packet(&packet, &patch).unwrap_err();
+    assert!(err.to_string().contains("does not directly follow"));
+}
+
 #[test]
 fn reject_unknown_block_id() {
     let packet = baseline_packet();
@@ -146,7 +224,7 @@ fn reject_unknown_block_id() {
 
     let patch = bind_patch_to_edit_packet(patch, &packet);
     let err = apply_patch_against_edit_packet(&packet, &patch).unwrap_err();
-    assert!(err.contains("references unknown block_id"));
+    assert!(err.to_string().contains("references unknown block_id"));
 }
 
 #[test]
@@ -162,7 +240,7 @@ fn reject_before_too_short() {
 
     let patch = bind_patch_to_edit_packet(patch, &packet);
     let err = apply_patch_against_edit_packet(&packet, &patch).unwrap_err();
-    assert!(err.contains("before is too short"));
+    assert!(err.to_string().contains("before is too short"));
 }
 
 fn bind_patch_to_edit_packet(mut patch: PatchV1, ep: &EditPacketV1) -> PatchV1 {
@@ -0,0 +1,72 @@
+use serde_json::json;
+
+use bdir_core::model::Document;
+use bdir_patch::{
+    apply_patch_against_document,
+    apply_patch_against_document_with_options,
+    apply_patch_against_document_with_telemetry,
+    ApplyError,
+    DiagnosticCode,
+    PatchV1,
+    ValidateOptions,
+};
+
+fn doc() -> Document {
+    let mut doc: Document = serde_json::from_value(json!({
+        "page_hash": "",
+        "hash_algorithm": "xxh64",
+        "blocks": [
+            {"id": "p1", "kind_code": 2, "text_hash": "", "text": "This is teh first paragraph."}
+        ]
+    }))
+    .unwrap();
+    doc.recompute_hashes();
+    doc
+}
+
+fn bound_patch(doc: &Document, before: &str, after: &str) -> PatchV1 {
+    let mut patch: PatchV1 = serde_json::from_value(json!({
+        "v": 1,
+        "ops": [{"op": "replace", "block_id": "p1", "before": before, "after": after}]
+    }))
+    .unwrap();
+    patch.h = Some(doc.page_hash.clone());
+    patch.ha = Some(doc.hash_algorithm.clone());
+    patch
+}
+
+#[test]
+fn validation_failure_keeps_validator_diagnostics() {
+    let doc = doc();
+    let patch = bound_patch(&doc, "teh", "the");
+
+    let err = apply_patch_against_document(&doc, &patch).unwrap_err();
+    let ApplyError::Validation(ref v) = err else {
+        panic!("expected a validation error, got {err:?}");
+    };
+    assert_eq!(v.diagnostics[0].code, DiagnosticCode::BeforeTooShort);
+    assert_eq!(err.code(), DiagnosticCode::BeforeTooShort);
+    assert_eq!(err.diagnostics(), *v);
+    assert_eq!(err.to_string(), "ops[0] before is too short (<8 chars); likely ambiguous");
+}
+
+#[test]
+fn document_apply_honours_relaxed_options() {
+    let doc = doc();
+    let patch = bound_patch(&doc, "teh", "the");
+    let opts = ValidateOptions { min_before_len: 3, ..ValidateOptions::default() };
+
+    let updated = apply_patch_against_document_with_options(&doc, &patch, opts).unwrap();
+    assert_eq!(updated.blocks[0].text, "This is the first paragraph.");
+}
+
+#[test]
+fn telemetry_error_code_reports_the_diagnostic_code() {
+    let doc = doc();
+    let patch = bound_patch(&doc, "not present anywhere", "x");
+
+    let (res, tel) = apply_patch_against_document_with_telemetry(&doc, &patch, ValidateOptions::default());
+    assert!(res.is_err());
+    assert!(!tel.ok);
+    assert_eq!(tel.error_code.as_deref(), Some("beforenotfound"));
+}
@@ -0,0 +1,139 @@
+mod util;
+
+use serde_json::json;
+
+use bdir_core::ids::IdStrategy;
+use bdir_core::model::Document;
+use bdir_patch::{
+    apply_patch_against_document,
+    apply_patch_against_document_with_options,
+    apply_patch_against_edit_packet_with_options,
+    assign_new_block_ids,
+    validate_patch_with_diagnostics,
+    DiagnosticCode,
+    EditPacketV1,
+    PatchV1,
+    ValidateOptions,
+};
+
+/// t1 > (p1, h1 > p2), b1
+fn sectioned_doc() -> Document {
+    let mut doc: Document = serde_json::from_value(json!({
+        "hash_algorithm": "sha256",
+        "blocks": [
+            { "id": "t1", "kind_code": 0, "text": "Guide" },
+            { "id": "p1", "kind_code": 2, "text": "Intro paragraph.", "parent": "t1" },
+            { "id": "h1", "kind_code": 1, "text": "Usage", "parent": "t1" },
+            { "id": "p2", "kind_code": 2, "text": "Run the tool. Then check the output.", "parent": "h1" },
+            { "id": "b1", "kind_code": 20, "text": "Home > Guide" }
+        ]
+    }))
+    .unwrap();
+    doc.recompute_hashes();
+    doc
+}
+
+fn patch(ops: serde_json::Value) -> PatchV1 {
+    serde_json::from_value(json!({ "v": 1, "ops": ops })).unwrap()
+}
+
+fn assigning(strategy: IdStrategy) -> ValidateOptions {
+    ValidateOptions { assign_new_block_ids: Some(strategy), ..ValidateOptions::default() }
+}
+
+fn ids(doc: &Document) -> Vec<&str> {
+    doc.blocks.iter().map(|b| b.id.as_str()).collect()
+}
+
+#[test]
+fn missing_new_block_id_is_rejected_unless_the_engine_assigns_ids() {
+    let doc = util::load_doc();
+    let p = util::bound(
+        &doc,
+        json!([{ "op": "insert_after", "block_id": "p1", "kind_code": 2, "text": "Added paragraph." }]),
+    );
+
+    let err = validate_patch_with_diagnostics(&doc, &p, ValidateOptions::default()).unwrap_err();
+    assert_eq!(err.diagnostics[0].code, DiagnosticCode::MissingField);
+    assert!(apply_patch_against_document(&doc, &p).is_err());
+
+    validate_patch_with_diagnostics(&doc, &p, assigning(IdStrategy::Positional)).unwrap();
+    let out = apply_patch_against_document_with_options(&doc, &p, assigning(IdStrategy::Positional)).unwrap();
+    assert_eq!(ids(&out), vec!["t1", "p1", "p2", "b1"]);
+}
+
+#[test]
+fn positional_ids_skip_existing_and_explicit_ids() {
+    let doc = sectioned_doc();
+    let p = util::bound(&doc, json!([
+        { "op": "insert_before", "block_id": "p1", "kind_code": 2, "text": "Before intro." },
+        { "op": "insert_after", "block_id": "p2", "new_block_id": "p3", "kind_code": 2, "text": "Explicit." },
+        { "op": "insert_after", "block_id": "h1", "kind_code": 1, "text": "Options" },
+        { "op": "split_block", "block_id": "p2", "before": "Then check" }
+    ]));
+
+    let assigned = assign_new_block_ids(&doc, &p, IdStrategy::Positional);
+    let new_ids: Vec<_> = assigned.ops.iter().map(|op| op.new_block_id.as_deref().unwrap()).collect();
+    assert_eq!(new_ids, vec!["p4", "p3", "h2", "p5"]);
+
+    let out = apply_patch_against_document_with_options(&doc, &p, assigning(IdStrategy::Positional)).unwrap();
+    assert_eq!(ids(&out), vec!["t1", "p4", "p1", "h1", "h2", "p2", "p5", "p3", "b1"]);
+}
+
+#[test]
+fn hierarchical_ids_are_scoped_to_the_anchor_parent() {
+    let doc = sectioned_doc();
+    let p = util::bound(&doc, json!([
+        { "op": "insert_after", "block_id": "p2", "kind_code": 2, "text": "More usage." },
+        { "op": "insert_after", "block_id": "p1", "kind_code": 2, "text": "More intro." },
+        { "op": "insert_after", "block_id": "b1", "kind_code": 21, "text": "Docs | Blog" }
+    ]));
+
+    let out = apply_patch_against_document_with_options(&doc, &p, assigning(IdStrategy::Hierarchical)).unwrap();
+    let rows: Vec<(&str, Option<&str>)> = out.blocks.iter().map(|b| (b.id.as_str(), b.parent.as_deref())).collect();
+    assert_eq!(
+        rows,
+        vec![
+            ("t1", None),
+            ("p1", Some("t1")),
+            ("t1.p1", Some("t1")),
+            ("h1", Some("t1")),
+            ("p2", Some("h1")),
+            ("h1.p1", Some("h1")),
+            ("b1", None),
+            ("nav1", None),
+        ]
+    );
+}
+
+#[test]
+fn content_addressed_ids_are_deterministic_and_unique() {
+    let doc = sectioned_doc();
+    let p = util::bound(&doc, json!([
+        { "op": "insert_after", "block_id": "p1", "kind_code": 2, "text": "Note." },
+        { "op": "insert_after", "block_id": "p1", "kind_code": 2, "text": "Note." },
+        { "op": "insert_after", "block_id": "p2", "kind_code": 2, "text": "Note." }
+    ]));
+
+    let first = assign_new_block_ids(&doc, &p, IdStrategy::ContentAddressed);
+    assert_eq!(first, assign_new_block_ids(&doc, &p, IdStrategy::ContentAddressed));
+    let new_ids: Vec<&str> = first.ops.iter().map(|op| op.new_block_id.as_deref().unwrap()).collect();
+    assert!(new_ids[0].starts_with("p-") && new_ids[0].len() == 10);
+    assert_eq!(new_ids[1], format!("{}-2", new_ids[0]));
+    // Keyed by the anchor: the same text elsewhere hashes differently.
+    assert_ne!(&new_ids[2][..10], new_ids[0]);
+
+    let out = apply_patch_against_document_with_options(&doc, &p, assigning(IdStrategy::ContentAddressed)).unwrap();
+    assert_eq!(out.blocks.len(), 8);
+}
+
+#[test]
+fn edit_packet_apply_assigns_ids_too() {
+    let path = util::workspace_root().join("examples").join("edit-packet.json");
+    let packet: EditPacketV1 = serde_json::from_str(&std::fs::read_to_string(path).unwrap()).unwrap();
+    let p = patch(json!([{ "op": "insert_after", "block_id": "b1", "kind_code": 2, "text": "Closing paragraph." }]));
+
+    let out = apply_patch_against_edit_packet_with_options(&packet, &p, assigning(IdStrategy::Positional)).unwrap();
+    let last = out.b.last().unwrap();
+    assert_eq!((last.0.as_str(), last.3.as_str()), ("p2", "Closing paragraph."));
+}
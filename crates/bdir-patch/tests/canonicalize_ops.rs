@@ -19,6 +19,11 @@ fn op(op: OpType, block_id: &str) -> PatchOpV1 {
         new_block_id: None,
         kind_code: None,
         text: None,
+        text_hash: None,
+        after_block_id: None,
+        context_before: None,
+        context_after: None,
+        next_block_id: None,
         message: None,
         severity: None,
     }
@@ -27,6 +32,7 @@ fn op(op: OpType, block_id: &str) -> PatchOpV1 {
 #[test]
 fn canonicalize_falls_back_to_blockid_and_op_rank() {
     let mut patch = mk_patch(vec![
+        op(OpType::DeleteBlock, "a"),
         op(OpType::Suggest, "b"),
         op(OpType::Delete, "a"),
         op(OpType::Replace, "a"),
@@ -42,11 +48,12 @@ fn canonicalize_falls_back_to_blockid_and_op_rank() {
         .map(|o| (o.op, o.block_id.clone()))
         .collect();
 
-    // block_id asc, then delete, replace, insert_after, suggest
+    // block_id asc, then delete, replace, insert_after, suggest, delete_block
     let expect = vec![
         (OpType::Delete, "a".to_string()),
         (OpType::Replace, "a".to_string()),
         (OpType::InsertAfter, "a".to_string()),
+        (OpType::DeleteBlock, "a".to_string()),
         (OpType::Delete, "b".to_string()),
         (OpType::Suggest, "b".to_string()),
     ];
@@ -65,6 +72,7 @@ fn canonicalize_prefers_edit_packet_block_order() {
             ("p2".to_string(), 2, "h2".to_string(), "two".to_string()),
             ("p1".to_string(), 2, "h1".to_string(), "one".to_string()),
         ],
+        p: Default::default(),
     };
 
     let mut patch = mk_patch(vec![
@@ -94,3 +102,20 @@ fn canonicalize_orders_delete_occurrence_first_then_all() {
     assert_eq!(patch.ops[0].occurrence, Some(Occurrence::Legacy(DeleteOccurrence::First)));
     assert_eq!(patch.ops[1].occurrence, Some(Occurrence::Legacy(DeleteOccurrence::All)));
 }
+
+#[test]
+fn canonicalize_puts_moves_first_in_their_original_order() {
+    let mut m1 = op(OpType::Move, "p2");
+    m1.after_block_id = Some("p3".to_string());
+    let mut m2 = op(OpType::Move, "p1");
+    m2.after_block_id = Some("p2".to_string());
+
+    let mut patch = mk_patch(vec![op(OpType::Replace, "p0"), m1, op(OpType::Delete, "p1"), m2]);
+    canonicalize_patch_ops(&mut patch);
+
+    let order: Vec<(OpType, &str)> = patch.ops.iter().map(|o| (o.op, o.block_id.as_str())).collect();
+    assert_eq!(
+        order,
+        vec![(OpType::Move, "p2"), (OpType::Move, "p1"), (OpType::Replace, "p0"), (OpType::Delete, "p1")]
+    );
+}
@@ -0,0 +1,62 @@
+mod util;
+
+use serde_json::json;
+
+use bdir_patch::{
+    ClosestMatch,
+    DiagnosticCode,
+};
+
+fn hint(text: &str, offset: usize, distance: usize) -> Option<ClosestMatch> {
+    Some(ClosestMatch { text: text.to_string(), offset, distance })
+}
+
+#[test]
+fn curly_quotes_double_spaces_and_casing_point_at_the_block_text() {
+    let doc = util::doc_with_p1("She said \u{201c}hello there\u{201d} and left  the room. The End.");
+
+    let op = json!({ "op": "replace", "block_id": "p1", "before": "\"hello there\"", "after": "hi" });
+    let diag = util::first_diagnostic(&doc, json!([op]));
+    assert_eq!(diag.code, DiagnosticCode::BeforeNotFound);
+    assert_eq!(diag.message, "ops[0] (replace) before substring not found in block 'p1'");
+    assert_eq!(diag.closest_match, hint("\u{201c}hello there\u{201d}", 9, 2));
+
+    let diag = util::first_diagnostic(&doc, json!([{ "op": "delete", "block_id": "p1", "before": "left the room" }]));
+    assert_eq!(diag.closest_match, hint("left  the room", 27, 1));
+
+    let diag = util::first_diagnostic(&doc, json!([{ "op": "delete", "block_id": "p1", "before": "the end." }]));
+    assert_eq!(diag.closest_match, hint("The End.", 43, 2));
+}
+
+#[test]
+fn split_block_gets_a_hint_too() {
+    let doc = util::doc_with_p1("This is an example paragraph with a typo teh.");
+
+    let diag = util::first_diagnostic(
+        &doc,
+        json!([{ "op": "split_block", "block_id": "p1", "before": "paragraf with", "new_block_id": "p1b" }]),
+    );
+    assert_eq!(diag.code, DiagnosticCode::BeforeNotFound);
+    assert_eq!(diag.closest_match, hint("paragraph with", 19, 2));
+}
+
+#[test]
+fn unrelated_before_has_no_hint() {
+    let doc = util::doc_with_p1("This is an example paragraph with a typo teh.");
+
+    let diag = util::first_diagnostic(&doc, json!([{ "op": "delete", "block_id": "p1", "before": "zzzzqqqqxxxx" }]));
+    assert_eq!(diag.code, DiagnosticCode::BeforeNotFound);
+    assert_eq!(diag.closest_match, None);
+    assert!(serde_json::to_value(&diag).unwrap().get("closest_match").is_none());
+}
+
+#[test]
+fn hint_serializes_as_structured_fields() {
+    let doc = util::doc_with_p1("This is an example paragraph with a typo teh.");
+
+    let diag = util::first_diagnostic(&doc, json!([{ "op": "delete", "block_id": "p1", "before": "a typo the." }]));
+    assert_eq!(
+        serde_json::to_value(&diag).unwrap()["closest_match"],
+        json!({ "text": "a typo teh.", "offset": 34, "distance": 2 })
+    );
+}
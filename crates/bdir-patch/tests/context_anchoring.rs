@@ -0,0 +1,186 @@
+mod util;
+
+use serde_json::json;
+
+use bdir_editpacket::convert::from_document;
+use bdir_patch::{
+    apply_patch_against_document,
+    apply_patch_against_edit_packet,
+    merge_patches,
+    rebase_patch,
+    DiagnosticCode,
+    PatchV1,
+    RebaseConflictReason,
+    ValidationDiagnostic,
+};
+
+const CATS: &str = "First the tabby cat sat. Then the tabby cat ran. Finally the tabby cat slept.";
+
+#[test]
+fn context_before_selects_one_of_several_matches() {
+    let doc = util::doc_with_p1(CATS);
+    let patch = util::bound(
+        &doc,
+        json!([{ "op": "replace", "block_id": "p1", "before": "tabby cat", "after": "old dog", "context_before": "Then the " }]),
+    );
+
+    let out = apply_patch_against_document(&doc, &patch).unwrap();
+    assert_eq!(
+        out.blocks[1].text,
+        "First the tabby cat sat. Then the old dog ran. Finally the tabby cat slept."
+    );
+}
+
+#[test]
+fn context_after_selects_the_match_to_delete_in_an_edit_packet() {
+    let doc = util::doc_with_p1(CATS);
+    let packet = from_document(&doc, None);
+    let patch: PatchV1 = serde_json::from_value(json!({
+        "v": 1,
+        "h": packet.h,
+        "ha": packet.ha,
+        "ops": [{ "op": "delete", "block_id": "p1", "before": "the tabby ", "contextAfter": "cat slept" }]
+    }))
+    .unwrap();
+
+    let out = apply_patch_against_edit_packet(&packet, &patch).unwrap();
+    assert_eq!(out.b[1].3, "First the tabby cat sat. Then the tabby cat ran. Finally cat slept.");
+}
+
+#[test]
+fn both_anchors_must_hold_for_the_same_match() {
+    let doc = util::doc_with_p1(CATS);
+    let patch = util::bound(
+        &doc,
+        json!([{
+            "op": "replace", "block_id": "p1", "before": "tabby cat", "after": "cat",
+            "context_before": "the ", "context_after": " sat"
+        }]),
+    );
+
+    let out = apply_patch_against_document(&doc, &patch).unwrap();
+    assert_eq!(out.blocks[1].text, "First the cat sat. Then the tabby cat ran. Finally the tabby cat slept.");
+}
+
+#[test]
+fn context_that_selects_no_match_or_several_is_rejected() {
+    let doc = util::doc_with_p1(CATS);
+
+    let ValidationDiagnostic { code, path, message, .. } = util::first_diagnostic(
+        &doc,
+        json!([{ "op": "replace", "block_id": "p1", "before": "tabby cat", "after": "x", "context_before": "Later the " }]),
+    );
+    assert_eq!(code, DiagnosticCode::ContextNotFound);
+    assert_eq!(path.as_deref(), Some("ops[0].context_before"));
+    assert_eq!(message, "ops[0] (replace) no match of before in block 'p1' has the given context");
+
+    let ValidationDiagnostic { code, path, message, .. } = util::first_diagnostic(
+        &doc,
+        json!([{ "op": "delete", "block_id": "p1", "before": "tabby cat", "context_before": "the " }]),
+    );
+    assert_eq!(code, DiagnosticCode::ContextAmbiguous);
+    assert_eq!(path.as_deref(), Some("ops[0].context_before"));
+    assert_eq!(
+        message,
+        "ops[0] (delete) context is ambiguous in block 'p1' (3 matches of before have it); widen context_before/context_after"
+    );
+}
+
+#[test]
+fn context_is_exclusive_with_occurrence_and_limited_to_replace_and_delete() {
+    let doc = util::doc_with_p1(CATS);
+
+    let ValidationDiagnostic { code, path, .. } = util::first_diagnostic(
+        &doc,
+        json!([{
+            "op": "replace", "block_id": "p1", "before": "tabby cat", "after": "x",
+            "occurrence": 2, "context_before": "Then the "
+        }]),
+    );
+    assert_eq!(code, DiagnosticCode::UnexpectedField);
+    assert_eq!(path.as_deref(), Some("ops[0].occurrence"));
+
+    let ValidationDiagnostic { code, path, message, .. } = util::first_diagnostic(
+        &doc,
+        json!([{ "op": "insert_after", "block_id": "p1", "new_block_id": "p2", "kind_code": 2, "text": "New", "context_after": "x" }]),
+    );
+    assert_eq!(code, DiagnosticCode::UnexpectedField);
+    assert_eq!(path.as_deref(), Some("ops[0].context_after"));
+    assert_eq!(
+        message,
+        "ops[0] (insert_after) unexpected context_after (context is only valid for replace and delete)"
+    );
+}
+
+#[test]
+fn context_fields_serialize_in_snake_case_and_accept_camel_case() {
+    let doc = util::doc_with_p1(CATS);
+    let patch = util::bound(
+        &doc,
+        json!([{ "op": "replace", "block_id": "p1", "before": "tabby cat", "after": "x", "contextBefore": "Then the " }]),
+    );
+    assert_eq!(patch.ops[0].context_before.as_deref(), Some("Then the "));
+
+    let value = serde_json::to_value(&patch.ops[0]).unwrap();
+    assert_eq!(value["context_before"], "Then the ");
+    assert!(value.get("context_after").is_none());
+}
+
+#[test]
+fn rebase_keeps_a_context_anchored_op_when_match_counts_shift() {
+    let base = util::doc_with_p1(CATS);
+    let new = util::doc_with_p1(&format!("A tabby cat woke. {CATS}"));
+    let patch = util::bound(
+        &base,
+        json!([
+            { "op": "replace", "block_id": "p1", "before": "tabby cat", "after": "old dog", "context_before": "Then the " },
+            { "op": "delete", "block_id": "p1", "before": "tabby cat", "occurrence": 3 }
+        ]),
+    );
+
+    let report = rebase_patch(&base, &new, &patch).unwrap();
+    assert_eq!(report.conflicts.len(), 1);
+    assert_eq!(report.conflicts[0].op_index, 1);
+    assert_eq!(report.conflicts[0].reason, RebaseConflictReason::OccurrenceShifted);
+
+    let out = apply_patch_against_document(&new, &report.patch).unwrap();
+    assert_eq!(
+        out.blocks[1].text,
+        "A tabby cat woke. First the tabby cat sat. Then the old dog ran. Finally the tabby cat slept."
+    );
+}
+
+#[test]
+fn rebase_reports_context_that_no_longer_selects_one_match() {
+    let base = util::doc_with_p1(CATS);
+    let new = util::doc_with_p1("First the tabby cat sat. Later the tabby cat ran.");
+    let patch = util::bound(
+        &base,
+        json!([{ "op": "replace", "block_id": "p1", "before": "tabby cat", "after": "old dog", "context_before": "Then the " }]),
+    );
+
+    let report = rebase_patch(&base, &new, &patch).unwrap();
+    assert_eq!(report.conflicts.len(), 1);
+    assert_eq!(report.conflicts[0].reason, RebaseConflictReason::BeforeNotFound);
+}
+
+#[test]
+fn merge_uses_the_match_selected_by_context() {
+    let doc = util::doc_with_p1(CATS);
+    let packet = from_document(&doc, None);
+    let second = util::bound(
+        &doc,
+        json!([{ "op": "replace", "block_id": "p1", "before": "tabby cat", "after": "old dog", "context_before": "Then the " }]),
+    );
+    let third = util::bound(
+        &doc,
+        json!([{ "op": "delete", "block_id": "p1", "before": "tabby cat", "context_after": " slept" }]),
+    );
+    let also_second = util::bound(
+        &doc,
+        json!([{ "op": "replace", "block_id": "p1", "before": "tabby cat", "after": "kitten", "occurrence": 2 }]),
+    );
+
+    assert!(merge_patches(&packet, &[second.clone(), third]).is_clean());
+    assert!(!merge_patches(&packet, &[second, also_second]).is_clean());
+}
@@ -0,0 +1,94 @@
+mod util;
+
+use serde_json::json;
+
+use bdir_core::model::Document;
+use bdir_patch::{
+    apply_patch_against_document,
+    apply_patch_against_edit_packet,
+    validate_patch_with_diagnostics,
+    DiagnosticCode,
+    ValidateOptions,
+};
+
+fn hash_of(doc: &Document, id: &str) -> String {
+    doc.blocks.iter().find(|b| b.id == id).unwrap().text_hash.clone()
+}
+
+#[test]
+fn delete_block_removes_the_block_from_a_document() {
+    let doc = util::load_doc();
+    let patch = util::bound(
+        &doc,
+        json!([{ "op": "delete_block", "block_id": "b1", "text_hash": hash_of(&doc, "b1") }]),
+    );
+
+    let out = apply_patch_against_document(&doc, &patch).unwrap();
+    let ids: Vec<&str> = out.blocks.iter().map(|b| b.id.as_str()).collect();
+    assert_eq!(ids, vec!["t1", "p1"]);
+    assert_ne!(out.page_hash, doc.page_hash);
+}
+
+#[test]
+fn delete_block_removes_the_tuple_from_an_edit_packet() {
+    let packet = util::load_edit_packet();
+    let patch = util::bound_to_packet(
+        &packet,
+        json!([{ "op": "delete_block", "block_id": "b1", "text_hash": packet.b[2].2[..8] }]),
+    );
+
+    let out = apply_patch_against_edit_packet(&packet, &patch).unwrap();
+    assert_eq!(out.b.len(), 2);
+    assert!(out.b.iter().all(|t| t.0 != "b1"));
+    assert_ne!(out.h, packet.h);
+}
+
+#[test]
+fn stale_text_hash_is_rejected() {
+    let mut doc = util::load_doc();
+    let stale = hash_of(&doc, "p1");
+    doc.blocks[1].text.push_str(" Edited upstream.");
+    doc.recompute_hashes();
+    let patch = util::bound(&doc, json!([{ "op": "delete_block", "block_id": "p1", "text_hash": stale }]));
+
+    let err = validate_patch_with_diagnostics(&doc, &patch, ValidateOptions::default()).unwrap_err();
+    let diag = &err.diagnostics[0];
+    assert_eq!(diag.code, DiagnosticCode::BlockHashMismatch);
+    assert_eq!(diag.path.as_deref(), Some("ops[0].text_hash"));
+}
+
+#[test]
+fn missing_or_short_text_hash_and_stray_fields_are_rejected() {
+    let doc = util::load_doc();
+    let cases = [
+        (json!({ "op": "delete_block", "block_id": "b1" }), DiagnosticCode::MissingField),
+        (json!({ "op": "delete_block", "block_id": "b1", "text_hash": "4f7d6d6" }), DiagnosticCode::HashTooShort),
+        (
+            json!({ "op": "delete_block", "block_id": "b1", "text_hash": hash_of(&doc, "b1"), "before": "Home > Section" }),
+            DiagnosticCode::UnexpectedField,
+        ),
+    ];
+
+    for (op, code) in cases {
+        let patch = util::bound(&doc, json!([op]));
+        let err = validate_patch_with_diagnostics(&doc, &patch, ValidateOptions::default()).unwrap_err();
+        assert_eq!(err.diagnostics[0].code, code);
+    }
+}
+
+#[test]
+fn ops_after_delete_block_may_not_target_the_deleted_block() {
+    let doc = util::load_doc();
+    let patch = util::bound(
+        &doc,
+        json!([
+            { "op": "delete_block", "block_id": "p1", "text_hash": hash_of(&doc, "p1") },
+            { "op": "replace", "block_id": "p1", "before": "with a typo teh", "after": "with a typo the" }
+        ]),
+    );
+
+    let err = validate_patch_with_diagnostics(&doc, &patch, ValidateOptions::default()).unwrap_err();
+    let diag = &err.diagnostics[0];
+    assert_eq!(diag.code, DiagnosticCode::TargetsDeletedBlock);
+    assert_eq!(diag.message, "ops[1] targets block 'p1', which ops[0] deletes");
+}
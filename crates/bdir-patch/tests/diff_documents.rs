@@ -0,0 +1,201 @@
+mod util;
+
+use bdir_core::model::{Block, Document};
+use bdir_patch::{
+    apply_patch_against_document,
+    diff_documents,
+    DiffError,
+    OpType,
+    PatchOpV1,
+};
+
+fn with_text(doc: &Document, id: &str, text: &str) -> Document {
+    let mut out = doc.clone();
+    out.blocks.iter_mut().find(|b| b.id == id).unwrap().text = text.to_string();
+    out
+}
+
+fn new_block(id: &str, text: &str) -> Block {
+    Block { id: id.to_string(), kind_code: 2, text_hash: String::new(), text: text.to_string(), parent: None }
+}
+
+fn assert_round_trips(original: &Document, updated: &Document) -> Vec<PatchOpV1> {
+    let patch = diff_documents(original, updated).unwrap();
+    assert_eq!(patch.h.as_deref(), Some(original.page_hash.as_str()));
+    let applied = apply_patch_against_document(original, &patch).unwrap();
+    let texts = |d: &Document| -> Vec<(String, u16, String, Option<String>)> {
+        d.blocks.iter().map(|b| (b.id.clone(), b.kind_code, b.text.clone(), b.parent.clone())).collect()
+    };
+    assert_eq!(texts(&applied), texts(updated));
+    patch.ops
+}
+
+#[test]
+fn typo_fix_becomes_one_small_anchored_replace() {
+    let original = util::load_doc();
+    let updated = with_text(&original, "p1", "This is an example paragraph with a typo the.");
+
+    let ops = assert_round_trips(&original, &updated);
+    assert_eq!(ops, vec![PatchOpV1::replace("p1", "typo teh.", "typo the.")]);
+}
+
+#[test]
+fn separate_word_edits_become_separate_ops_in_document_order() {
+    let original = util::load_doc();
+    let updated = with_text(&original, "t1", "Sample Page Title");
+    let updated = with_text(&updated, "p1", "This is a sample paragraph with a typo the.");
+
+    let ops = assert_round_trips(&original, &updated);
+    let targets: Vec<(&str, Option<&str>)> =
+        ops.iter().map(|o| (o.block_id.as_str(), o.before.as_deref())).collect();
+    assert_eq!(
+        targets,
+        vec![("t1", Some("Example Page")), ("p1", Some("an example")), ("p1", Some("typo teh."))]
+    );
+}
+
+#[test]
+fn removed_words_become_a_delete() {
+    let original = util::load_doc();
+    let updated = with_text(&original, "p1", "This is an example paragraph teh.");
+
+    let ops = assert_round_trips(&original, &updated);
+    assert_eq!(ops.len(), 1);
+    assert_eq!(ops[0].op, OpType::Delete);
+}
+
+#[test]
+fn repeated_words_still_produce_unique_anchors() {
+    let base = with_text(&util::load_doc(), "p1", "one two one two one two three and one two four");
+    for target in [
+        "one two one 2 one two three and one two four",
+        "one two one two one two three and one two five",
+        "one two one two three",
+        "zero one two one two one two three and one two four",
+    ] {
+        let updated = with_text(&base, "p1", target);
+        assert_round_trips(&base, &updated);
+    }
+}
+
+#[test]
+fn new_blocks_are_inserted_after_the_preceding_block_in_order() {
+    let original = util::load_doc();
+    let mut updated = original.clone();
+    updated.blocks.insert(2, new_block("p2", "Second paragraph."));
+    updated.blocks.insert(3, new_block("p3", "Third paragraph."));
+
+    let ops = assert_round_trips(&original, &updated);
+    assert!(ops.iter().all(|o| o.op == OpType::InsertAfter && o.block_id == "p1"));
+}
+
+#[test]
+fn identical_documents_produce_an_empty_patch() {
+    let doc = util::load_doc();
+    let patch = diff_documents(&doc, &doc).unwrap();
+    assert!(patch.ops.is_empty());
+}
+
+#[test]
+fn removed_block_becomes_delete_block() {
+    let original = util::load_doc();
+    let mut updated = original.clone();
+    updated.blocks.retain(|b| b.id != "b1");
+
+    let ops = assert_round_trips(&original, &updated);
+    assert_eq!(ops, vec![PatchOpV1::delete_block("b1", original.blocks[2].text_hash.clone())]);
+}
+
+#[test]
+fn reordered_blocks_become_move_ops() {
+    let original = util::load_doc();
+    let mut updated = original.clone();
+    updated.blocks.swap(1, 2);
+
+    let ops = assert_round_trips(&original, &updated);
+    assert_eq!(ops, vec![PatchOpV1::move_after("b1", "t1")]);
+}
+
+#[test]
+fn reversed_document_keeps_first_block_in_place_and_moves_the_rest() {
+    let original = util::load_doc();
+    let mut updated = with_text(&original, "p1", "This is an example paragraph with a typo the.");
+    updated.blocks.reverse();
+    updated.blocks.insert(1, new_block("p2", "Inserted between moved blocks."));
+
+    let ops = assert_round_trips(&original, &updated);
+    assert_eq!(&ops[..2], &[PatchOpV1::move_after("p1", "b1"), PatchOpV1::move_after("t1", "p1")]);
+}
+
+#[test]
+fn changed_kind_code_becomes_reclassify_after_text_ops() {
+    let original = util::load_doc();
+    let mut updated = with_text(&original, "p1", "This is an example paragraph with a typo the.");
+    updated.blocks[1].kind_code = 20;
+
+    let ops = assert_round_trips(&original, &updated);
+    assert_eq!(
+        ops,
+        vec![PatchOpV1::replace("p1", "typo teh.", "typo the."), PatchOpV1::reclassify("p1", 20)]
+    );
+}
+
+#[test]
+fn duplicate_updated_block_ids_are_unrepresentable() {
+    let original = util::load_doc();
+    let mut updated = original.clone();
+    updated.blocks.push(updated.blocks[1].clone());
+
+    let err = diff_documents(&original, &updated).unwrap_err();
+    assert_eq!(
+        err,
+        DiffError::Unrepresentable {
+            block_id: "p1".to_string(),
+            message: "block id 'p1' appears more than once in the updated document".to_string(),
+        }
+    );
+}
+
+#[test]
+fn blocks_inserted_at_document_start_use_insert_before() {
+    let original = util::load_doc();
+    let mut updated = original.clone();
+    updated.blocks.insert(0, new_block("p0", "Lead paragraph."));
+    updated.blocks.insert(1, new_block("p00", "Second lead paragraph."));
+
+    let ops = assert_round_trips(&original, &updated);
+    assert!(ops.iter().all(|o| o.op == OpType::InsertBefore && o.block_id == "t1"));
+}
+
+#[test]
+fn new_blocks_are_anchored_where_they_get_their_parent() {
+    let original = util::load_sections();
+    let mut updated = original.clone();
+    let mut first_child = new_block("p0", "Cats are popular pets.");
+    first_child.parent = Some("h1".to_string());
+    updated.blocks.insert(2, first_child);
+
+    let ops = assert_round_trips(&original, &updated);
+    assert_eq!((ops[0].op, ops[0].block_id.as_str()), (OpType::InsertBefore, "p1"));
+}
+
+#[test]
+fn parent_changes_without_an_op_are_unrepresentable() {
+    let original = util::load_sections();
+    let mut updated = original.clone();
+    updated.blocks[2].parent = None;
+    let err = diff_documents(&original, &updated).unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "block 'p1' cannot be reparented with patch ops (parent none in the updated document, 'h1' after the patch)"
+    );
+
+    let mut updated = original.clone();
+    let mut stray = new_block("p4", "Cats again.");
+    stray.parent = Some("h1".to_string());
+    updated.blocks.push(stray);
+    assert!(matches!(
+        diff_documents(&original, &updated).unwrap_err(),
+        DiffError::Unrepresentable { block_id, .. } if block_id == "p4"
+    ));
+}
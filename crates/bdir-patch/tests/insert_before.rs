@@ -0,0 +1,75 @@
+mod util;
+
+use serde_json::json;
+
+use bdir_patch::{
+    apply_patch_against_document,
+    apply_patch_against_edit_packet,
+    validate_patch_with_diagnostics,
+    DiagnosticCode,
+    ValidateOptions,
+};
+
+#[test]
+fn insert_before_first_block_adds_a_new_lead_block() {
+    let doc = util::load_doc();
+    let patch = util::bound(
+        &doc,
+        json!([
+            { "op": "insert_before", "block_id": "t1", "new_block_id": "h0", "kind_code": 1, "text": "Site Name" },
+            { "op": "insert_before", "block_id": "t1", "new_block_id": "h1", "kind_code": 2, "text": "Lead paragraph." }
+        ]),
+    );
+
+    let out = apply_patch_against_document(&doc, &patch).unwrap();
+    let ids: Vec<&str> = out.blocks.iter().map(|b| b.id.as_str()).collect();
+    assert_eq!(ids, vec!["h0", "h1", "t1", "p1", "b1"]);
+    assert!(!out.blocks[0].text_hash.is_empty());
+}
+
+#[test]
+fn insert_before_applies_to_edit_packets() {
+    let packet = util::load_edit_packet();
+    let patch = util::bound_to_packet(
+        &packet,
+        json!([{ "op": "insert_before", "block_id": "b1", "new_block_id": "p2", "kind_code": 2, "text": "Closing paragraph." }]),
+    );
+
+    let out = apply_patch_against_edit_packet(&packet, &patch).unwrap();
+    let ids: Vec<&str> = out.b.iter().map(|t| t.0.as_str()).collect();
+    assert_eq!(ids, vec!["t1", "p1", "p2", "b1"]);
+}
+
+#[test]
+fn insert_before_shares_insert_after_validation() {
+    let doc = util::load_doc();
+    let cases = [
+        (
+            json!({ "op": "insert_before", "block_id": "t1", "new_block_id": "p1", "kind_code": 2, "text": "Dup." }),
+            DiagnosticCode::DuplicateBlockId,
+            "ops[0] (insert_before) new_block_id 'p1' already exists",
+        ),
+        (
+            json!({ "op": "insert_before", "block_id": "t1", "new_block_id": "h0", "text": "No kind." }),
+            DiagnosticCode::MissingField,
+            "ops[0] (insert_before) missing kind_code",
+        ),
+        (
+            json!({ "op": "insert_before", "block_id": "t1", "new_block_id": "h0", "kind_code": 2, "text": "  " }),
+            DiagnosticCode::ContentEmpty,
+            "ops[0] (insert_before) text is empty",
+        ),
+        (
+            json!({ "op": "insert_before", "block_id": "zz", "new_block_id": "h0", "kind_code": 2, "text": "Lead." }),
+            DiagnosticCode::UnknownBlockId,
+            "ops[0] references unknown block_id 'zz'",
+        ),
+    ];
+
+    for (op, code, message) in cases {
+        let patch = util::bound(&doc, json!([op]));
+        let err = validate_patch_with_diagnostics(&doc, &patch, ValidateOptions::default()).unwrap_err();
+        assert_eq!(err.diagnostics[0].code, code);
+        assert_eq!(err.diagnostics[0].message, message);
+    }
+}
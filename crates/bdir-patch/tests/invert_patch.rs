@@ -0,0 +1,182 @@
+mod util;
+
+use serde_json::json;
+
+use bdir_core::model::Document;
+use bdir_patch::{
+    apply_patch_against_document,
+    invert_patch,
+    InvertError,
+    OpType,
+    PatchV1,
+};
+
+fn assert_round_trips(doc: &Document, patch: &PatchV1) -> PatchV1 {
+    let updated = apply_patch_against_document(doc, patch).unwrap();
+    let inverse = invert_patch(doc, patch).unwrap();
+    assert_eq!(inverse.h.as_deref(), Some(updated.page_hash.as_str()));
+    assert_eq!(inverse.ha.as_deref(), Some(updated.hash_algorithm.as_str()));
+
+    let restored = apply_patch_against_document(&updated, &inverse).unwrap();
+    assert_eq!(restored.blocks, doc.blocks);
+    assert_eq!(restored.page_hash, doc.page_hash);
+    inverse
+}
+
+#[test]
+fn inverse_of_replace_restores_original() {
+    let doc = util::load_doc();
+    let patch: PatchV1 = serde_json::from_str(&util::read_fixture("patch.valid.json")).unwrap();
+
+    let inverse = assert_round_trips(&doc, &patch);
+    assert_eq!(inverse.ops.len(), 1);
+    let op = &inverse.ops[0];
+    assert_eq!(op.op, OpType::Replace);
+    assert_eq!(op.block_id, "p1");
+    assert_eq!(op.before.as_deref(), Some("po: the t"));
+    assert_eq!(op.after.as_deref(), Some("po t"));
+}
+
+#[test]
+fn inverse_of_delete_and_multi_block_edits_restores_original() {
+    let doc = util::load_doc();
+    let patch = util::bound(
+        &doc,
+        json!([
+            { "op": "delete", "block_id": "p1", "before": " with a typo teh" },
+            { "op": "replace", "block_id": "t1", "before": "Example Page", "after": "Sample Page" },
+            { "op": "suggest", "block_id": "b1", "message": "Breadcrumb looks fine." }
+        ]),
+    );
+
+    let inverse = assert_round_trips(&doc, &patch);
+    let targets: Vec<&str> = inverse.ops.iter().map(|o| o.block_id.as_str()).collect();
+    assert_eq!(targets, vec!["t1", "p1"]);
+}
+
+#[test]
+fn inverse_of_insert_after_deletes_the_new_block() {
+    let doc = util::load_doc();
+    let patch = util::bound(
+        &doc,
+        json!([{ "op": "insert_after", "block_id": "p1", "new_block_id": "p2", "kind_code": 2, "text": "New paragraph." }]),
+    );
+
+    let inverse = assert_round_trips(&doc, &patch);
+    assert_eq!(inverse.ops.len(), 1);
+    assert_eq!(inverse.ops[0].op, OpType::DeleteBlock);
+    assert_eq!(inverse.ops[0].block_id, "p2");
+}
+
+#[test]
+fn inverse_of_delete_block_reinserts_blocks_in_order() {
+    let doc = util::load_doc();
+    let hash = |id: &str| doc.blocks.iter().find(|b| b.id == id).unwrap().text_hash.clone();
+    let patch = util::bound(
+        &doc,
+        json!([
+            { "op": "delete_block", "block_id": "p1", "text_hash": hash("p1") },
+            { "op": "delete_block", "block_id": "b1", "text_hash": hash("b1") }
+        ]),
+    );
+
+    let inverse = assert_round_trips(&doc, &patch);
+    assert!(inverse.ops.iter().all(|o| o.op == OpType::InsertAfter && o.block_id == "t1"));
+}
+
+#[test]
+fn inverse_of_deleting_the_first_block_uses_insert_before() {
+    let doc = util::load_doc();
+    let patch =
+        util::bound(&doc, json!([{ "op": "delete_block", "block_id": "t1", "text_hash": doc.blocks[0].text_hash }]));
+
+    let inverse = assert_round_trips(&doc, &patch);
+    assert_eq!(inverse.ops.len(), 1);
+    assert_eq!(inverse.ops[0].op, OpType::InsertBefore);
+    assert_eq!(inverse.ops[0].block_id, "p1");
+}
+
+#[test]
+fn restored_blocks_keep_their_original_parent() {
+    let doc = util::load_sections();
+    // p1 sits under h1; its preceding survivor h1 is a root block, the next one is under h1.
+    let patch =
+        util::bound(&doc, json!([{ "op": "delete_block", "block_id": "p1", "text_hash": doc.blocks[2].text_hash }]));
+
+    let inverse = assert_round_trips(&doc, &patch);
+    assert_eq!((inverse.ops[0].op, inverse.ops[0].block_id.as_str()), (OpType::InsertBefore, "h1a"));
+}
+
+#[test]
+fn parents_that_cannot_be_restored_are_not_invertible() {
+    let doc = util::load_sections();
+    let last =
+        util::bound(&doc, json!([{ "op": "delete_block", "block_id": "p3", "text_hash": doc.blocks[6].text_hash }]));
+    let err = invert_patch(&doc, &last).unwrap_err();
+    assert_eq!(err.to_string(), "inverse patch cannot restore the parent of block 'p3' (was 'h2', would be none)");
+
+    // Deleting a heading reparents its children, which no op can undo.
+    let heading =
+        util::bound(&doc, json!([{ "op": "delete_block", "block_id": "h1a", "text_hash": doc.blocks[3].text_hash }]));
+    let err = invert_patch(&doc, &heading).unwrap_err();
+    assert_eq!(err.to_string(), "inverse patch cannot restore the parent of block 'p2' (was 'h1a', would be 'h1')");
+
+    let merge = util::bound(&doc, json!([{ "op": "merge_block", "block_id": "p1", "next_block_id": "h1a" }]));
+    assert!(matches!(invert_patch(&doc, &merge).unwrap_err(), InvertError::NotInvertible { .. }));
+}
+
+#[test]
+fn deleting_every_block_is_not_invertible() {
+    let doc = util::load_doc();
+    let ops: Vec<serde_json::Value> = doc
+        .blocks
+        .iter()
+        .map(|b| json!({ "op": "delete_block", "block_id": b.id, "text_hash": b.text_hash }))
+        .collect();
+    let patch = util::bound(&doc, json!(ops));
+
+    let err = invert_patch(&doc, &patch).unwrap_err();
+    assert!(matches!(err, InvertError::NotInvertible { op_index: Some(0), .. }));
+}
+
+#[test]
+fn inverse_of_move_restores_block_order() {
+    let doc = util::load_doc();
+    let patch = util::bound(&doc, json!([
+        { "op": "move", "block_id": "t1", "after_block_id": "b1" },
+        { "op": "replace", "block_id": "p1", "before": "with a typo teh", "after": "with a typo the" }
+    ]));
+
+    let inverse = assert_round_trips(&doc, &patch);
+    assert_eq!(inverse.ops[0].op, OpType::Move);
+}
+
+#[test]
+fn inverse_of_reclassify_restores_kind_code() {
+    let doc = util::load_doc();
+    let patch = util::bound(&doc, json!([{ "op": "reclassify", "block_id": "p1", "kind_code": 20 }]));
+
+    let inverse = assert_round_trips(&doc, &patch);
+    assert_eq!(inverse.ops.len(), 1);
+    assert_eq!(inverse.ops[0].op, OpType::Reclassify);
+    assert_eq!(inverse.ops[0].kind_code, Some(2));
+}
+
+#[test]
+fn suggest_only_patch_has_nothing_to_invert() {
+    let doc = util::load_doc();
+    let patch = util::bound(&doc, json!([{ "op": "suggest", "block_id": "p1", "message": "Consider rewording." }]));
+
+    assert_eq!(invert_patch(&doc, &patch).unwrap_err(), InvertError::NoChanges);
+}
+
+#[test]
+fn forward_patch_that_does_not_apply_is_reported() {
+    let doc = util::load_doc();
+    let patch = util::bound(
+        &doc,
+        json!([{ "op": "replace", "block_id": "p1", "before": "not in this block", "after": "x" }]),
+    );
+
+    assert!(matches!(invert_patch(&doc, &patch).unwrap_err(), InvertError::Apply(_)));
+}
@@ -0,0 +1,154 @@
+mod util;
+
+use serde_json::json;
+
+use bdir_patch::{
+    apply_patch_against_edit_packet,
+    merge_patches,
+    MergeConflictKind,
+    MergeOpRef,
+    OpType,
+};
+
+fn op_ref(patch_index: usize, op_index: usize) -> MergeOpRef {
+    MergeOpRef { patch_index, op_index: Some(op_index) }
+}
+
+#[test]
+fn disjoint_and_duplicate_ops_merge_into_one_canonical_patch() {
+    let packet = util::load_edit_packet();
+    let a = util::bound_to_packet(&packet, json!([
+        { "op": "replace", "block_id": "p1", "before": "with a typo teh", "after": "with a typo the" }
+    ]));
+    let b = util::bound_to_packet(&packet, json!([
+        { "op": "replace", "block_id": "t1", "before": "Example Page", "after": "Sample Page" },
+        { "op": "replace", "block_id": "p1", "before": "with a typo teh", "after": "with a typo the" }
+    ]));
+
+    let report = merge_patches(&packet, &[a, b]);
+    assert!(report.is_clean());
+    assert_eq!(report.patch.h.as_deref(), Some(packet.h.as_str()));
+    let targets: Vec<&str> = report.patch.ops.iter().map(|o| o.block_id.as_str()).collect();
+    assert_eq!(targets, vec!["t1", "p1"]);
+
+    let out = apply_patch_against_edit_packet(&packet, &report.patch).unwrap();
+    assert_eq!(out.b[0].3, "Sample Page Title");
+    assert_eq!(out.b[1].3, "This is an example paragraph with a typo the.");
+}
+
+#[test]
+fn conflicts_are_detected_and_conflicting_ops_are_dropped() {
+    let packet = util::load_edit_packet();
+    let a = util::bound_to_packet(&packet, json!([
+        { "op": "replace", "block_id": "p1", "before": "with a typo teh", "after": "with a typo the" },
+        { "op": "replace", "block_id": "t1", "before": "Example Page", "after": "Sample Page" },
+        { "op": "suggest", "block_id": "b1", "message": "Breadcrumb looks fine." }
+    ]));
+    let b = util::bound_to_packet(&packet, json!([
+        { "op": "replace", "block_id": "p1", "before": "typo teh.", "after": "typo, the." },
+        { "op": "replace", "block_id": "t1", "before": "Example Page", "after": "Sample Page" },
+        { "op": "insert_after", "block_id": "p1", "new_block_id": "p2", "kind_code": 2, "text": "First new paragraph." }
+    ]));
+    let c = util::bound_to_packet(&packet, json!([
+        { "op": "insert_after", "block_id": "p1", "new_block_id": "p2", "kind_code": 2, "text": "Second new paragraph." },
+        { "op": "replace", "block_id": "t1", "before": "Example Page", "after": "Demo Page" }
+    ]));
+    let d = util::bound_to_packet(&packet, json!([
+        { "op": "replace", "block_id": "p1", "before": "not in this block", "after": "x" }
+    ]));
+
+    let report = merge_patches(&packet, &[a, b, c, d]);
+
+    let got: Vec<(MergeConflictKind, Vec<MergeOpRef>)> =
+        report.conflicts.iter().map(|c| (c.kind, c.ops.clone())).collect();
+    assert_eq!(
+        got,
+        vec![
+            (MergeConflictKind::OverlappingBefore, vec![op_ref(0, 0), op_ref(1, 0)]),
+            (MergeConflictKind::ContradictoryReplacement, vec![op_ref(0, 1), op_ref(2, 1)]),
+            (MergeConflictKind::DuplicateNewBlockId, vec![op_ref(1, 2), op_ref(2, 0)]),
+            (MergeConflictKind::InvalidPatch, vec![MergeOpRef { patch_index: 3, op_index: None }]),
+        ]
+    );
+    assert_eq!(
+        report.conflicts[0].message,
+        "patches[0].ops[0] and patches[1].ops[0] edit overlapping ranges in block 'p1'"
+    );
+
+    assert_eq!(report.patch.ops.len(), 1);
+    assert_eq!(report.patch.ops[0].op, OpType::Suggest);
+}
+
+#[test]
+fn merge_output_is_deterministic_regardless_of_input_op_order() {
+    let packet = util::load_edit_packet();
+    let a = util::bound_to_packet(&packet, json!([
+        { "op": "suggest", "block_id": "b1", "message": "Breadcrumb looks fine." },
+        { "op": "replace", "block_id": "t1", "before": "Example Page", "after": "Sample Page" }
+    ]));
+    let b = util::bound_to_packet(&packet, json!([
+        { "op": "replace", "block_id": "p1", "before": "with a typo teh", "after": "with a typo the" }
+    ]));
+
+    let ab = merge_patches(&packet, &[a.clone(), b.clone()]);
+    let ba = merge_patches(&packet, &[b, a]);
+    assert_eq!(ab.patch, ba.patch);
+}
+
+#[test]
+fn deleting_a_block_another_patch_edits_conflicts() {
+    let packet = util::load_edit_packet();
+    let a = util::bound_to_packet(&packet, json!([
+        { "op": "replace", "block_id": "p1", "before": "with a typo teh", "after": "with a typo the" }
+    ]));
+    let b = util::bound_to_packet(&packet, json!([
+        { "op": "suggest", "block_id": "p1", "message": "Paragraph is redundant." },
+        { "op": "delete_block", "block_id": "p1", "text_hash": packet.b[1].2 }
+    ]));
+
+    let report = merge_patches(&packet, &[a, b]);
+    assert_eq!(report.conflicts.len(), 1);
+    assert_eq!(report.conflicts[0].kind, MergeConflictKind::DeletedBlockEdited);
+    assert_eq!(
+        report.conflicts[0].message,
+        "patches[1].ops[1] deletes block 'p1', which patches[0].ops[0] also changes"
+    );
+    assert_eq!(report.patch.ops.len(), 1);
+    assert_eq!(report.patch.ops[0].op, OpType::Suggest);
+}
+
+#[test]
+fn moving_one_block_to_different_places_conflicts() {
+    let packet = util::load_edit_packet();
+    let a = util::bound_to_packet(&packet, json!([{ "op": "move", "block_id": "t1", "after_block_id": "p1" }]));
+    let b = util::bound_to_packet(&packet, json!([{ "op": "move", "block_id": "t1", "after_block_id": "b1" }]));
+
+    let report = merge_patches(&packet, &[a.clone(), b]);
+    assert_eq!(report.conflicts.len(), 1);
+    assert_eq!(report.conflicts[0].kind, MergeConflictKind::ConflictingMove);
+    assert_eq!(
+        report.conflicts[0].message,
+        "patches[0].ops[0] and patches[1].ops[0] move block 't1' to different places"
+    );
+    assert!(report.patch.ops.is_empty());
+
+    let same = merge_patches(&packet, &[a.clone(), a]);
+    assert!(same.is_clean());
+    assert_eq!(same.patch.ops.len(), 1);
+}
+
+#[test]
+fn reclassifying_one_block_differently_conflicts() {
+    let packet = util::load_edit_packet();
+    let a = util::bound_to_packet(&packet, json!([{ "op": "reclassify", "block_id": "p1", "kind_code": 20 }]));
+    let b = util::bound_to_packet(&packet, json!([{ "op": "reclassify", "block_id": "p1", "kind_code": 21 }]));
+
+    let report = merge_patches(&packet, &[a, b]);
+    assert_eq!(report.conflicts.len(), 1);
+    assert_eq!(report.conflicts[0].kind, MergeConflictKind::ConflictingReclassify);
+    assert_eq!(
+        report.conflicts[0].message,
+        "patches[0].ops[0] and patches[1].ops[0] reclassify block 'p1' differently (kind_code 20 vs 21)"
+    );
+    assert!(report.patch.ops.is_empty());
+}
@@ -0,0 +1,127 @@
+mod util;
+
+use serde_json::json;
+
+use bdir_patch::{
+    apply_patch_against_document,
+    apply_patch_against_edit_packet,
+    DiagnosticCode,
+    ValidationDiagnostic,
+};
+
+#[test]
+fn move_relocates_a_document_block_keeping_id_kind_and_text() {
+    let doc = util::load_doc();
+    let patch = util::bound(&doc, json!([{ "op": "move", "block_id": "t1", "after_block_id": "b1" }]));
+
+    let out = apply_patch_against_document(&doc, &patch).unwrap();
+    let ids: Vec<&str> = out.blocks.iter().map(|b| b.id.as_str()).collect();
+    assert_eq!(ids, vec!["p1", "b1", "t1"]);
+    assert_eq!(out.blocks[2], doc.blocks[0]);
+    assert_ne!(out.page_hash, doc.page_hash);
+}
+
+#[test]
+fn move_relocates_an_edit_packet_tuple() {
+    let packet = util::load_edit_packet();
+    let patch = util::bound_to_packet(&packet, json!([{ "op": "move", "block_id": "b1", "afterBlockId": "t1" }]));
+
+    let out = apply_patch_against_edit_packet(&packet, &patch).unwrap();
+    let ids: Vec<&str> = out.b.iter().map(|t| t.0.as_str()).collect();
+    assert_eq!(ids, vec!["t1", "b1", "p1"]);
+    assert_eq!(out.b[1], packet.b[2]);
+}
+
+#[test]
+fn missing_or_unknown_anchor_and_stray_fields_are_rejected() {
+    let doc = util::load_doc();
+    let cases = [
+        (json!({ "op": "move", "block_id": "p1" }), DiagnosticCode::MissingField),
+        (json!({ "op": "move", "block_id": "p1", "after_block_id": "nope" }), DiagnosticCode::UnknownBlockId),
+        (
+            json!({ "op": "move", "block_id": "p1", "after_block_id": "b1", "text": "Moved." }),
+            DiagnosticCode::UnexpectedField,
+        ),
+    ];
+
+    for (op, code) in cases {
+        assert_eq!(util::first_diagnostic(&doc, json!([op])).code, code);
+    }
+}
+
+#[test]
+fn move_cycles_are_rejected() {
+    let doc = util::load_doc();
+
+    let ValidationDiagnostic { code, message, .. } =
+        util::first_diagnostic(&doc, json!([{ "op": "move", "block_id": "p1", "after_block_id": "p1" }]));
+    assert_eq!(code, DiagnosticCode::MoveCycle);
+    assert_eq!(message, "ops[0] (move) forms a cycle: p1 -> p1");
+
+    let ValidationDiagnostic { code, message, .. } = util::first_diagnostic(
+        &doc,
+        json!([
+            { "op": "move", "block_id": "t1", "after_block_id": "b1" },
+            { "op": "move", "block_id": "b1", "after_block_id": "t1" }
+        ]),
+    );
+    assert_eq!(code, DiagnosticCode::MoveCycle);
+    assert_eq!(message, "ops[0] (move) forms a cycle: t1 -> b1 -> t1");
+}
+
+#[test]
+fn moving_or_anchoring_on_a_deleted_block_is_rejected() {
+    let doc = util::load_doc();
+    let b1_hash = doc.blocks[2].text_hash.clone();
+
+    let ValidationDiagnostic { code, message, .. } = util::first_diagnostic(
+        &doc,
+        json!([
+            { "op": "move", "block_id": "b1", "after_block_id": "t1" },
+            { "op": "delete_block", "block_id": "b1", "text_hash": b1_hash }
+        ]),
+    );
+    assert_eq!(code, DiagnosticCode::TargetsDeletedBlock);
+    assert_eq!(message, "ops[0] (move) moves block 'b1', which ops[1] deletes");
+
+    let ValidationDiagnostic { code, message, .. } = util::first_diagnostic(
+        &doc,
+        json!([
+            { "op": "move", "block_id": "t1", "after_block_id": "b1" },
+            { "op": "delete_block", "block_id": "b1", "text_hash": b1_hash }
+        ]),
+    );
+    assert_eq!(code, DiagnosticCode::TargetsDeletedBlock);
+    assert_eq!(message, "ops[0] (move) anchors on block 'b1', which ops[1] deletes");
+}
+
+#[test]
+fn inserts_that_depend_on_a_later_move_are_rejected() {
+    let doc = util::load_doc();
+    let insert = |op: &str, anchor: &str| {
+        json!({ "op": op, "block_id": anchor, "new_block_id": "p2", "kind_code": 2, "text": "New paragraph." })
+    };
+
+    let ValidationDiagnostic { code, message, .. } = util::first_diagnostic(
+        &doc,
+        json!([insert("insert_before", "b1"), { "op": "move", "block_id": "b1", "after_block_id": "t1" }]),
+    );
+    assert_eq!(code, DiagnosticCode::MoveConflict);
+    assert_eq!(message, "ops[0] (insert_before) anchors on block 'b1', which ops[1] moves later");
+
+    let ValidationDiagnostic { code, message, .. } = util::first_diagnostic(
+        &doc,
+        json!([insert("insert_after", "t1"), { "op": "move", "block_id": "b1", "after_block_id": "t1" }]),
+    );
+    assert_eq!(code, DiagnosticCode::MoveConflict);
+    assert_eq!(message, "ops[0] (insert_after) anchors on block 't1', which ops[1] later uses as a move anchor");
+
+    // The same ops are fine once the move comes first.
+    let patch = util::bound(
+        &doc,
+        json!([{ "op": "move", "block_id": "b1", "after_block_id": "t1" }, insert("insert_after", "t1")]),
+    );
+    let out = apply_patch_against_document(&doc, &patch).unwrap();
+    let ids: Vec<&str> = out.blocks.iter().map(|b| b.id.as_str()).collect();
+    assert_eq!(ids, vec!["t1", "p2", "b1", "p1"]);
+}
@@ -0,0 +1,111 @@
+mod util;
+
+use serde_json::json;
+
+use bdir_patch::{
+    apply_patch_against_document,
+    validate_patch_with_diagnostics,
+    PatchV1,
+    PatchV2,
+    ValidateOptions,
+    VersionedPatch,
+};
+
+#[test]
+fn deserialize_dispatches_on_v() {
+    let v1: VersionedPatch = serde_json::from_str(&util::read_fixture("patch.valid.json")).unwrap();
+    assert_eq!(v1.version(), 1);
+    assert!(matches!(v1, VersionedPatch::V1(_)));
+
+    let v2: VersionedPatch = serde_json::from_value(json!({
+        "v": 2,
+        "h": "ff0e1875ab7f8425",
+        "ops": [{ "op": "delete", "block_id": "p1", "before": "a typo", "occurrence": "all" }]
+    }))
+    .unwrap();
+    assert_eq!(v2.version(), 2);
+    assert!(matches!(v2, VersionedPatch::V2(_)));
+
+    let err = serde_json::from_value::<VersionedPatch>(json!({ "v": 3, "ops": [] })).unwrap_err();
+    assert_eq!(err.to_string(), "unsupported patch version 3");
+
+    let err = serde_json::from_value::<VersionedPatch>(json!({ "ops": [] })).unwrap_err();
+    assert_eq!(err.to_string(), "missing field `v`");
+}
+
+#[test]
+fn v2_rejects_other_versions() {
+    let err = serde_json::from_value::<PatchV2>(json!({ "v": 1, "ops": [] })).unwrap_err();
+    assert_eq!(err.to_string(), "patch v2 requires `v` = 2, got 1");
+
+    let v2: PatchV2 = serde_json::from_value(json!({ "v": 2, "ops": [] })).unwrap();
+    assert_eq!(v2.v, 2);
+}
+
+#[test]
+fn v2_rejects_legacy_first_occurrence() {
+    let err = serde_json::from_value::<VersionedPatch>(json!({
+        "v": 2,
+        "ops": [{ "op": "delete", "block_id": "p1", "before": "a typo", "occurrence": "first" }]
+    }))
+    .unwrap_err();
+    assert_eq!(err.to_string(), "ops[0]: occurrence \"first\" is not valid in patch v2; use 1");
+}
+
+#[test]
+fn v2_rejects_v1_field_aliases() {
+    let op = json!({ "op": "replace", "blockId": "p1", "before": "a typo", "after": "a fix" });
+    let err = serde_json::from_value::<VersionedPatch>(json!({ "v": 2, "ops": [op] })).unwrap_err();
+    assert_eq!(err.to_string(), "ops[0]: `blockId` is not valid in patch v2; use `block_id`");
+
+    let err = serde_json::from_value::<PatchV2>(json!({
+        "v": 2,
+        "ops": [
+            { "op": "delete", "block_id": "p1", "before": "a typo" },
+            { "op": "insert_after", "block_id": "p1", "new_block_id": "p9", "kind_code": 2, "content": "x" }
+        ]
+    }))
+    .unwrap_err();
+    assert_eq!(err.to_string(), "ops[1]: `content` is not valid in patch v2; use `text`");
+
+    let v1: VersionedPatch = serde_json::from_value(json!({ "v": 1, "ops": [op] })).unwrap();
+    assert!(matches!(v1, VersionedPatch::V1(_)));
+}
+
+#[test]
+fn upgrade_is_lossless_and_applies_identically() {
+    let doc = util::load_doc();
+    let v1: PatchV1 = serde_json::from_value(json!({
+        "v": 1,
+        "h": doc.page_hash,
+        "ha": doc.hash_algorithm,
+        "ops": [
+            { "op": "replace", "block_id": "p1", "before": "example paragraph", "after": "sample paragraph" },
+            { "op": "delete", "block_id": "p1", "before": " with a typo", "occurrence": "first" }
+        ]
+    }))
+    .unwrap();
+
+    let v2 = VersionedPatch::V1(v1.clone()).upgrade();
+    assert_eq!(v2.v, 2);
+    assert_eq!((&v2.h, &v2.ha), (&v1.h, &v1.ha));
+    assert_eq!(serde_json::to_value(&v2.ops[1]).unwrap()["occurrence"], json!(1));
+    assert_eq!(v2.ops[0], v1.ops[0]);
+
+    let upgraded = VersionedPatch::V2(v2).into_patch();
+    assert_eq!(upgraded.v, 2);
+    validate_patch_with_diagnostics(&doc, &upgraded, ValidateOptions::default()).unwrap();
+    assert_eq!(
+        apply_patch_against_document(&doc, &upgraded).unwrap(),
+        apply_patch_against_document(&doc, &v1).unwrap()
+    );
+}
+
+#[test]
+fn versioned_patch_serializes_untagged() {
+    let patch = VersionedPatch::V2(PatchV2 { v: 2, h: None, ha: None, ops: Vec::new() });
+    assert_eq!(serde_json::to_value(&patch).unwrap(), json!({ "v": 2, "ops": [] }));
+
+    let round: VersionedPatch = serde_json::from_value(serde_json::to_value(&patch).unwrap()).unwrap();
+    assert_eq!(round, patch);
+}
@@ -0,0 +1,194 @@
+mod util;
+
+use serde_json::json;
+
+use bdir_core::model::Document;
+use bdir_patch::{
+    apply_patch_against_document,
+    rebase_patch,
+    rebase_patch_with_options,
+    DiagnosticCode,
+    RebaseConflictReason,
+    ValidateOptions,
+};
+
+fn edited(base: &Document, edit: impl FnOnce(&mut Document)) -> Document {
+    let mut doc = base.clone();
+    edit(&mut doc);
+    doc.recompute_hashes();
+    doc
+}
+
+fn set_text(doc: &mut Document, id: &str, text: &str) {
+    doc.blocks.iter_mut().find(|b| b.id == id).unwrap().text = text.to_string();
+}
+
+#[test]
+fn unchanged_and_still_unique_ops_are_rebased_and_bound_to_new_hash() {
+    let base = util::load_doc();
+    let new = edited(&base, |d| {
+        set_text(d, "p1", "Intro. This is an example paragraph with a typo teh.");
+        set_text(d, "b1", "Home > Docs > Page");
+    });
+    let patch = util::bound(
+        &base,
+        json!([
+            { "op": "replace", "block_id": "p1", "before": "example paragraph with a typo", "after": "example paragraph" },
+            { "op": "replace", "block_id": "t1", "before": "Example Page", "after": "Sample Page" }
+        ]),
+    );
+
+    let report = rebase_patch(&base, &new, &patch).unwrap();
+    assert!(report.is_clean());
+    assert_eq!(report.patch.h.as_deref(), Some(new.page_hash.as_str()));
+    assert_eq!(report.patch.ops.len(), 2);
+
+    let updated = apply_patch_against_document(&new, &report.patch).unwrap();
+    assert_eq!(updated.blocks[0].text, "Sample Page Title");
+    assert_eq!(updated.blocks[1].text, "Intro. This is an example paragraph teh.");
+}
+
+#[test]
+fn conflicting_ops_are_reported_in_input_order() {
+    let base = util::load_doc();
+    let new = edited(&base, |d| {
+        set_text(d, "t1", "Updated Page Title");
+        set_text(d, "p1", "example paragraph with a typo; example paragraph with a typo");
+        d.blocks.retain(|b| b.id != "b1");
+    });
+    let patch = util::bound(
+        &base,
+        json!([
+            { "op": "replace", "block_id": "t1", "before": "Example Page", "after": "Sample Page" },
+            { "op": "replace", "block_id": "p1", "before": "example paragraph with a typo", "after": "x" },
+            { "op": "suggest", "block_id": "b1", "message": "Breadcrumb looks fine." }
+        ]),
+    );
+
+    let report = rebase_patch(&base, &new, &patch).unwrap();
+    assert!(report.patch.ops.is_empty());
+    let got: Vec<(usize, RebaseConflictReason)> = report.conflicts.iter().map(|c| (c.op_index, c.reason)).collect();
+    assert_eq!(
+        got,
+        vec![
+            (0, RebaseConflictReason::BeforeNotFound),
+            (1, RebaseConflictReason::BeforeAmbiguous),
+            (2, RebaseConflictReason::BlockRemoved),
+        ]
+    );
+    assert_eq!(report.conflicts[2].message, "ops[2] targets block 'b1', which no longer exists");
+}
+
+#[test]
+fn indexed_occurrence_conflicts_when_its_match_moves_even_if_the_count_holds() {
+    let base = edited(&util::load_doc(), |d| set_text(d, "p1", "a cat box and dog, then a cat box again."));
+    let patch = util::bound(
+        &base,
+        json!([{ "op": "replace", "block_id": "p1", "before": "a cat box", "after": "a bird", "occurrence": 2 }]),
+    );
+
+    // A new match ahead of the target, the old first one gone: still two matches.
+    let moved = edited(&base, |d| set_text(d, "p1", "dog and a cat box, and then a cat box again."));
+    let report = rebase_patch(&base, &moved, &patch).unwrap();
+    assert_eq!(report.conflicts[0].reason, RebaseConflictReason::OccurrenceShifted);
+
+    let edited_after = edited(&base, |d| set_text(d, "p1", "a cat box and dog, then a cat box again!"));
+    let report = rebase_patch(&base, &edited_after, &patch).unwrap();
+    assert!(report.is_clean());
+    let updated = apply_patch_against_document(&edited_after, &report.patch).unwrap();
+    assert_eq!(updated.blocks[1].text, "a cat box and dog, then a bird again!");
+}
+
+#[test]
+fn insert_after_with_taken_new_block_id_conflicts() {
+    let base = util::load_doc();
+    let new = edited(&base, |d| {
+        let mut extra = d.blocks[1].clone();
+        extra.id = "p2".to_string();
+        d.blocks.push(extra);
+    });
+    let patch = util::bound(
+        &base,
+        json!([{ "op": "insert_after", "block_id": "p1", "new_block_id": "p2", "kind_code": 2, "text": "New paragraph." }]),
+    );
+
+    let report = rebase_patch(&base, &new, &patch).unwrap();
+    assert_eq!(report.conflicts[0].reason, RebaseConflictReason::NewBlockIdTaken);
+}
+
+#[test]
+fn kept_ops_that_fail_validation_on_new_document_are_reported_invalid() {
+    let base = util::load_doc();
+    let new = edited(&base, |d| d.blocks[0].kind_code = 20);
+    let patch = util::bound(
+        &base,
+        json!([
+            { "op": "replace", "block_id": "t1", "before": "Example Page", "after": "Sample Page" },
+            { "op": "replace", "block_id": "p1", "before": "example paragraph with a typo", "after": "x" }
+        ]),
+    );
+    let opts = ValidateOptions { strict_kind_code: true, ..ValidateOptions::default() };
+
+    let report = rebase_patch_with_options(&base, &new, &patch, opts).unwrap();
+    assert_eq!(report.patch.ops.len(), 1);
+    assert_eq!(report.patch.ops[0].block_id, "p1");
+    assert_eq!(report.conflicts.len(), 1);
+    assert_eq!(report.conflicts[0].op_index, 0);
+    assert_eq!(report.conflicts[0].reason, RebaseConflictReason::Invalid);
+}
+
+#[test]
+fn patch_not_bound_to_base_is_rejected() {
+    let base = util::load_doc();
+    let new = edited(&base, |d| set_text(d, "t1", "Updated Page Title"));
+    let patch = util::bound(
+        &new,
+        json!([{ "op": "replace", "block_id": "p1", "before": "example paragraph with a typo", "after": "x" }]),
+    );
+
+    let err = rebase_patch(&base, &new, &patch).unwrap_err();
+    assert_eq!(err.diagnostics[0].code, DiagnosticCode::PatchPageHashMismatch);
+}
+
+#[test]
+fn delete_block_of_a_changed_block_conflicts() {
+    let base = util::load_doc();
+    let new = edited(&base, |d| {
+        set_text(d, "b1", "Home > Docs > Page");
+        set_text(d, "t1", "Updated Page Title");
+    });
+    let patch = util::bound(
+        &base,
+        json!([
+            { "op": "delete_block", "block_id": "b1", "text_hash": base.blocks[2].text_hash },
+            { "op": "delete_block", "block_id": "p1", "text_hash": base.blocks[1].text_hash }
+        ]),
+    );
+
+    let report = rebase_patch(&base, &new, &patch).unwrap();
+    assert_eq!(report.conflicts.len(), 1);
+    assert_eq!(report.conflicts[0].reason, RebaseConflictReason::BlockChanged);
+    assert_eq!(report.conflicts[0].block_id, "b1");
+    assert_eq!(report.patch.ops.len(), 1);
+    assert_eq!(report.patch.ops[0].block_id, "p1");
+}
+
+#[test]
+fn reclassify_of_a_block_reclassified_upstream_conflicts() {
+    let base = util::load_doc();
+    let new = edited(&base, |d| d.blocks[2].kind_code = 21);
+    let patch = util::bound(
+        &base,
+        json!([
+            { "op": "reclassify", "block_id": "b1", "kind_code": 22 },
+            { "op": "reclassify", "block_id": "p1", "kind_code": 20 }
+        ]),
+    );
+
+    let report = rebase_patch(&base, &new, &patch).unwrap();
+    assert_eq!(report.conflicts.len(), 1);
+    assert_eq!(report.conflicts[0].reason, RebaseConflictReason::BlockChanged);
+    assert_eq!(report.conflicts[0].block_id, "b1");
+    assert_eq!(report.patch.ops.len(), 1);
+    assert_eq!(report.patch.ops[0].block_id, "p1");
+}
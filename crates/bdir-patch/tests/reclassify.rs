@@ -0,0 +1,51 @@
+mod util;
+
+use serde_json::json;
+
+use bdir_patch::{
+    apply_patch_against_document,
+    apply_patch_against_edit_packet,
+    validate_patch_with_diagnostics,
+    DiagnosticCode,
+    ValidateOptions,
+};
+
+#[test]
+fn reclassify_changes_kind_code_and_page_hash_of_a_document() {
+    let doc = util::load_doc();
+    let patch = util::bound(&doc, json!([{ "op": "reclassify", "block_id": "p1", "kind_code": 20 }]));
+
+    let out = apply_patch_against_document(&doc, &patch).unwrap();
+    assert_eq!(out.blocks[1].kind_code, 20);
+    assert_eq!(out.blocks[1].text, doc.blocks[1].text);
+    assert_eq!(out.blocks[1].text_hash, doc.blocks[1].text_hash);
+    assert_ne!(out.page_hash, doc.page_hash);
+}
+
+#[test]
+fn reclassify_changes_the_tuple_kind_in_an_edit_packet() {
+    let packet = util::load_edit_packet();
+    let patch = util::bound_to_packet(&packet, json!([{ "op": "reclassify", "block_id": "p1", "kindCode": 20 }]));
+
+    let out = apply_patch_against_edit_packet(&packet, &patch).unwrap();
+    assert_eq!(out.b[1].1, 20);
+    assert_ne!(out.h, packet.h);
+}
+
+#[test]
+fn missing_kind_code_and_stray_fields_are_rejected() {
+    let doc = util::load_doc();
+    let cases = [
+        (json!({ "op": "reclassify", "block_id": "p1" }), DiagnosticCode::MissingField),
+        (
+            json!({ "op": "reclassify", "block_id": "p1", "kind_code": 20, "text": "Navigation" }),
+            DiagnosticCode::UnexpectedField,
+        ),
+    ];
+
+    for (op, code) in cases {
+        let patch = util::bound(&doc, json!([op]));
+        let err = validate_patch_with_diagnostics(&doc, &patch, ValidateOptions::default()).unwrap_err();
+        assert_eq!(err.diagnostics[0].code, code);
+    }
+}
@@ -0,0 +1,157 @@
+mod util;
+
+use serde_json::json;
+
+use bdir_core::model::Document;
+use bdir_editpacket::convert::from_document;
+use bdir_patch::{
+    apply_patch_against_document,
+    apply_patch_against_edit_packet,
+    validate_patch_against_edit_packet_with_diagnostics,
+    validate_patch_with_diagnostics,
+    DiagnosticCode,
+    ValidateOptions,
+};
+
+fn in_section(root: &str) -> ValidateOptions {
+    ValidateOptions { section: Some(root.to_string()), ..ValidateOptions::default() }
+}
+
+fn parents(doc: &Document) -> Vec<(&str, Option<&str>)> {
+    doc.blocks.iter().map(|b| (b.id.as_str(), b.parent.as_deref())).collect()
+}
+
+#[test]
+fn section_ids_follow_parent_chains_in_document_order() {
+    let doc = util::load_sections();
+    assert_eq!(doc.section_ids("h1"), Some(vec!["h1", "p1", "h1a", "p2"]));
+    assert_eq!(doc.section_ids("h1a"), Some(vec!["h1a", "p2"]));
+    assert_eq!(doc.section_ids("p3"), Some(vec!["p3"]));
+    assert_eq!(doc.section_ids("missing"), None);
+}
+
+#[test]
+fn parents_are_metadata_outside_the_page_hash() {
+    let doc = util::load_sections();
+    let mut flat = doc.clone();
+    for b in &mut flat.blocks {
+        b.parent = None;
+    }
+    flat.recompute_hashes();
+    assert_eq!(flat.page_hash, doc.page_hash);
+    assert!(serde_json::to_value(&flat.blocks[1]).unwrap().get("parent").is_none());
+}
+
+#[test]
+fn edit_packets_carry_parents_as_a_side_table() {
+    let doc = util::load_sections();
+    let packet = from_document(&doc, None);
+    let value = serde_json::to_value(&packet).unwrap();
+    assert_eq!(value["p"], json!({ "p1": "h1", "h1a": "h1", "p2": "h1a", "p3": "h2" }));
+    assert_eq!(value["b"][2], json!(["p1", 2, doc.blocks[2].text_hash, "Cats sleep most of the day."]));
+
+    let flat = from_document(&serde_json::from_str(&util::read_example_document_json()).unwrap(), None);
+    assert!(serde_json::to_value(&flat).unwrap().get("p").is_none());
+}
+
+#[test]
+fn section_scope_rejects_ops_outside_the_section() {
+    let doc = util::load_sections();
+    let patch = util::bound(
+        &doc,
+        json!([
+            { "op": "replace", "block_id": "p2", "before": "striped coats", "after": "striped fur" },
+            { "op": "replace", "block_id": "p3", "before": "long walks", "after": "short walks" }
+        ]),
+    );
+
+    validate_patch_with_diagnostics(&doc, &patch, ValidateOptions::default()).unwrap();
+    let err = validate_patch_with_diagnostics(&doc, &patch, in_section("h1")).unwrap_err();
+    let diag = &err.diagnostics[0];
+    assert_eq!(diag.code, DiagnosticCode::OutOfSection);
+    assert_eq!(diag.path.as_deref(), Some("ops[1].block_id"));
+    assert_eq!(diag.message, "ops[1] (replace) references a block outside section 'h1' ('p3')");
+
+    let packet = from_document(&doc, None);
+    let err = validate_patch_against_edit_packet_with_diagnostics(&packet, &patch, in_section("h1a")).unwrap_err();
+    assert_eq!(err.diagnostics[0].op_index, Some(1));
+}
+
+#[test]
+fn section_scope_checks_anchors_and_insert_positions() {
+    let doc = util::load_sections();
+
+    let patch = util::bound(
+        &doc,
+        json!([
+            { "op": "insert_after", "block_id": "h1a", "new_block_id": "h1a0", "kind_code": 2, "text": "Some are orange." },
+            { "op": "move", "block_id": "p1", "after_block_id": "p2" }
+        ]),
+    );
+    validate_patch_with_diagnostics(&doc, &patch, in_section("h1")).unwrap();
+
+    let moves_out = util::bound(&doc, json!([{ "op": "move", "block_id": "p1", "after_block_id": "p3" }]));
+    let err = validate_patch_with_diagnostics(&doc, &moves_out, in_section("h1")).unwrap_err();
+    assert_eq!(err.diagnostics[0].path.as_deref(), Some("ops[0].after_block_id"));
+
+    let before_root = util::bound(
+        &doc,
+        json!([{ "op": "insert_before", "block_id": "h1", "new_block_id": "x", "kind_code": 2, "text": "Intro" }]),
+    );
+    let err = validate_patch_with_diagnostics(&doc, &before_root, in_section("h1")).unwrap_err();
+    assert_eq!(err.diagnostics[0].message, "ops[0] (insert_before) would insert before section 'h1' ('h1')");
+
+    let err = validate_patch_with_diagnostics(&doc, &before_root, in_section("nope")).unwrap_err();
+    assert_eq!(err.diagnostics[0].code, DiagnosticCode::SectionNotFound);
+    assert_eq!(err.diagnostics[0].path.as_deref(), Some("section"));
+
+    let collect_all = ValidateOptions { collect_all_diagnostics: true, ..in_section("nope") };
+    let unknown = util::bound(&doc, json!([{ "op": "delete_block", "block_id": "zz" }]));
+    let err = validate_patch_with_diagnostics(&doc, &unknown, collect_all).unwrap_err();
+    let codes: Vec<_> = err.diagnostics.iter().map(|d| d.code).collect();
+    assert_eq!(codes, vec![DiagnosticCode::SectionNotFound, DiagnosticCode::UnknownBlockId]);
+}
+
+#[test]
+fn apply_keeps_sections_connected() {
+    let doc = util::load_sections();
+    let patch = util::bound(
+        &doc,
+        json!([
+            { "op": "insert_after", "block_id": "p1", "new_block_id": "p1b", "kind_code": 2, "text": "Kittens play." },
+            { "op": "split_block", "block_id": "p3", "before": "walks outside", "new_block_id": "p3b" },
+            { "op": "merge_block", "block_id": "h1a", "next_block_id": "p2" },
+            { "op": "delete_block", "block_id": "h1", "text_hash": doc.blocks[1].text_hash }
+        ]),
+    );
+
+    let out = apply_patch_against_document(&doc, &patch).unwrap();
+    assert_eq!(
+        parents(&out),
+        vec![
+            ("title", None),
+            ("p1", None),
+            ("p1b", None),
+            ("h1a", None),
+            ("h2", None),
+            ("p3", Some("h2")),
+            ("p3b", Some("h2")),
+        ]
+    );
+
+    let packet = apply_patch_against_edit_packet(&from_document(&doc, None), &patch).unwrap();
+    assert_eq!(packet.p, from_document(&out, None).p);
+}
+
+#[test]
+fn merging_a_heading_away_moves_its_children_to_the_surviving_block() {
+    let doc = util::load_sections();
+    let patch = util::bound(&doc, json!([{ "op": "merge_block", "block_id": "p1", "next_block_id": "h1a" }]));
+
+    let out = apply_patch_against_document(&doc, &patch).unwrap();
+    assert_eq!(out.section_ids("p1"), Some(vec!["p1", "p2"]));
+
+    let packet = apply_patch_against_edit_packet(&from_document(&doc, None), &patch).unwrap();
+    assert_eq!(packet.p.get("p2").map(String::as_str), Some("p1"));
+    assert!(!packet.p.contains_key("h1a"));
+}
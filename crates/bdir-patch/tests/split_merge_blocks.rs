@@ -0,0 +1,182 @@
+mod util;
+
+use serde_json::json;
+
+use bdir_core::model::Document;
+use bdir_editpacket::convert::from_document;
+use bdir_patch::{
+    apply_patch_against_document,
+    canonicalize_patch_ops_against_edit_packet,
+    invert_patch,
+    DiagnosticCode,
+    ValidationDiagnostic,
+};
+
+fn texts(doc: &Document) -> Vec<(&str, u16, &str)> {
+    doc.blocks.iter().map(|b| (b.id.as_str(), b.kind_code, b.text.as_str())).collect()
+}
+
+#[test]
+fn split_then_merge_round_trips_a_document() {
+    let doc = util::load_doc();
+    let split = util::bound(
+        &doc,
+        json!([{ "op": "split_block", "block_id": "p1", "before": "paragraph with", "new_block_id": "p1b", "kind_code": 3 }]),
+    );
+
+    let out = apply_patch_against_document(&doc, &split).unwrap();
+    assert_eq!(
+        texts(&out),
+        vec![
+            ("t1", 0, "Example Page Title"),
+            ("p1", 2, "This is an example"),
+            ("p1b", 3, "paragraph with a typo teh."),
+            ("b1", 20, "Home > Section > Page"),
+        ]
+    );
+
+    let merge = util::bound(&out, json!([{ "op": "merge_block", "block_id": "p1", "nextBlockId": "p1b" }]));
+    let back = apply_patch_against_document(&out, &merge).unwrap();
+    assert_eq!(texts(&back), texts(&doc));
+    assert_eq!(back.page_hash, doc.page_hash);
+}
+
+#[test]
+fn split_and_merge_are_invertible() {
+    let doc = util::load_doc();
+    for ops in [
+        json!([{ "op": "split_block", "block_id": "p1", "before": "paragraph with", "new_block_id": "p1b" }]),
+        json!([{ "op": "merge_block", "block_id": "t1", "next_block_id": "p1" }]),
+    ] {
+        let patch = util::bound(&doc, ops);
+        let updated = apply_patch_against_document(&doc, &patch).unwrap();
+        let inverse = invert_patch(&doc, &patch).unwrap();
+        let restored = apply_patch_against_document(&updated, &inverse).unwrap();
+        assert_eq!(restored.blocks, doc.blocks);
+    }
+}
+
+#[test]
+fn split_anchor_must_be_unique_and_leave_text_behind() {
+    let doc = util::load_doc();
+    let split = |before: &str, new_id: &str| {
+        json!([{ "op": "split_block", "block_id": "p1", "before": before, "new_block_id": new_id }])
+    };
+
+    let cases = [
+        (split("This is an", "p1b"), DiagnosticCode::ContentEmpty),
+        (split("not in the block", "p1b"), DiagnosticCode::BeforeNotFound),
+        (split("paragraph with", "b1"), DiagnosticCode::DuplicateBlockId),
+        (json!([{ "op": "split_block", "block_id": "p1", "before": "paragraph with" }]), DiagnosticCode::MissingField),
+    ];
+    for (ops, code) in cases {
+        assert_eq!(util::first_diagnostic(&doc, ops).code, code);
+    }
+}
+
+#[test]
+fn new_block_ids_must_be_unique_within_the_patch() {
+    let doc = util::load_doc();
+    let ValidationDiagnostic { code, message, .. } = util::first_diagnostic(
+        &doc,
+        json!([
+            { "op": "split_block", "block_id": "p1", "before": "paragraph with", "new_block_id": "p2" },
+            { "op": "insert_after", "block_id": "b1", "new_block_id": "p2", "kind_code": 2, "text": "Closing." }
+        ]),
+    );
+    assert_eq!(code, DiagnosticCode::DuplicateBlockId);
+    assert_eq!(message, "ops[1] (insert_after) new_block_id 'p2' is already created by ops[0]");
+
+    let ValidationDiagnostic { code, .. } = util::first_diagnostic(
+        &doc,
+        json!([
+            { "op": "insert_before", "block_id": "t1", "new_block_id": "p0", "kind_code": 2, "text": "Lead." },
+            { "op": "insert_after", "block_id": "b1", "new_block_id": "p0", "kind_code": 2, "text": "Closing." }
+        ]),
+    );
+    assert_eq!(code, DiagnosticCode::DuplicateBlockId);
+}
+
+#[test]
+fn merge_requires_the_directly_following_block() {
+    let doc = util::load_doc();
+
+    let ValidationDiagnostic { code, message, .. } =
+        util::first_diagnostic(&doc, json!([{ "op": "merge_block", "block_id": "t1", "next_block_id": "b1" }]));
+    assert_eq!(code, DiagnosticCode::BlocksNotAdjacent);
+    assert_eq!(message, "ops[0] (merge_block) next_block_id 'b1' does not directly follow block 't1'");
+
+    let ValidationDiagnostic { code, .. } =
+        util::first_diagnostic(&doc, json!([{ "op": "merge_block", "block_id": "t1", "next_block_id": "nope" }]));
+    assert_eq!(code, DiagnosticCode::UnknownBlockId);
+}
+
+#[test]
+fn ops_after_a_merge_may_not_target_the_merged_away_block() {
+    let doc = util::load_doc();
+    let ValidationDiagnostic { code, message, .. } = util::first_diagnostic(
+        &doc,
+        json!([
+            { "op": "merge_block", "block_id": "t1", "next_block_id": "p1" },
+            { "op": "replace", "block_id": "p1", "before": "with a typo teh", "after": "with a typo the" }
+        ]),
+    );
+    assert_eq!(code, DiagnosticCode::TargetsDeletedBlock);
+    assert_eq!(message, "ops[1] targets block 'p1', which ops[0] merges away");
+}
+
+#[test]
+fn edits_after_split_and_split_merge_combinations_are_structural_conflicts() {
+    let doc = util::load_doc();
+    let split = json!({ "op": "split_block", "block_id": "p1", "before": "paragraph with", "new_block_id": "p1b" });
+
+    let ValidationDiagnostic { code, message, .. } = util::first_diagnostic(
+        &doc,
+        json!([split, { "op": "replace", "block_id": "p1", "before": "with a typo teh", "after": "with a typo the" }]),
+    );
+    assert_eq!(code, DiagnosticCode::StructuralConflict);
+    assert_eq!(
+        message,
+        "ops[1] (replace) targets block 'p1' after ops[0] splits it; edit the block before splitting it"
+    );
+
+    let merge = json!({ "op": "merge_block", "block_id": "t1", "next_block_id": "p1" });
+    let ValidationDiagnostic { code, .. } = util::first_diagnostic(&doc, json!([split, merge]));
+    assert_eq!(code, DiagnosticCode::StructuralConflict);
+
+    let ValidationDiagnostic { code, message, .. } = util::first_diagnostic(
+        &doc,
+        json!([
+            { "op": "merge_block", "block_id": "p1", "next_block_id": "b1" },
+            { "op": "merge_block", "block_id": "t1", "next_block_id": "p1" }
+        ]),
+    );
+    assert_eq!(code, DiagnosticCode::StructuralConflict);
+    assert_eq!(
+        message,
+        "ops[0] (merge_block) merges into block 'p1', which ops[1] merges away; merge chains need separate patches"
+    );
+}
+
+#[test]
+fn merge_sorts_after_edits_of_the_merged_away_block() {
+    let doc = util::load_doc();
+    let packet = from_document(&doc, None);
+    let mut patch = util::bound(
+        &doc,
+        json!([
+            { "op": "replace", "block_id": "p1", "before": "with a typo teh", "after": "with a typo the" },
+            { "op": "merge_block", "block_id": "t1", "next_block_id": "p1" },
+            { "op": "split_block", "block_id": "b1", "before": "Section > Page", "new_block_id": "b2" }
+        ]),
+    );
+    let expected = apply_patch_against_document(&doc, &patch).unwrap();
+
+    canonicalize_patch_ops_against_edit_packet(&packet, &mut patch);
+    let order: Vec<&str> = patch.ops.iter().map(|o| o.op.as_str()).collect();
+    assert_eq!(order, vec!["replace", "merge_block", "split_block"]);
+
+    let canonical = apply_patch_against_document(&doc, &patch).unwrap();
+    assert_eq!(texts(&canonical), texts(&expected));
+    assert_eq!(canonical.blocks[0].text, "Example Page Title This is an example paragraph with a typo the.");
+}
@@ -0,0 +1,119 @@
+mod util;
+
+use serde_json::json;
+
+use bdir_editpacket::convert::from_document;
+use bdir_patch::{
+    apply_patch_against_document_with_options,
+    apply_patch_against_document_with_telemetry,
+    apply_patch_against_edit_packet_with_options,
+    tolerant_match_diagnostics,
+    validate_patch_with_diagnostics,
+    DiagnosticCode,
+    MatchMode,
+    PatchV1,
+    ValidateOptions,
+};
+
+const QUOTED: &str = "She said \u{201c}hello\u{a0}there\u{201d} \u{2014} and  left the room.";
+
+fn tolerant() -> ValidateOptions {
+    ValidateOptions { match_mode: MatchMode::Tolerant, ..ValidateOptions::default() }
+}
+
+#[test]
+fn exact_mode_stays_the_default() {
+    let doc = util::doc_with_p1(QUOTED);
+    let patch =
+        util::bound(&doc, json!([{ "op": "replace", "block_id": "p1", "before": "\"hello there\"", "after": "hi" }]));
+
+    assert_eq!(ValidateOptions::default().match_mode, MatchMode::Exact);
+    let err = validate_patch_with_diagnostics(&doc, &patch, ValidateOptions::default()).unwrap_err();
+    assert_eq!(err.diagnostics[0].code, DiagnosticCode::BeforeNotFound);
+    assert!(tolerant_match_diagnostics(&doc, &patch, &ValidateOptions::default()).is_empty());
+}
+
+#[test]
+fn tolerant_replace_edits_the_original_characters() {
+    let doc = util::doc_with_p1(QUOTED);
+    let patch = util::bound(
+        &doc,
+        json!([{ "op": "replace", "block_id": "p1", "before": "\"hello there\" - and left", "after": "goodbye, and left" }]),
+    );
+
+    let out = apply_patch_against_document_with_options(&doc, &patch, tolerant()).unwrap();
+    assert_eq!(out.blocks[1].text, "She said goodbye, and left the room.");
+}
+
+#[test]
+fn tolerant_delete_and_occurrence_select_among_folded_matches() {
+    let doc = util::doc_with_p1("Pages 10\u{2013}12 and pages 10\u{2014}12 are  missing.");
+    let patch = util::bound(
+        &doc,
+        json!([{ "op": "delete", "block_id": "p1", "before": "pages 10-12 ", "occurrence": 1 }]),
+    );
+
+    let out = apply_patch_against_document_with_options(&doc, &patch, tolerant()).unwrap();
+    assert_eq!(out.blocks[1].text, "Pages 10\u{2013}12 and are  missing.");
+
+    let ambiguous = util::bound(&doc, json!([{ "op": "delete", "block_id": "p1", "before": "10-12 " }]));
+    let err = validate_patch_with_diagnostics(&doc, &ambiguous, ValidateOptions { min_before_len: 4, ..tolerant() })
+        .unwrap_err();
+    assert_eq!(err.diagnostics[0].code, DiagnosticCode::BeforeAmbiguous);
+}
+
+#[test]
+fn tolerant_split_uses_the_mapped_position() {
+    let doc = util::doc_with_p1(QUOTED);
+    let patch = util::bound(
+        &doc,
+        json!([{ "op": "split_block", "block_id": "p1", "before": "- and left", "new_block_id": "p1b" }]),
+    );
+
+    let out = apply_patch_against_document_with_options(&doc, &patch, tolerant()).unwrap();
+    assert_eq!(out.blocks[1].text, "She said \u{201c}hello\u{a0}there\u{201d}");
+    assert_eq!(out.blocks[2].text, "\u{2014} and  left the room.");
+}
+
+#[test]
+fn verbatim_matches_are_not_reported_as_tolerant() {
+    let doc = util::doc_with_p1(QUOTED);
+    let patch = util::bound(
+        &doc,
+        json!([
+            { "op": "replace", "block_id": "p1", "before": "She said", "after": "He said" },
+            { "op": "delete", "block_id": "p1", "before": " and left" }
+        ]),
+    );
+
+    let notes = tolerant_match_diagnostics(&doc, &patch, &tolerant());
+    assert_eq!(notes.len(), 1);
+    assert_eq!(notes[0].code, DiagnosticCode::TolerantMatch);
+    assert_eq!(notes[0].op_index, Some(1));
+    assert_eq!(notes[0].path.as_deref(), Some("ops[1].before"));
+    assert_eq!(
+        notes[0].message,
+        "ops[1] (delete) before matched block 'p1' only after whitespace/typography normalization"
+    );
+
+    let (res, tel) = apply_patch_against_document_with_telemetry(&doc, &patch, tolerant());
+    assert_eq!(res.unwrap().blocks[1].text, "He said \u{201c}hello\u{a0}there\u{201d} \u{2014} the room.");
+    assert_eq!(tel.tolerant_match_ops, vec![1]);
+}
+
+#[test]
+fn tolerant_matching_applies_to_edit_packets_and_context() {
+    let doc = util::doc_with_p1("\u{201c}Yes\u{201d}, she said. \u{201c}Yes\u{201d}, he said.");
+    let packet = from_document(&doc, None);
+    let patch: PatchV1 = serde_json::from_value(json!({
+        "v": 1,
+        "h": packet.h,
+        "ha": packet.ha,
+        "ops": [{ "op": "replace", "block_id": "p1", "before": "\"Yes\", ", "after": "\"No\", ", "context_after": "he said" }]
+    }))
+    .unwrap();
+
+    let opts = ValidateOptions { min_before_len: 4, ..tolerant() };
+    let out = apply_patch_against_edit_packet_with_options(&packet, &patch, opts).unwrap();
+    assert_eq!(out.b[1].3, "\u{201c}Yes\u{201d}, she said. \"No\", he said.");
+}
@@ -34,6 +34,7 @@ fn make_packet_single_block(text: &str) -> EditPacketV1 {
         h,
         ha: algo.to_string(),
         b,
+        p: Default::default(),
     }
 }
 
@@ -60,6 +61,11 @@ fn validate_and_apply_respects_unicode_nfc_normalization() {
             new_block_id: None,
             kind_code: None,
             text: None,
+            text_hash: None,
+            after_block_id: None,
+            context_before: None,
+            context_after: None,
+            next_block_id: None,
             // suggest fields
             message: None,
             severity: None,
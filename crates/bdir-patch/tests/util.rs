@@ -1,5 +1,10 @@
 use std::path::{Path, PathBuf};
 
+use serde_json::json;
+
+use bdir_core::model::Document;
+use bdir_patch::{validate_patch_with_diagnostics, EditPacketV1, PatchV1, ValidateOptions, ValidationDiagnostic};
+
 #[allow(dead_code)]
 pub fn workspace_root() -> PathBuf {
     Path::new(env!("CARGO_MANIFEST_DIR"))
@@ -15,6 +20,7 @@ pub fn read_example_document_json() -> String {
     })
 }
 
+#[allow(dead_code)]
 pub fn read_fixture(name: &str) -> String {
     let path = Path::new(env!("CARGO_MANIFEST_DIR"))
         .join("tests")
@@ -25,3 +31,60 @@ pub fn read_fixture(name: &str) -> String {
         panic!("failed to read fixture {} at {}: {e}", name, path.display())
     })
 }
+
+/// `examples/document.json` with block and page hashes recomputed.
+#[allow(dead_code)]
+pub fn load_doc() -> Document {
+    let mut doc: Document = serde_json::from_str(&read_example_document_json()).expect("document must parse");
+    doc.recompute_hashes();
+    doc
+}
+
+/// The sectioned fixture document (`tests/fixtures/document.sections.json`).
+#[allow(dead_code)]
+pub fn load_sections() -> Document {
+    let mut doc: Document =
+        serde_json::from_str(&read_fixture("document.sections.json")).expect("document must parse");
+    doc.recompute_hashes();
+    doc
+}
+
+/// The example document with the text of `p1` replaced.
+#[allow(dead_code)]
+pub fn doc_with_p1(text: &str) -> Document {
+    let mut doc = load_doc();
+    doc.blocks.iter_mut().find(|b| b.id == "p1").unwrap().text = text.to_string();
+    doc.recompute_hashes();
+    doc
+}
+
+#[allow(dead_code)]
+pub fn load_edit_packet() -> EditPacketV1 {
+    let path = workspace_root().join("examples").join("edit-packet.json");
+    let json = std::fs::read_to_string(&path).expect("read examples/edit-packet.json");
+    serde_json::from_str(&json).expect("edit packet must parse")
+}
+
+#[allow(dead_code)]
+pub fn load_patch(name: &str) -> PatchV1 {
+    serde_json::from_str(&read_fixture(name)).expect("patch fixture must parse")
+}
+
+/// A v1 patch of `ops` bound to the page hash of `doc`.
+#[allow(dead_code)]
+pub fn bound(doc: &Document, ops: serde_json::Value) -> PatchV1 {
+    serde_json::from_value(json!({ "v": 1, "h": doc.page_hash, "ha": doc.hash_algorithm, "ops": ops })).unwrap()
+}
+
+/// A v1 patch of `ops` bound to the page hash of `packet`.
+#[allow(dead_code)]
+pub fn bound_to_packet(packet: &EditPacketV1, ops: serde_json::Value) -> PatchV1 {
+    serde_json::from_value(json!({ "v": 1, "h": packet.h, "ha": packet.ha, "ops": ops })).unwrap()
+}
+
+/// First diagnostic of a bound patch of `ops` that is expected to fail validation.
+#[allow(dead_code)]
+pub fn first_diagnostic(doc: &Document, ops: serde_json::Value) -> ValidationDiagnostic {
+    let err = validate_patch_with_diagnostics(doc, &bound(doc, ops), ValidateOptions::default()).unwrap_err();
+    err.diagnostics.into_iter().next().unwrap()
+}
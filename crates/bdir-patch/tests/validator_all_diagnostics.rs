@@ -0,0 +1,68 @@
+mod util;
+
+use serde_json::json;
+
+use bdir_patch::{
+    validate_patch_against_edit_packet_with_diagnostics,
+    DiagnosticCode,
+    PatchV1,
+    ValidateOptions,
+};
+
+fn multi_error_patch() -> PatchV1 {
+    serde_json::from_value(json!({
+        "v": 1,
+        "h": "00000000deadbeef",
+        "ha": "xxh64",
+        "ops": [
+            { "op": "replace", "block_id": "p1", "before": "example paragraph", "after": "sample paragraph" },
+            { "op": "replace", "block_id": "nope", "before": "example paragraph", "after": "x" },
+            { "op": "replace", "block_id": "p1", "before": "not in the block", "after": "x" },
+            { "op": "suggest", "block_id": "t1", "message": "   " }
+        ]
+    }))
+    .unwrap()
+}
+
+#[test]
+fn fail_fast_is_the_default() {
+    let packet = util::load_edit_packet();
+    let err = validate_patch_against_edit_packet_with_diagnostics(&packet, &multi_error_patch(), ValidateOptions::default())
+        .unwrap_err();
+
+    assert_eq!(err.diagnostics.len(), 1);
+    assert_eq!(err.diagnostics[0].code, DiagnosticCode::PatchPageHashMismatch);
+}
+
+#[test]
+fn collect_all_returns_every_diagnostic_in_deterministic_order() {
+    let packet = util::load_edit_packet();
+    let opts = ValidateOptions { collect_all_diagnostics: true, ..ValidateOptions::default() };
+    let err = validate_patch_against_edit_packet_with_diagnostics(&packet, &multi_error_patch(), opts.clone())
+        .unwrap_err();
+
+    let got: Vec<(DiagnosticCode, Option<usize>)> =
+        err.diagnostics.iter().map(|d| (d.code, d.op_index)).collect();
+    assert_eq!(
+        got,
+        vec![
+            (DiagnosticCode::PatchPageHashMismatch, None),
+            (DiagnosticCode::UnknownBlockId, Some(1)),
+            (DiagnosticCode::BeforeNotFound, Some(2)),
+            (DiagnosticCode::MessageEmpty, Some(3)),
+        ]
+    );
+
+    // Re-running yields the identical report.
+    let again = validate_patch_against_edit_packet_with_diagnostics(&packet, &multi_error_patch(), opts).unwrap_err();
+    assert_eq!(err, again);
+}
+
+#[test]
+fn collect_all_on_a_valid_patch_is_ok() {
+    let packet = util::load_edit_packet();
+    let patch: PatchV1 = serde_json::from_str(&util::read_fixture("patch.valid.json")).unwrap();
+    let opts = ValidateOptions { collect_all_diagnostics: true, ..ValidateOptions::default() };
+
+    validate_patch_against_edit_packet_with_diagnostics(&packet, &patch, opts).unwrap();
+}
@@ -0,0 +1,95 @@
+mod util;
+
+use serde_json::json;
+
+use bdir_editpacket::convert::{from_document_with_options, EditPacketOptions};
+use bdir_patch::{
+    validate_patch_against_edit_packet_with_diagnostics,
+    validate_patch_with_diagnostics,
+    DiagnosticCode,
+    PatchV1,
+    ValidateOptions,
+};
+
+fn verify_opts() -> ValidateOptions {
+    ValidateOptions { verify_block_hashes: true, ..ValidateOptions::default() }
+}
+
+#[test]
+fn untampered_packet_passes_block_hash_verification() {
+    let packet = util::load_edit_packet();
+    let patch = util::load_patch("patch.valid.json");
+
+    validate_patch_against_edit_packet_with_diagnostics(&packet, &patch, verify_opts()).unwrap();
+}
+
+#[test]
+fn tampered_block_text_is_rejected_only_when_verification_is_enabled() {
+    let mut packet = util::load_edit_packet();
+    packet.b[1].3.push_str(" Injected.");
+    let patch = util::load_patch("patch.valid.json");
+
+    validate_patch_against_edit_packet_with_diagnostics(&packet, &patch, ValidateOptions::default())
+        .expect("verification is opt-in");
+
+    let err = validate_patch_against_edit_packet_with_diagnostics(&packet, &patch, verify_opts()).unwrap_err();
+    let diag = &err.diagnostics[0];
+    assert_eq!(diag.code, DiagnosticCode::BlockHashMismatch);
+    assert_eq!(diag.op_index, Some(0));
+    assert_eq!(diag.block_id.as_deref(), Some("p1"));
+    assert_eq!(diag.path.as_deref(), Some("ops[0].block_id"));
+}
+
+#[test]
+fn truncated_block_hashes_pass_verification() {
+    let packet = from_document_with_options(&util::load_doc(), None, EditPacketOptions { hash_prefix_len: Some(8) });
+    let patch = util::load_patch("patch.valid.json");
+
+    validate_patch_against_edit_packet_with_diagnostics(&packet, &patch, verify_opts()).unwrap();
+}
+
+#[test]
+fn block_hash_shorter_than_minimum_is_rejected() {
+    let mut packet = util::load_edit_packet();
+    packet.b[1].2 = "6dcea19".to_string();
+    let patch = util::load_patch("patch.valid.json");
+
+    let err = validate_patch_against_edit_packet_with_diagnostics(&packet, &patch, verify_opts()).unwrap_err();
+    assert_eq!(err.diagnostics[0].code, DiagnosticCode::HashTooShort);
+}
+
+#[test]
+fn stale_document_text_hash_is_rejected() {
+    let mut doc = util::load_doc();
+    let p1 = doc.blocks.iter_mut().find(|b| b.id == "p1").unwrap();
+    p1.text_hash = "0000000000000000".to_string();
+    let patch = util::load_patch("patch.valid.json");
+
+    let err = validate_patch_with_diagnostics(&doc, &patch, verify_opts()).unwrap_err();
+    let diag = &err.diagnostics[0];
+    assert_eq!(diag.code, DiagnosticCode::BlockHashMismatch);
+    assert_eq!(
+        diag.message,
+        "ops[0] targets block 'p1' whose text_hash '0000000000000000' does not match its text (computed '6dcea198d9a3abcd')"
+    );
+}
+
+#[test]
+fn merge_and_move_verify_every_block_they_read() {
+    let mut doc = util::load_doc();
+    doc.blocks.iter_mut().find(|b| b.id == "b1").unwrap().text.push_str(" > Injected");
+    let patch = |op: serde_json::Value| -> PatchV1 {
+        serde_json::from_value(json!({ "v": 1, "h": doc.page_hash, "ha": doc.hash_algorithm, "ops": [op] })).unwrap()
+    };
+
+    let merge = patch(json!({ "op": "merge_block", "block_id": "p1", "next_block_id": "b1" }));
+    validate_patch_with_diagnostics(&doc, &merge, ValidateOptions::default()).expect("verification is opt-in");
+    let err = validate_patch_with_diagnostics(&doc, &merge, verify_opts()).unwrap_err();
+    assert_eq!(err.diagnostics[0].code, DiagnosticCode::BlockHashMismatch);
+    assert_eq!(err.diagnostics[0].path.as_deref(), Some("ops[0].next_block_id"));
+
+    let moved = patch(json!({ "op": "move", "block_id": "t1", "after_block_id": "b1" }));
+    let err = validate_patch_with_diagnostics(&doc, &moved, verify_opts()).unwrap_err();
+    assert_eq!(err.diagnostics[0].block_id.as_deref(), Some("b1"));
+    assert_eq!(err.diagnostics[0].path.as_deref(), Some("ops[0].after_block_id"));
+}
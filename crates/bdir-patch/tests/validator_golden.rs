@@ -140,7 +140,7 @@ fn unsupported_version_fails_with_stable_message() {
 
     let opts = ValidateOptions { expected_page_hash: Some(doc.page_hash.clone()), ..ValidateOptions::default() };
     let err = validate_patch_with_options(&doc, &patch, opts).unwrap_err();
-    assert_eq!(err, "unsupported patch version 2");
+    assert_eq!(err, "unsupported patch version 3");
 }
 
 #[test]
@@ -205,3 +205,19 @@ fn missing_page_hash_binding_is_rejected_by_default() {
         "patch is missing required page hash binding: include patch.h or provide expected_page_hash"
     );
 }
+
+#[test]
+fn unregistered_hash_algorithm_is_rejected() {
+    let mut doc = load_doc();
+    doc.hash_algorithm = "md5".to_string();
+    let patch = load_patch("patch.valid.json");
+
+    let opts = ValidateOptions { expected_page_hash: Some(doc.page_hash.clone()), ..ValidateOptions::default() };
+    let err = validate_patch_with_diagnostics(&doc, &patch, opts).unwrap_err();
+    let diag = &err.diagnostics[0];
+    assert_eq!(diag.code, DiagnosticCode::UnsupportedHashAlgorithm);
+    assert_eq!(
+        diag.message,
+        "unsupported hash algorithm 'md5' (supported: blake3, sha256, sha512_256, xxh3, xxh64)"
+    );
+}
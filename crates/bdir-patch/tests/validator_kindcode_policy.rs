@@ -14,12 +14,14 @@ fn make_doc() -> Document {
                 kind_code: 2,
                 text_hash: "".to_string(),
                 text: "Hello world".to_string(),
+                parent: None,
             },
             Block {
                 id: "boiler".to_string(),
                 kind_code: 25,
                 text_hash: "".to_string(),
                 text: "Cookie banner".to_string(),
+                parent: None,
             },
         ],
     };
@@ -43,13 +45,20 @@ fn strict_kindcode_blocks_mutation_outside_default_allow_range() {
             new_block_id: None,
             kind_code: None,
             text: None,
+            text_hash: None,
+            after_block_id: None,
+            context_before: None,
+            context_after: None,
+            next_block_id: None,
             message: None,
             severity: None,
         }],
     };
 
-    let mut opts = ValidateOptions::default();
-    opts.strict_kind_code = true;
+    let opts = ValidateOptions {
+        strict_kind_code: true,
+        ..ValidateOptions::default()
+    };
 
     let err = validate_patch_with_diagnostics(&doc, &patch, opts).unwrap_err();
     assert_eq!(err.diagnostics[0].code, DiagnosticCode::KindCodeDisallowed);
@@ -71,13 +80,20 @@ fn strict_kindcode_allows_suggest_any_by_default() {
             new_block_id: None,
             kind_code: None,
             text: None,
+            text_hash: None,
+            after_block_id: None,
+            context_before: None,
+            context_after: None,
+            next_block_id: None,
             message: Some("Consider minimizing this banner.".to_string()),
             severity: None,
         }],
     };
 
-    let mut opts = ValidateOptions::default();
-    opts.strict_kind_code = true;
+    let opts = ValidateOptions {
+        strict_kind_code: true,
+        ..ValidateOptions::default()
+    };
 
     validate_patch_with_diagnostics(&doc, &patch, opts).unwrap();
 }
@@ -100,17 +116,65 @@ fn strict_kindcode_allows_custom_ranges() {
             new_block_id: None,
             kind_code: None,
             text: None,
+            text_hash: None,
+            after_block_id: None,
+            context_before: None,
+            context_after: None,
+            next_block_id: None,
             message: None,
             severity: None,
         }],
     };
 
-    let mut opts = ValidateOptions::default();
-    opts.strict_kind_code = true;
-    opts.kind_code_policy = KindCodePolicy {
-        allow_ranges: vec![(20, 39)],
-        allow_suggest_any: true,
+    let opts = ValidateOptions {
+        strict_kind_code: true,
+        kind_code_policy: KindCodePolicy {
+            allow_ranges: vec![(20, 39)],
+            allow_suggest_any: true,
+            allow_boilerplate_promotion: false,
+        },
+        ..ValidateOptions::default()
+    };
+
+    validate_patch_with_diagnostics(&doc, &patch, opts).unwrap();
+}
+
+fn reclassify_patch(doc: &Document, block_id: &str, kind_code: u16) -> PatchV1 {
+    PatchV1 {
+        v: 1,
+        h: Some(doc.page_hash.clone()),
+        ha: Some(doc.hash_algorithm.clone()),
+        ops: vec![PatchOpV1::reclassify(block_id, kind_code)],
+    }
+}
+
+#[test]
+fn strict_kindcode_forbids_promoting_boilerplate_into_core() {
+    let doc = make_doc();
+    let patch = reclassify_patch(&doc, "boiler", 2);
+    let mut opts = ValidateOptions {
+        strict_kind_code: true,
+        kind_code_policy: KindCodePolicy { allow_ranges: vec![(0, 39)], ..KindCodePolicy::default() },
+        ..ValidateOptions::default()
     };
 
+    let err = validate_patch_with_diagnostics(&doc, &patch, opts.clone()).unwrap_err();
+    let diag = &err.diagnostics[0];
+    assert_eq!(diag.code, DiagnosticCode::KindCodeDisallowed);
+    assert_eq!(diag.path.as_deref(), Some("ops[0].kind_code"));
+
+    opts.kind_code_policy.allow_boilerplate_promotion = true;
+    validate_patch_with_diagnostics(&doc, &patch, opts).unwrap();
+
+    // Non-strict mode does not gate reclassification.
+    validate_patch_with_diagnostics(&doc, &patch, ValidateOptions::default()).unwrap();
+}
+
+#[test]
+fn strict_kindcode_allows_demoting_core_into_boilerplate() {
+    let doc = make_doc();
+    let patch = reclassify_patch(&doc, "core", 20);
+    let opts = ValidateOptions { strict_kind_code: true, ..ValidateOptions::default() };
+
     validate_patch_with_diagnostics(&doc, &patch, opts).unwrap();
 }
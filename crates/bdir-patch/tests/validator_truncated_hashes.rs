@@ -0,0 +1,89 @@
+mod util;
+
+use bdir_editpacket::convert::{from_document_with_options, EditPacketOptions};
+use bdir_patch::{
+    validate_patch_against_edit_packet_with_diagnostics,
+    DiagnosticCode,
+    EditPacketV1,
+    PatchV1,
+    ValidateOptions,
+};
+
+fn load_spec_fixture(name: &str) -> (EditPacketV1, PatchV1) {
+    let path = util::workspace_root()
+        .join("spec")
+        .join("fixtures")
+        .join("validation")
+        .join(name);
+    let json = std::fs::read_to_string(&path)
+        .unwrap_or_else(|e| panic!("failed to read {}: {e}", path.display()));
+    let v: serde_json::Value = serde_json::from_str(&json).expect("fixture must parse");
+    let packet = serde_json::from_value(v["packet"].clone()).expect("fixture packet must parse");
+    let patch = serde_json::from_value(v["patch"].clone()).expect("fixture patch must parse");
+    (packet, patch)
+}
+
+#[test]
+fn spec_v008_truncated_hashes_validate() {
+    let (packet, patch) = load_spec_fixture("v008-truncated-hashes-valid.json");
+    let opts = ValidateOptions { min_before_len: 4, ..ValidateOptions::default() };
+    validate_patch_against_edit_packet_with_diagnostics(&packet, &patch, opts).unwrap();
+}
+
+#[test]
+fn spec_v009_hash_shorter_than_8_chars_is_rejected() {
+    let (packet, patch) = load_spec_fixture("v009-truncated-hash-too-short.json");
+    let opts = ValidateOptions { min_before_len: 4, ..ValidateOptions::default() };
+    let err = validate_patch_against_edit_packet_with_diagnostics(&packet, &patch, opts).unwrap_err();
+    let diag = &err.diagnostics[0];
+    assert_eq!(diag.code, DiagnosticCode::HashTooShort);
+    assert_eq!(diag.path.as_deref(), Some("h"));
+    assert_eq!(
+        diag.message,
+        "patch.h 'abcdef0' is too short (<8 hex chars); truncated hashes must keep at least 8"
+    );
+}
+
+#[test]
+fn truncated_patch_hash_binds_to_full_packet_hash() {
+    let packet = util::load_edit_packet();
+    let mut patch = util::load_patch("patch.valid.json");
+    patch.h = Some(packet.h[..8].to_string());
+
+    validate_patch_against_edit_packet_with_diagnostics(&packet, &patch, ValidateOptions::default()).unwrap();
+}
+
+#[test]
+fn truncated_patch_hash_below_minimum_is_rejected() {
+    let packet = util::load_edit_packet();
+    let mut patch = util::load_patch("patch.valid.json");
+    patch.h = Some(packet.h[..7].to_string());
+
+    let err = validate_patch_against_edit_packet_with_diagnostics(&packet, &patch, ValidateOptions::default())
+        .unwrap_err();
+    assert_eq!(err.diagnostics[0].code, DiagnosticCode::HashTooShort);
+}
+
+#[test]
+fn truncated_patch_hash_with_wrong_prefix_is_a_mismatch() {
+    let packet = util::load_edit_packet();
+    let mut patch = util::load_patch("patch.valid.json");
+    patch.h = Some("00000000".to_string());
+
+    let err = validate_patch_against_edit_packet_with_diagnostics(&packet, &patch, ValidateOptions::default())
+        .unwrap_err();
+    assert_eq!(err.diagnostics[0].code, DiagnosticCode::PatchPageHashMismatch);
+}
+
+#[test]
+fn edit_packet_with_truncated_hashes_accepts_full_hash_patch() {
+    let doc: bdir_core::model::Document =
+        serde_json::from_str(&util::read_example_document_json()).expect("document must parse");
+    let packet = from_document_with_options(&doc, None, EditPacketOptions { hash_prefix_len: Some(8) });
+
+    assert_eq!(packet.h, "ff0e1875");
+    assert!(packet.b.iter().all(|b| b.2.len() == 8));
+
+    let patch = util::load_patch("patch.valid.json");
+    validate_patch_against_edit_packet_with_diagnostics(&packet, &patch, ValidateOptions::default()).unwrap();
+}
@@ -0,0 +1,105 @@
+//! HTML fragment output: one element per block, no `<html>`/`<body>` wrapper.
+
+use bdir_core::model::Document;
+
+use crate::{shapes, RenderOptions, Shape};
+
+/// Render `doc` as an HTML fragment.
+pub fn render_html(doc: &Document) -> String {
+    render_shapes(&shapes(doc, &RenderOptions::default()))
+}
+
+pub(crate) fn render_shapes(shapes: &[Shape<'_>]) -> String {
+    let mut out = String::new();
+    for shape in shapes {
+        let block = render_shape(shape);
+        if !block.is_empty() {
+            out.push_str(&block);
+            out.push('\n');
+        }
+    }
+    out
+}
+
+fn render_shape(shape: &Shape<'_>) -> String {
+    match shape {
+        Shape::Heading(level, text) => format!("<h{level}>{}</h{level}>", with_breaks(text)),
+        Shape::Paragraph(text) => format!("<p>{}</p>", with_breaks(text)),
+        Shape::List(items) => list(items),
+        Shape::Table(rows) => table(rows),
+        Shape::Figure(text) => format!("<figure><figcaption>{}</figcaption></figure>", with_breaks(text)),
+        Shape::Code(text) => format!("<pre><code>{}</code></pre>", escape(text)),
+    }
+}
+
+/// Nested `<ul>` from (depth, item) pairs; a depth deeper than one level below the
+/// previous item is treated as exactly one level deeper.
+fn list(items: &[(usize, &str)]) -> String {
+    if items.is_empty() {
+        return String::new();
+    }
+    let mut out = String::from("<ul>\n");
+    let mut depth = 0;
+    for (i, (item_depth, text)) in items.iter().enumerate() {
+        let item_depth = if i == 0 { 0 } else { (*item_depth).min(depth + 1) };
+        if i > 0 {
+            if item_depth > depth {
+                out.push_str("\n<ul>\n");
+            } else {
+                out.push_str("</li>\n");
+                while depth > item_depth {
+                    out.push_str("</ul></li>\n");
+                    depth -= 1;
+                }
+            }
+        }
+        depth = item_depth;
+        out.push_str("<li>");
+        out.push_str(&escape(text));
+    }
+    out.push_str("</li>\n");
+    for _ in 0..depth {
+        out.push_str("</ul></li>\n");
+    }
+    out.push_str("</ul>");
+    out
+}
+
+fn table(rows: &[Vec<&str>]) -> String {
+    let Some((head, body)) = rows.split_first() else {
+        return String::new();
+    };
+    let row = |cells: &[&str], tag: &str| {
+        let cells: String = cells.iter().map(|c| format!("<{tag}>{}</{tag}>", escape(c))).collect();
+        format!("<tr>{cells}</tr>")
+    };
+    let mut out = format!("<table>\n<thead>\n{}\n</thead>\n", row(head, "th"));
+    if !body.is_empty() {
+        out.push_str("<tbody>\n");
+        for cells in body {
+            out.push_str(&row(cells, "td"));
+            out.push('\n');
+        }
+        out.push_str("</tbody>\n");
+    }
+    out.push_str("</table>");
+    out
+}
+
+fn with_breaks(text: &str) -> String {
+    text.lines().map(escape).collect::<Vec<_>>().join("<br>\n")
+}
+
+fn escape(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => out.push_str("&amp;"),
+            '<' => out.push_str("&lt;"),
+            '>' => out.push_str("&gt;"),
+            '"' => out.push_str("&quot;"),
+            c => out.push(c),
+        }
+    }
+    out
+}
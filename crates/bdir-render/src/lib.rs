@@ -0,0 +1,147 @@
+//! Deterministic renderers from a Document to Markdown, HTML and plain text.
+//!
+//! Rendering is driven by `kind_code` (see `bdir_codebook::codes`):
+//!
+//! | kindCode | rendered as |
+//! |---|---|
+//! | 0 (title) | level-1 heading |
+//! | 1 (heading) | heading, level from its `parent` depth |
+//! | 3 (list) | one item per line; two leading spaces per nesting level |
+//! | 4 (table) | one row per line, cells split on ` | `; the first row is the header |
+//! | 5 (figure) | image / figure caption |
+//! | 6 (code) | code block, verbatim |
+//! | anything else | paragraph; line breaks in the text are kept |
+//!
+//! A heading's level is one more than the number of blocks above it in the `parent`
+//! chain; a heading without a parent sits one level below the title when the
+//! Document has one. Levels are capped at 6. This is the text layout the Markdown and
+//! HTML importers produce, so importing a rendered Markdown file yields the same block
+//! texts again.
+
+mod html;
+mod markdown;
+mod text;
+
+use std::collections::HashMap;
+use std::fmt;
+use std::str::FromStr;
+
+use bdir_codebook::{codes, is_core};
+use bdir_core::model::{Block, Document};
+
+pub use html::render_html;
+pub use markdown::render_markdown;
+pub use text::render_text;
+
+/// Output format of `render_with_options`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RenderFormat {
+    Markdown,
+    Html,
+    Text,
+}
+
+impl RenderFormat {
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            RenderFormat::Markdown => "md",
+            RenderFormat::Html => "html",
+            RenderFormat::Text => "txt",
+        }
+    }
+}
+
+impl fmt::Display for RenderFormat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for RenderFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "md" | "markdown" => Ok(RenderFormat::Markdown),
+            "html" => Ok(RenderFormat::Html),
+            "txt" | "text" => Ok(RenderFormat::Text),
+            other => Err(format!("unknown render format '{other}' (expected md, html or txt)")),
+        }
+    }
+}
+
+/// Options for rendering.
+#[derive(Debug, Clone, Default)]
+pub struct RenderOptions {
+    /// Render only core content (kindCodes 0–19), dropping boilerplate, UI chrome and
+    /// unknown blocks.
+    pub core_only: bool,
+}
+
+/// Render `doc` in `format` with explicit options.
+pub fn render_with_options(doc: &Document, format: RenderFormat, opts: &RenderOptions) -> String {
+    let shapes = shapes(doc, opts);
+    match format {
+        RenderFormat::Markdown => markdown::render_shapes(&shapes),
+        RenderFormat::Html => html::render_shapes(&shapes),
+        RenderFormat::Text => text::render_shapes(&shapes),
+    }
+}
+
+/// A block laid out for rendering.
+pub(crate) enum Shape<'a> {
+    Heading(u8, &'a str),
+    Paragraph(&'a str),
+    /// (nesting depth, item text).
+    List(Vec<(usize, &'a str)>),
+    Table(Vec<Vec<&'a str>>),
+    Figure(&'a str),
+    Code(&'a str),
+}
+
+pub(crate) fn shapes<'a>(doc: &'a Document, opts: &RenderOptions) -> Vec<Shape<'a>> {
+    let parents: HashMap<&str, Option<&str>> =
+        doc.blocks.iter().map(|b| (b.id.as_str(), b.parent.as_deref())).collect();
+    let has_title = doc.blocks.iter().any(|b| b.kind_code == codes::TITLE);
+
+    doc.blocks
+        .iter()
+        .filter(|b| !opts.core_only || is_core(b.kind_code))
+        .map(|b| match b.kind_code {
+            codes::TITLE => Shape::Heading(1, &b.text),
+            codes::HEADING => Shape::Heading(heading_level(b, &parents, has_title), &b.text),
+            codes::LIST => Shape::List(
+                b.text
+                    .lines()
+                    .filter(|l| !l.trim().is_empty())
+                    .map(|l| {
+                        let item = l.trim_start_matches(' ');
+                        ((l.len() - item.len()) / 2, item.trim_end())
+                    })
+                    .collect(),
+            ),
+            codes::TABLE => Shape::Table(
+                b.text
+                    .lines()
+                    .filter(|l| !l.trim().is_empty())
+                    .map(|l| l.split(" | ").map(str::trim).collect())
+                    .collect(),
+            ),
+            codes::FIGURE => Shape::Figure(&b.text),
+            codes::CODE => Shape::Code(&b.text),
+            _ => Shape::Paragraph(&b.text),
+        })
+        .collect()
+}
+
+fn heading_level(block: &Block, parents: &HashMap<&str, Option<&str>>, has_title: bool) -> u8 {
+    let mut depth = 0usize;
+    let mut current = block.parent.as_deref();
+    // Bounded walk: a malformed parent cycle cannot loop forever.
+    while let Some(id) = current.filter(|_| depth <= parents.len()) {
+        depth += 1;
+        current = parents.get(id).copied().flatten();
+    }
+    let depth = if depth == 0 && has_title { 1 } else { depth };
+    (depth + 1).min(6) as u8
+}
@@ -0,0 +1,89 @@
+//! CommonMark output (GFM pipe tables).
+//!
+//! Block text is plain text, so Markdown punctuation in it is backslash-escaped; line
+//! breaks inside paragraphs become hard breaks.
+
+use bdir_core::model::Document;
+
+use crate::{shapes, RenderOptions, Shape};
+
+/// Render `doc` as Markdown.
+pub fn render_markdown(doc: &Document) -> String {
+    render_shapes(&shapes(doc, &RenderOptions::default()))
+}
+
+pub(crate) fn render_shapes(shapes: &[Shape<'_>]) -> String {
+    let blocks: Vec<String> = shapes.iter().map(render_shape).filter(|b| !b.is_empty()).collect();
+    if blocks.is_empty() {
+        return String::new();
+    }
+    blocks.join("\n\n") + "\n"
+}
+
+fn render_shape(shape: &Shape<'_>) -> String {
+    match shape {
+        Shape::Heading(level, text) => {
+            format!("{} {}", "#".repeat(usize::from(*level)), escape_line(&text.replace('\n', " ")))
+        }
+        Shape::Paragraph(text) => text.lines().map(escape_line).collect::<Vec<_>>().join("\\\n"),
+        Shape::List(items) => items
+            .iter()
+            .map(|(depth, item)| format!("{}- {}", "  ".repeat(*depth), escape_line(item)))
+            .collect::<Vec<_>>()
+            .join("\n"),
+        Shape::Table(rows) => table(rows),
+        Shape::Figure(text) => format!("![{}]()", escape_inline(&text.replace('\n', " "))),
+        Shape::Code(text) => {
+            let fence = "`".repeat(longest_backtick_run(text).max(2) + 1);
+            format!("{fence}\n{text}\n{fence}")
+        }
+    }
+}
+
+fn table(rows: &[Vec<&str>]) -> String {
+    let columns = rows.iter().map(Vec::len).max().unwrap_or(0);
+    if columns == 0 {
+        return String::new();
+    }
+    let row = |cells: &[&str]| {
+        let cells: Vec<String> = (0..columns)
+            .map(|i| escape_inline(cells.get(i).copied().unwrap_or("")))
+            .collect();
+        format!("| {} |", cells.join(" | "))
+    };
+    let mut lines = vec![row(&rows[0]), format!("|{}", " --- |".repeat(columns))];
+    lines.extend(rows[1..].iter().map(|r| row(r)));
+    lines.join("\n")
+}
+
+/// Escape one line of text so it is not read as block markup either.
+///
+/// The line is trimmed: Markdown drops leading whitespace from paragraph lines anyway,
+/// and four spaces or a tab would start an indented code block. `#`, `>`, `*`, `_` and
+/// backticks are already escaped inline; this adds list, setext and `~~~` fence starts.
+fn escape_line(line: &str) -> String {
+    let mut out = escape_inline(line.trim());
+    let digits = out.len() - out.trim_start_matches(|c: char| c.is_ascii_digit()).len();
+    if out.starts_with(['-', '+', '=', '~']) {
+        out.insert(0, '\\');
+    } else if digits > 0 && out[digits..].starts_with(['.', ')']) {
+        out.insert(digits, '\\');
+    }
+    out
+}
+
+/// Backslash-escape Markdown punctuation (`|` included, for table cells).
+fn escape_inline(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    for c in text.chars() {
+        if matches!(c, '\\' | '`' | '*' | '_' | '[' | ']' | '<' | '>' | '&' | '#' | '|') {
+            out.push('\\');
+        }
+        out.push(c);
+    }
+    out
+}
+
+fn longest_backtick_run(text: &str) -> usize {
+    text.split(|c| c != '`').map(str::len).max().unwrap_or(0)
+}
@@ -0,0 +1,32 @@
+//! Plain text output: block texts separated by blank lines, list items bulleted.
+
+use bdir_core::model::Document;
+
+use crate::{shapes, RenderOptions, Shape};
+
+/// Render `doc` as plain text.
+pub fn render_text(doc: &Document) -> String {
+    render_shapes(&shapes(doc, &RenderOptions::default()))
+}
+
+pub(crate) fn render_shapes(shapes: &[Shape<'_>]) -> String {
+    let blocks: Vec<String> = shapes.iter().map(render_shape).filter(|b| !b.is_empty()).collect();
+    if blocks.is_empty() {
+        return String::new();
+    }
+    blocks.join("\n\n") + "\n"
+}
+
+fn render_shape(shape: &Shape<'_>) -> String {
+    match shape {
+        Shape::Heading(_, text) | Shape::Paragraph(text) | Shape::Figure(text) | Shape::Code(text) => {
+            text.to_string()
+        }
+        Shape::List(items) => items
+            .iter()
+            .map(|(depth, item)| format!("{}- {item}", "  ".repeat(*depth)))
+            .collect::<Vec<_>>()
+            .join("\n"),
+        Shape::Table(rows) => rows.iter().map(|cells| cells.join(" | ")).collect::<Vec<_>>().join("\n"),
+    }
+}
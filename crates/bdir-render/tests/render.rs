@@ -0,0 +1,172 @@
+use std::path::Path;
+
+use bdir_core::model::{Block, Document};
+use bdir_markdown::parse_markdown_str;
+use bdir_render::{render_html, render_markdown, render_text, render_with_options, RenderFormat, RenderOptions};
+
+fn read_markdown_fixture(name: &str) -> String {
+    let path = Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("..")
+        .join("bdir-markdown")
+        .join("tests")
+        .join("fixtures")
+        .join(name);
+    std::fs::read_to_string(&path).unwrap_or_else(|e| panic!("failed to read {}: {e}", path.display()))
+}
+
+fn block(id: &str, kind_code: u16, text: &str, parent: Option<&str>) -> Block {
+    Block {
+        id: id.to_string(),
+        kind_code,
+        text_hash: String::new(),
+        text: text.to_string(),
+        parent: parent.map(str::to_string),
+    }
+}
+
+fn sample() -> Document {
+    let mut doc = Document {
+        page_hash: String::new(),
+        hash_algorithm: "sha256".to_string(),
+        blocks: vec![
+            block("t1", 0, "Release notes", None),
+            block("p1", 2, "Fixes *bold* claims & <tags>.\nSecond line.", Some("t1")),
+            block("h1", 1, "Changes", Some("t1")),
+            block("l1", 3, "Faster apply\nNew flags\n  --section\n2. not a number", Some("h1")),
+            block("h2", 1, "Details", Some("h1")),
+            block("tbl1", 4, "Flag | Effect\n--min | Minified | output", Some("h2")),
+            block("code1", 6, "bdir render doc.json\n```", Some("h2")),
+            block("fig1", 5, "Screenshot", Some("h2")),
+            block("nav1", 21, "Home\nDocs", None),
+        ],
+    };
+    doc.recompute_hashes();
+    doc
+}
+
+#[test]
+fn markdown_output_is_escaped_and_leveled_by_parent_depth() {
+    assert_eq!(
+        render_markdown(&sample()),
+        "# Release notes\n\n\
+         Fixes \\*bold\\* claims \\& \\<tags\\>.\\\nSecond line.\n\n\
+         ## Changes\n\n\
+         - Faster apply\n- New flags\n  - \\--section\n- 2\\. not a number\n\n\
+         ### Details\n\n\
+         | Flag | Effect |  |\n| --- | --- | --- |\n| --min | Minified | output |\n\n\
+         ````\nbdir render doc.json\n```\n````\n\n\
+         ![Screenshot]()\n\n\
+         Home\\\nDocs\n"
+    );
+}
+
+#[test]
+fn rendered_markdown_imports_back_to_the_same_blocks() {
+    let doc = parse_markdown_str(&read_markdown_fixture("guide.md"));
+    let rendered = render_markdown(&doc);
+    let reimported = parse_markdown_str(&rendered);
+
+    let rows = |d: &Document| -> Vec<(String, Option<String>)> {
+        d.blocks.iter().map(|b| (b.text.clone(), b.parent.clone())).collect()
+    };
+    assert_eq!(rows(&reimported), rows(&doc));
+    // Raw HTML is rendered as escaped text, so it comes back as a paragraph.
+    let kinds = |d: &Document| d.blocks.iter().map(|b| b.kind_code).collect::<Vec<_>>();
+    assert_eq!(kinds(&reimported)[..10], kinds(&doc)[..10]);
+    assert_eq!(render_markdown(&reimported), rendered);
+}
+
+#[test]
+fn paragraphs_that_look_like_block_markup_round_trip_as_paragraphs() {
+    let mut doc = Document {
+        page_hash: String::new(),
+        hash_algorithm: "sha256".to_string(),
+        blocks: vec![
+            block("p1", 2, "~~~\nnot code", None),
+            block("p2", 2, "after", None),
+            block("p3", 2, "    indented\tx", None),
+        ],
+    };
+    doc.recompute_hashes();
+
+    let rendered = render_markdown(&doc);
+    assert_eq!(rendered, "\\~~~\\\nnot code\n\nafter\n\nindented\tx\n");
+    let reimported = parse_markdown_str(&rendered);
+    let rows = |d: &Document| -> Vec<(u16, String)> {
+        d.blocks.iter().map(|b| (b.kind_code, b.text.clone())).collect()
+    };
+    // Leading whitespace is not representable in a Markdown paragraph.
+    doc.blocks[2].text = "indented\tx".to_string();
+    assert_eq!(rows(&reimported), rows(&doc));
+}
+
+#[test]
+fn html_output_nests_lists_and_escapes_text() {
+    assert_eq!(
+        render_html(&sample()),
+        "<h1>Release notes</h1>\n\
+         <p>Fixes *bold* claims &amp; &lt;tags&gt;.<br>\nSecond line.</p>\n\
+         <h2>Changes</h2>\n\
+         <ul>\n<li>Faster apply</li>\n<li>New flags\n<ul>\n<li>--section</li>\n</ul></li>\n<li>2. not a number</li>\n</ul>\n\
+         <h3>Details</h3>\n\
+         <table>\n<thead>\n<tr><th>Flag</th><th>Effect</th></tr>\n</thead>\n\
+         <tbody>\n<tr><td>--min</td><td>Minified</td><td>output</td></tr>\n</tbody>\n</table>\n\
+         <pre><code>bdir render doc.json\n```</code></pre>\n\
+         <figure><figcaption>Screenshot</figcaption></figure>\n\
+         <p>Home<br>\nDocs</p>\n"
+    );
+}
+
+#[test]
+fn text_output_keeps_block_text_with_bulleted_lists() {
+    assert_eq!(
+        render_text(&sample()),
+        "Release notes\n\n\
+         Fixes *bold* claims & <tags>.\nSecond line.\n\n\
+         Changes\n\n\
+         - Faster apply\n- New flags\n  - --section\n- 2. not a number\n\n\
+         Details\n\n\
+         Flag | Effect\n--min | Minified | output\n\n\
+         bdir render doc.json\n```\n\n\
+         Screenshot\n\n\
+         Home\nDocs\n"
+    );
+}
+
+#[test]
+fn core_only_drops_chrome_and_flat_headings_sit_below_the_title() {
+    let mut doc = sample();
+    for b in &mut doc.blocks {
+        b.parent = None;
+    }
+    let opts = RenderOptions { core_only: true };
+
+    let text = render_with_options(&doc, RenderFormat::Markdown, &opts);
+    assert!(text.starts_with("# Release notes\n"));
+    assert!(text.contains("\n## Changes\n") && text.contains("\n## Details\n"));
+    assert!(!text.contains("Home"));
+
+    doc.blocks.remove(0);
+    let html = render_with_options(&doc, RenderFormat::Html, &opts);
+    assert!(html.contains("<h1>Changes</h1>"));
+}
+
+#[test]
+fn formats_parse_from_cli_names() {
+    assert_eq!("md".parse::<RenderFormat>(), Ok(RenderFormat::Markdown));
+    assert_eq!("html".parse::<RenderFormat>(), Ok(RenderFormat::Html));
+    assert_eq!("txt".parse::<RenderFormat>(), Ok(RenderFormat::Text));
+    assert_eq!(
+        "pdf".parse::<RenderFormat>(),
+        Err("unknown render format 'pdf' (expected md, html or txt)".to_string())
+    );
+    assert_eq!(RenderFormat::Text.to_string(), "txt");
+}
+
+#[test]
+fn rendering_is_deterministic_across_importers() {
+    let html = bdir_html::parse_html_str("<main><h1>A</h1><h2>B</h2><p>C &amp; D</p></main><footer>F</footer>");
+    let opts = RenderOptions { core_only: true };
+    assert_eq!(render_with_options(&html, RenderFormat::Markdown, &opts), "# A\n\n## B\n\nC \\& D\n");
+    assert_eq!(render_with_options(&html, RenderFormat::Text, &RenderOptions::default()), "A\n\nB\n\nC & D\n\nF\n");
+}